@@ -3,59 +3,46 @@ import type {
   AiChatRequest,
   AiChatResponse, 
   ConnectionTestResponse,
+  PdfSummarizationRequest,
   PdfSummarizationResponse,
 } from '../types';
 
 export interface LLMClientOptions {
-  apiKey: string;
-  baseUrl?: string;
-  model?: string;
-  maxTokens?: number;
-  temperature?: number;
-  timeout?: number;
+  /**
+   * Name of the provider profile to use; the backend falls back to the
+   * active profile. Keys and endpoints stay on the backend.
+   */
+  profile?: string;
 }
 
 export class LLMClient {
   private options: LLMClientOptions;
 
-  constructor(options: LLMClientOptions) {
+  constructor(options: LLMClientOptions = {}) {
     this.options = options;
   }
 
   async testConnection(): Promise<ConnectionTestResponse> {
-    return invoke('test_ai_connection', {
-      apiKey: this.options.apiKey,
-      baseUrl: this.options.baseUrl,
-      model: this.options.model,
-      timeout: this.options.timeout,
-    });
+    return invoke('test_ai_connection', { profile: this.options.profile });
   }
 
   async chat(prompt: string): Promise<AiChatResponse> {
     const request: AiChatRequest = {
       prompt,
-      apiKey: this.options.apiKey,
-      baseUrl: this.options.baseUrl,
-      model: this.options.model,
-      maxTokens: this.options.maxTokens,
-      temperature: this.options.temperature,
-      timeout: this.options.timeout,
+      profile: this.options.profile,
     };
 
     return invoke('process_ai_chat', { chatRequest: request });
   }
 
-  async summarizePdf(filePath: string, prompt: string): Promise<PdfSummarizationResponse> {
-    return invoke('process_pdf_summarization', {
+  async summarizePdf(filePath: string, prompt?: string): Promise<PdfSummarizationResponse> {
+    const request: PdfSummarizationRequest = {
       filePath,
       prompt,
-      apiKey: this.options.apiKey,
-      baseUrl: this.options.baseUrl,
-      model: this.options.model,
-      maxTokens: this.options.maxTokens,
-      temperature: this.options.temperature,
-      timeout: this.options.timeout,
-    });
+      profile: this.options.profile,
+    };
+
+    return invoke('process_pdf_summarization', { summarizationRequest: request });
   }
 
   updateOptions(newOptions: Partial<LLMClientOptions>): void {
@@ -6,14 +6,7 @@ export function createLLMClient(options: LLMClientOptions): LLMClient {
 }
 
 export function createLLMClientFromSettings(settings: AISettings): LLMClient {
-  return new LLMClient({
-    apiKey: settings.apiKey,
-    baseUrl: settings.baseUrl,
-    model: settings.model,
-    maxTokens: settings.maxTokens,
-    temperature: settings.temperature,
-    timeout: settings.timeout,
-  });
+  return new LLMClient({ profile: settings.profile });
 }
 
 export function detectProviderFromBaseUrl(baseUrl: string): string {
@@ -1,5 +1,9 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { PdfAnalysisResponse } from '../types';
+import type {
+  PdfAnalysisResponse,
+  PdfSummarizationRequest,
+  PdfSummarizationResponse,
+} from '../types';
 
 export interface PdfTextExtractionResponse {
   content: string | null;
@@ -18,24 +22,11 @@ export class PDFClient {
 
   async summarizePdf(
     filePath: string,
-    prompt: string,
-    apiKey: string,
-    baseUrl?: string,
-    model?: string,
-    maxTokens?: number,
-    temperature?: number,
-    timeout?: number
-  ): Promise<any> {
-    return invoke('process_pdf_summarization', {
-      filePath,
-      prompt,
-      apiKey,
-      baseUrl,
-      model,
-      maxTokens,
-      temperature,
-      timeout,
-    });
+    prompt?: string,
+    profile?: string
+  ): Promise<PdfSummarizationResponse> {
+    const request: PdfSummarizationRequest = { filePath, prompt, profile };
+    return invoke('process_pdf_summarization', { summarizationRequest: request });
   }
 
   formatFileSize(bytes: number): string {
@@ -1,70 +1,127 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AISettings } from '../types';
+import type {
+  AISettings,
+  AppSettings,
+  ConnectionTestResponse,
+  ProviderProfile,
+  VaultStatus,
+} from '../types';
 import { DEFAULT_AI_SETTINGS } from '../types';
 
-const SETTINGS_KEY = 'ai_settings';
+// Where earlier versions kept the settings, API key included, in plain text.
+// After migration only the key is left, under LEGACY_API_KEY, until the
+// vault passphrase is entered to move it.
+const LEGACY_SETTINGS_KEY = 'ai_settings';
+const LEGACY_API_KEY = 'openai_api_key';
+
+function toAISettings(settings: AppSettings): AISettings {
+  const profile =
+    settings.profiles.find(p => p.name === settings.activeProfile) ?? settings.profiles[0];
+  if (!profile) {
+    return { ...DEFAULT_AI_SETTINGS, prompt: settings.summaryPrompt };
+  }
 
-export function saveAISettings(settings: AISettings): void {
+  return {
+    profile: profile.name,
+    apiKey: '',
+    apiKeyName: profile.apiKeyName ?? '',
+    vaultPassphrase: '',
+    baseUrl: profile.baseUrl,
+    model: profile.model,
+    prompt: settings.summaryPrompt,
+    maxTokens: profile.maxTokens,
+    temperature: profile.temperature,
+    timeout: profile.timeout,
+  };
+}
+
+function loadLegacyApiKey(): string {
   try {
-    localStorage.setItem(SETTINGS_KEY, JSON.stringify(settings));
-    console.log('AI settings saved successfully');
+    const saved = localStorage.getItem(LEGACY_SETTINGS_KEY);
+    const apiKey = saved ? (JSON.parse(saved) as Partial<{ apiKey: string }>).apiKey : undefined;
+    if (apiKey) {
+      return apiKey;
+    }
+    const oldApiKey = localStorage.getItem(LEGACY_API_KEY);
+    if (oldApiKey && oldApiKey !== 'your-api-key-here') {
+      return oldApiKey;
+    }
   } catch (error) {
-    console.error('Failed to save AI settings:', error);
-    throw new Error('Failed to save settings');
+    console.error('Failed to read legacy AI settings:', error);
   }
+  return '';
 }
 
-export function loadAISettings(): AISettings {
-  try {
-    const saved = localStorage.getItem(SETTINGS_KEY);
-    if (saved) {
-      const parsed = JSON.parse(saved) as Partial<AISettings>;
-      // Merge with defaults to ensure all fields exist
-      return {
-        ...DEFAULT_AI_SETTINGS,
-        ...parsed
-      };
+/** Loads the active profile from the backend. */
+export async function loadAISettings(): Promise<AISettings> {
+  return toAISettings(await invoke<AppSettings>('get_ai_settings'));
+}
+
+/**
+ * Saves the form as the active profile. A newly entered API key goes to
+ * the vault under the profile's key name, unlocking the vault first.
+ */
+export async function saveAISettings(
+  settings: AISettings,
+  originalName?: string
+): Promise<AISettings> {
+  const apiKey = settings.apiKey.trim();
+  const apiKeyName = settings.apiKeyName.trim() || (apiKey ? settings.profile.trim().toLowerCase() : '');
+
+  if (apiKey) {
+    const status = await invoke<VaultStatus>('get_vault_status');
+    if (!status.unlocked) {
+      if (!settings.vaultPassphrase) {
+        throw new Error('Enter the vault passphrase to store the API key');
+      }
+      await invoke<VaultStatus>('unlock_vault', { passphrase: settings.vaultPassphrase });
     }
-  } catch (error) {
-    console.error('Failed to load AI settings:', error);
+    await invoke('set_api_key', { name: apiKeyName, value: apiKey });
+    clearAISettings();
   }
-  
-  return DEFAULT_AI_SETTINGS;
+
+  const profile: ProviderProfile = {
+    name: settings.profile.trim(),
+    baseUrl: settings.baseUrl.trim(),
+    model: settings.model.trim(),
+    apiKeyName: apiKeyName || null,
+    maxTokens: settings.maxTokens,
+    temperature: settings.temperature,
+    timeout: settings.timeout,
+  };
+  await invoke<AppSettings>('save_ai_profile', { profile, originalName });
+  await invoke<AppSettings>('set_active_ai_profile', { name: profile.name });
+  const saved = await invoke<AppSettings>('set_summary_prompt', { prompt: settings.prompt });
+  return toAISettings(saved);
 }
 
+/** Removes the settings earlier versions left in localStorage. */
 export function clearAISettings(): void {
   try {
-    localStorage.removeItem(SETTINGS_KEY);
-    console.log('AI settings cleared');
+    localStorage.removeItem(LEGACY_SETTINGS_KEY);
+    localStorage.removeItem(LEGACY_API_KEY);
   } catch (error) {
-    console.error('Failed to clear AI settings:', error);
+    console.error('Failed to clear legacy AI settings:', error);
   }
 }
 
-export async function testAIConnection(settings: AISettings): Promise<{ success: boolean; error?: string }> {
+/** Tests a saved profile; the backend reads its key from the vault. */
+export async function testAIConnection(settings: Pick<AISettings, 'profile'>): Promise<{ success: boolean; error?: string }> {
   try {
-    // Use the dedicated connection test command
-    const response = await invoke('test_ai_connection', {
-      apiKey: settings.apiKey,
-      baseUrl: settings.baseUrl,
-      model: settings.model,
-      timeout: settings.timeout
+    const response = await invoke<ConnectionTestResponse>('test_ai_connection', {
+      profile: settings.profile,
     });
-
-    if (response && typeof response === 'object') {
-      const res = response as any;
-      return {
-        success: res.success,
-        error: res.error
-      };
-    }
-
-    return { success: true };
+    return {
+      success: response.success,
+      error: response.error ?? undefined,
+    };
   } catch (error) {
     console.error('AI connection test failed:', error);
-    return { 
-      success: false, 
-      error: error instanceof Error ? error.message : 'Connection test failed' 
+    return {
+      success: false,
+      error: typeof error === 'string'
+        ? error
+        : error instanceof Error ? error.message : 'Connection test failed'
     };
   }
 }
@@ -73,8 +130,8 @@ export async function testAIConnection(settings: AISettings): Promise<{ success:
 export function validateAISettings(settings: Partial<AISettings>): { isValid: boolean; errors: string[] } {
   const errors: string[] = [];
 
-  if (!settings.apiKey || settings.apiKey.trim() === '') {
-    errors.push('API key is required');
+  if (!settings.profile || settings.profile.trim() === '') {
+    errors.push('Profile name is required');
   }
 
   if (!settings.baseUrl || settings.baseUrl.trim() === '') {
@@ -113,23 +170,43 @@ export function validateAISettings(settings: Partial<AISettings>): { isValid: bo
   };
 }
 
-// Migration function for existing localStorage keys
-export function migrateOldSettings(): void {
+/**
+ * Copies the non-secret parts of settings saved by earlier versions into
+ * the backend's active profile. Their API key needs the vault passphrase
+ * to reach the vault, so it is set aside until `moveLegacyApiKey` stores
+ * it or `clearAISettings` discards it. Returns whether a key is waiting.
+ */
+export async function migrateOldSettings(): Promise<boolean> {
   try {
-    // Check for old openai_api_key
-    const oldApiKey = localStorage.getItem('openai_api_key');
-    if (oldApiKey && oldApiKey !== 'your-api-key-here') {
-      const currentSettings = loadAISettings();
-      if (!currentSettings.apiKey) {
-        const updatedSettings = {
-          ...currentSettings,
-          apiKey: oldApiKey
-        };
-        saveAISettings(updatedSettings);
-        console.log('Migrated old API key to new settings format');
+    const saved = localStorage.getItem(LEGACY_SETTINGS_KEY);
+    if (saved) {
+      const legacy = JSON.parse(saved) as Partial<AISettings>;
+      const current = toAISettings(await invoke<AppSettings>('get_ai_settings'));
+      await saveAISettings(
+        { ...current, ...legacy, profile: current.profile, apiKeyName: current.apiKeyName, apiKey: '', vaultPassphrase: '' },
+        current.profile
+      );
+      if (legacy.apiKey) {
+        localStorage.setItem(LEGACY_API_KEY, legacy.apiKey);
       }
+      localStorage.removeItem(LEGACY_SETTINGS_KEY);
+      console.log('Migrated AI settings to the backend');
     }
   } catch (error) {
     console.error('Failed to migrate old settings:', error);
   }
-}
\ No newline at end of file
+  return loadLegacyApiKey() !== '';
+}
+
+/**
+ * Stores the API key an earlier version left in localStorage in the vault
+ * for the active profile, then removes it from localStorage.
+ */
+export async function moveLegacyApiKey(vaultPassphrase: string): Promise<void> {
+  const apiKey = loadLegacyApiKey();
+  if (!apiKey) {
+    return;
+  }
+  const current = await loadAISettings();
+  await saveAISettings({ ...current, apiKey, vaultPassphrase }, current.profile);
+}
@@ -1,8 +1,13 @@
 export interface PdfSummarizationRequest {
     filePath: string;
-    prompt: string;
-    apiKey: string;
-    baseUrl?: string;
+    /** Defaults to the summary prompt from settings. */
+    prompt?: string;
+    /** Defaults to the active profile. */
+    profile?: string;
+    template?: string;
+    variables?: Record<string, string>;
+    password?: string;
+    pages?: string;
 }
 
 export interface PdfSummarizationResponse {
@@ -18,18 +23,24 @@ export interface ProcessingStatus {
 
 export interface AiChatRequest {
     prompt: string;
-    apiKey: string;
-    baseUrl?: string;
-    model?: string;
-    maxTokens?: number;
-    temperature?: number;
-    timeout?: number;
+    template?: string;
+    variables?: Record<string, string>;
+    sessionId?: string;
+    /** Defaults to the active profile. */
+    profile?: string;
+}
+
+export interface ReasoningResponse {
+    reasoning?: string | null;
+    output: string;
 }
 
 export interface AiChatResponse {
-    response: string;
+    response: ReasoningResponse | null;
     success: boolean;
     error?: string;
+    /** Set when the reply succeeded but was not saved to its chat session. */
+    warning?: string;
 }
 
 export interface ChatMessage {
@@ -1,5 +1,40 @@
+/** A provider profile as the backend stores it in settings.json. */
+export interface ProviderProfile {
+  name: string;
+  baseUrl: string;
+  model: string;
+  /** Name of the API key in the secret vault; local servers need none. */
+  apiKeyName?: string | null;
+  maxTokens: number;
+  temperature: number;
+  timeout: number;
+  embeddingModel?: string | null;
+}
+
+export interface AppSettings {
+  version: number;
+  activeProfile: string;
+  profiles: ProviderProfile[];
+  summaryPrompt: string;
+  maxPdfSizeBytes: number;
+  maxPdfOpenBytes: number;
+}
+
+export interface VaultStatus {
+  exists: boolean;
+  unlocked: boolean;
+}
+
+/**
+ * The settings form: the active profile plus the summary prompt. `apiKey`
+ * is only filled in to store a new key in the vault and is never read back.
+ */
 export interface AISettings {
+  profile: string;
   apiKey: string;
+  apiKeyName: string;
+  /** Unlocks (or creates) the vault when a new key is saved. */
+  vaultPassphrase: string;
   baseUrl: string;
   model: string;
   prompt: string;
@@ -9,7 +44,10 @@ export interface AISettings {
 }
 
 export const DEFAULT_AI_SETTINGS: AISettings = {
+  profile: 'OpenAI',
   apiKey: '',
+  apiKeyName: 'openai',
+  vaultPassphrase: '',
   baseUrl: 'https://api.openai.com/v1',
   model: 'gpt-3.5-turbo',
   prompt: 'Please provide a concise summary of this PDF document, highlighting the main points and key insights.',
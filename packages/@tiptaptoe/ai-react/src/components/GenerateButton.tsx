@@ -2,6 +2,7 @@ import React, { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { 
+  PdfSummarizationRequest,
   PdfSummarizationResponse, 
   ProcessingStatus, 
   FileContext,
@@ -27,7 +28,7 @@ export const GenerateButton: React.FC<GenerateButtonProps> = ({ onSummaryGenerat
     
     try {
       // Step 1: Load and validate AI settings
-      const settings = loadAISettings();
+      const settings = await loadAISettings();
       const validation = validateAISettings(settings);
       
       if (!validation.isValid) {
@@ -58,14 +59,7 @@ export const GenerateButton: React.FC<GenerateButtonProps> = ({ onSummaryGenerat
         message: 'Processing PDF file...'
       });
 
-      console.log("Using AI settings:", {
-        baseUrl: settings.baseUrl,
-        model: settings.model,
-        hasApiKey: !!settings.apiKey,
-        maxTokens: settings.maxTokens,
-        temperature: settings.temperature,
-        timeout: settings.timeout
-      });
+      console.log("Using AI profile:", settings.profile);
 
       setStatus({
         isProcessing: true,
@@ -75,15 +69,13 @@ export const GenerateButton: React.FC<GenerateButtonProps> = ({ onSummaryGenerat
       // Step 3: Call Tauri command with configured settings
       console.log("Calling Tauri command with configured settings");
 
-      const response = await invoke<PdfSummarizationResponse>('process_pdf_summarization', {
+      const summarizationRequest: PdfSummarizationRequest = {
         filePath: selectedFile,
         prompt: settings.prompt,
-        apiKey: settings.apiKey,
-        baseUrl: settings.baseUrl,
-        model: settings.model,
-        maxTokens: settings.maxTokens,
-        temperature: settings.temperature,
-        timeout: settings.timeout
+        profile: settings.profile
+      };
+      const response = await invoke<PdfSummarizationResponse>('process_pdf_summarization', {
+        summarizationRequest
       });
 
       console.log("Received response from Tauri:", response);
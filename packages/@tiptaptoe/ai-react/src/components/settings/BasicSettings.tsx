@@ -19,14 +19,38 @@ export const BasicSettings: React.FC<BasicSettingsProps> = ({
   return (
     <div style={{ display: 'flex', flexDirection: 'column', gap: '20px' }}>
       <Input
-        label="API Key *"
+        label="Profile *"
+        value={settings.profile}
+        onChange={(e) => onSettingsChange('profile', e.target.value)}
+        placeholder="Enter a profile name"
+      />
+
+      <Input
+        label="API Key"
         type="password"
         value={settings.apiKey}
         onChange={(e) => onSettingsChange('apiKey', e.target.value)}
-        placeholder="Enter your API key"
-        helperText="Your API key will be stored locally and never shared"
+        placeholder={settings.apiKeyName ? 'Leave empty to keep the stored key' : 'Enter your API key'}
+        helperText="Stored in the encrypted vault, never in the settings file"
       />
 
+      <Input
+        label="Key Name"
+        value={settings.apiKeyName}
+        onChange={(e) => onSettingsChange('apiKeyName', e.target.value)}
+        placeholder="Name of the key in the vault"
+      />
+
+      {settings.apiKey.trim() && (
+        <Input
+          label="Vault Passphrase"
+          type="password"
+          value={settings.vaultPassphrase}
+          onChange={(e) => onSettingsChange('vaultPassphrase', e.target.value)}
+          placeholder="Unlocks the vault, or creates it on first use"
+        />
+      )}
+
       <div>
         <Select
           label="Base URL"
@@ -0,0 +1,74 @@
+import React, { useState } from 'react';
+import { clearAISettings, moveLegacyApiKey } from '@tiptaptoe/ai-core';
+import { Alert, Button, Input, Modal } from '../../ui';
+
+interface LegacyKeyPromptProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+/**
+ * Asks for the vault passphrase to move an API key that an earlier version
+ * left in localStorage into the vault, or discards the key.
+ */
+export const LegacyKeyPrompt: React.FC<LegacyKeyPromptProps> = ({ isOpen, onClose }) => {
+  const [passphrase, setPassphrase] = useState('');
+  const [isMoving, setIsMoving] = useState(false);
+  const [error, setError] = useState('');
+
+  const handleMove = async () => {
+    setIsMoving(true);
+    setError('');
+    try {
+      await moveLegacyApiKey(passphrase);
+      setPassphrase('');
+      onClose();
+    } catch (err) {
+      setError(typeof err === 'string' ? err : err instanceof Error ? err.message : 'Failed to store the API key');
+    } finally {
+      setIsMoving(false);
+    }
+  };
+
+  const handleDiscard = () => {
+    clearAISettings();
+    setPassphrase('');
+    onClose();
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="Move API Key to the Vault" maxWidth="480px">
+      <div style={{ display: 'flex', flexDirection: 'column', gap: '20px' }}>
+        <p style={{ margin: 0, fontSize: '14px', color: '#374151' }}>
+          An earlier version saved your API key unencrypted. Enter the vault passphrase to
+          move it into the encrypted vault; a new vault is created with this passphrase if
+          there is none yet.
+        </p>
+
+        <Input
+          label="Vault Passphrase"
+          type="password"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          placeholder="Unlocks the vault, or creates it on first use"
+        />
+
+        {error && <Alert variant="error">{error}</Alert>}
+
+        <div style={{ display: 'flex', gap: '12px', justifyContent: 'flex-end' }}>
+          <Button variant="danger" onClick={handleDiscard} disabled={isMoving}>
+            Discard Key
+          </Button>
+          <Button
+            variant="primary"
+            onClick={handleMove}
+            disabled={isMoving || !passphrase}
+            isLoading={isMoving}
+          >
+            Move to Vault
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+};
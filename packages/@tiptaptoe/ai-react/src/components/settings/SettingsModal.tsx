@@ -6,6 +6,9 @@ import { AdvancedSettings } from './AdvancedSettings';
 import { ConnectionStatus } from './ConnectionStatus';
 import { SettingsActions } from './SettingsActions';
 
+// Local OpenAI-compatible servers accept requests without a key.
+const isLocalUrl = (url: string) => url.includes('localhost') || url.includes('127.0.0.1');
+
 interface SettingsModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -102,7 +105,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ isOpen, onClose })
           onSave={handleSave}
           isTestingConnection={isLoading}
           isSaving={isSaving}
-          hasApiKey={!!settings.apiKey.trim()}
+          hasApiKey={!!settings.apiKey.trim() || !!settings.apiKeyName.trim() || isLocalUrl(settings.baseUrl)}
         />
       </div>
     </Modal>
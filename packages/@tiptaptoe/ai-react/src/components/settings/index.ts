@@ -2,4 +2,5 @@ export * from './SettingsModal';
 export * from './BasicSettings';
 export * from './AdvancedSettings';
 export * from './ConnectionStatus';
-export * from './SettingsActions';
\ No newline at end of file
+export * from './SettingsActions';
+export * from './LegacyKeyPrompt';
\ No newline at end of file
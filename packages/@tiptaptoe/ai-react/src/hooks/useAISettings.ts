@@ -12,13 +12,25 @@ export const useAISettings = () => {
   const [settings, setSettings] = useState<AISettings>(DEFAULT_AI_SETTINGS);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  // The profile as the backend knows it, so a renamed profile is replaced.
+  const [savedProfile, setSavedProfile] = useState<string | undefined>(undefined);
 
   // Load settings on mount
   useEffect(() => {
-    const loadedSettings = loadAISettings();
-    setSettings(loadedSettings);
+    loadAISettings()
+      .then(loadedSettings => {
+        setSettings(loadedSettings);
+        setSavedProfile(loadedSettings.profile);
+      })
+      .catch(err => setError(err instanceof Error ? err.message : String(err)));
   }, []);
 
+  const persist = async () => {
+    const saved = await saveAISettings(settings, savedProfile);
+    setSettings(saved);
+    setSavedProfile(saved.profile);
+  };
+
   const updateSettings = (newSettings: Partial<AISettings>) => {
     setSettings(prev => ({ ...prev, ...newSettings }));
     setError(null);
@@ -40,10 +52,10 @@ export const useAISettings = () => {
         return false;
       }
 
-      saveAISettings(settings);
+      await persist();
       return true;
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to save settings');
+      setError(typeof err === 'string' ? err : err instanceof Error ? err.message : 'Failed to save settings');
       return false;
     } finally {
       setIsLoading(false);
@@ -62,13 +74,15 @@ export const useAISettings = () => {
         return { success: false, error: errorMsg };
       }
 
+      // The backend tests saved profiles, so the form is saved first.
+      await persist();
       const result = await testAIConnection(settings);
       if (!result.success) {
         setError(result.error || 'Connection test failed');
       }
       return result;
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : 'Connection test failed';
+      const errorMsg = typeof err === 'string' ? err : err instanceof Error ? err.message : 'Connection test failed';
       setError(errorMsg);
       return { success: false, error: errorMsg };
     } finally {
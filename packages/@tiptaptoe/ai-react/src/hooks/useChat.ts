@@ -70,7 +70,7 @@ export const useChat = ({ selectedText, onApplyResponse, initialFiles = [] }: Us
     const messageToSend = message || inputValue;
     if (!messageToSend.trim() || isLoading) return;
 
-    const settings = loadAISettings();
+    const settings = await loadAISettings();
     const validation = validateAISettings(settings);
 
     if (!validation.isValid) {
@@ -118,12 +118,7 @@ Please respond helpfully about the selected text and any provided file context.
       const response = await invoke<any>('process_ai_chat', {
         chatRequest: {
           prompt: conversationContext,
-          apiKey: settings.apiKey,
-          baseUrl: settings.baseUrl,
-          model: settings.model,
-          maxTokens: settings.maxTokens,
-          temperature: settings.temperature,
-          timeout: settings.timeout
+          profile: settings.profile
         }
       });
 
@@ -0,0 +1,48 @@
+// src-tauri/src/app_dirs.rs
+use crate::errors::AppError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+/// Returns `<app data dir>/<name>`, creating it if needed.
+pub fn app_data_subdir(app: &AppHandle, name: &str) -> Result<PathBuf, AppError> {
+    let base = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?;
+    let dir = base.join(name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Returns `<app data dir>/<file_name>`, creating the data dir if needed.
+pub fn app_data_file(app: &AppHandle, file_name: &str) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(file_name))
+}
+
+/// Returns `<app config dir>/<file_name>`, creating the config dir if needed.
+pub fn app_config_file(app: &AppHandle, file_name: &str) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(file_name))
+}
+
+/// The lock for load-modify-save cycles on one file. Stores are created per
+/// command, so the locks are shared by the whole process.
+pub fn file_lock(path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    locks.entry(path.to_path_buf()).or_default().clone()
+}
@@ -0,0 +1,14 @@
+// src-tauri/src/chat_history/mod.rs
+pub mod models;
+pub mod store;
+
+use crate::app_dirs::app_data_subdir;
+use crate::errors::AppError;
+use store::ChatSessionStore;
+use tauri::AppHandle;
+
+const CHAT_SESSION_DIR: &str = "chat_sessions";
+
+pub fn session_store(app: &AppHandle) -> Result<ChatSessionStore, AppError> {
+    Ok(ChatSessionStore::new(app_data_subdir(app, CHAT_SESSION_DIR)?))
+}
@@ -0,0 +1,70 @@
+// src-tauri/src/chat_history/models.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredChatMessage {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    pub reasoning: Option<String>,
+    pub model: Option<String>,
+    pub provider: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatSession {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+    pub messages: Vec<StoredChatMessage>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChatSessionSummary {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(rename = "messageCount")]
+    pub message_count: usize,
+    /// Model and provider of the most recent assistant reply.
+    pub model: Option<String>,
+    pub provider: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChatSearchMatch {
+    #[serde(rename = "messageId")]
+    pub message_id: Option<String>,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChatSearchResult {
+    pub session: ChatSessionSummary,
+    pub matches: Vec<ChatSearchMatch>,
+}
+
+impl ChatSession {
+    pub fn summary(&self) -> ChatSessionSummary {
+        let last_reply = self.messages.iter().rev().find(|m| m.role == "assistant");
+
+        ChatSessionSummary {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            message_count: self.messages.len(),
+            model: last_reply.and_then(|m| m.model.clone()),
+            provider: last_reply.and_then(|m| m.provider.clone()),
+        }
+    }
+}
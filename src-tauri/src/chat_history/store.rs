@@ -0,0 +1,299 @@
+// src-tauri/src/chat_history/store.rs
+use super::models::{
+    ChatSearchMatch, ChatSearchResult, ChatSession, ChatSessionSummary, StoredChatMessage,
+};
+use crate::app_dirs::file_lock;
+use crate::errors::AppError;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+pub const DEFAULT_SESSION_TITLE: &str = "New chat";
+const TITLE_MAX_CHARS: usize = 60;
+const SNIPPET_RADIUS: usize = 60;
+
+/// One JSON file per session under the given directory.
+pub struct ChatSessionStore {
+    dir: PathBuf,
+}
+
+impl ChatSessionStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn create(&self, title: Option<String>) -> Result<ChatSession, AppError> {
+        let now = Utc::now();
+        let session = ChatSession {
+            id: Uuid::new_v4().to_string(),
+            title: title
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| DEFAULT_SESSION_TITLE.to_string()),
+            created_at: now,
+            updated_at: now,
+            messages: Vec::new(),
+        };
+        self.save(&session)?;
+        Ok(session)
+    }
+
+    /// Lists sessions, most recently updated first. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<ChatSessionSummary>, AppError> {
+        let mut summaries: Vec<ChatSessionSummary> =
+            self.load_all()?.iter().map(ChatSession::summary).collect();
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+        Ok(summaries)
+    }
+
+    pub fn load(&self, id: &str) -> Result<ChatSession, AppError> {
+        let path = self.session_path(id)?;
+        if !path.exists() {
+            return Err(AppError::ChatHistoryError(format!(
+                "Chat session '{}' not found",
+                id
+            )));
+        }
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn rename(&self, id: &str, title: &str) -> Result<ChatSession, AppError> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(AppError::ChatHistoryError(
+                "Session title cannot be empty".to_string(),
+            ));
+        }
+        let lock = file_lock(&self.session_path(id)?);
+        let _guard = hold(&lock)?;
+        let mut session = self.load(id)?;
+        session.title = title.to_string();
+        session.updated_at = Utc::now();
+        self.save(&session)?;
+        Ok(session)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        let path = self.session_path(id)?;
+        let lock = file_lock(&path);
+        let _guard = hold(&lock)?;
+        if !path.exists() {
+            return Err(AppError::ChatHistoryError(format!(
+                "Chat session '{}' not found",
+                id
+            )));
+        }
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Appends messages to a session. An untitled session takes its title
+    /// from the first user message.
+    pub fn append(
+        &self,
+        id: &str,
+        messages: Vec<StoredChatMessage>,
+    ) -> Result<ChatSession, AppError> {
+        // Two replies finishing together must not both read the old file
+        // and have the later write drop the earlier one's messages.
+        let lock = file_lock(&self.session_path(id)?);
+        let _guard = hold(&lock)?;
+        let mut session = self.load(id)?;
+
+        if session.title == DEFAULT_SESSION_TITLE && session.messages.is_empty() {
+            if let Some(first) = messages.iter().find(|m| m.role == "user") {
+                session.title = title_from(&first.content);
+            }
+        }
+
+        session.messages.extend(messages);
+        session.updated_at = Utc::now();
+        self.save(&session)?;
+        Ok(session)
+    }
+
+    /// Case-insensitive search over titles and message contents. Every
+    /// whitespace-separated term must occur somewhere in the session.
+    pub fn search(&self, query: &str) -> Result<Vec<ChatSearchResult>, AppError> {
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results = Vec::new();
+        for session in self.load_all()? {
+            let title = session.title.to_lowercase();
+            let contents: Vec<String> = session
+                .messages
+                .iter()
+                .map(|m| m.content.to_lowercase())
+                .collect();
+
+            let all_terms_found = terms
+                .iter()
+                .all(|term| title.contains(term) || contents.iter().any(|c| c.contains(term)));
+            if !all_terms_found {
+                continue;
+            }
+
+            let mut matches = Vec::new();
+            if terms.iter().any(|term| title.contains(term)) {
+                matches.push(ChatSearchMatch {
+                    message_id: None,
+                    snippet: session.title.clone(),
+                });
+            }
+            for (message, content) in session.messages.iter().zip(&contents) {
+                if let Some(position) = terms.iter().filter_map(|term| content.find(term)).min() {
+                    matches.push(ChatSearchMatch {
+                        message_id: Some(message.id.clone()),
+                        snippet: snippet_around(&message.content, content, position),
+                    });
+                }
+            }
+
+            results.push(ChatSearchResult {
+                session: session.summary(),
+                matches,
+            });
+        }
+
+        results.sort_by_key(|r| std::cmp::Reverse(r.session.updated_at));
+        Ok(results)
+    }
+
+    fn save(&self, session: &ChatSession) -> Result<(), AppError> {
+        let path = self.session_path(&session.id)?;
+        let data = serde_json::to_string_pretty(session)?;
+        // Written aside and renamed so readers never see a partial file.
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<Vec<ChatSession>, AppError> {
+        let mut sessions = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match read_session(&path) {
+                Ok(session) => sessions.push(session),
+                Err(e) => eprintln!(
+                    "Warning: Skipping unreadable chat session {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        Ok(sessions)
+    }
+
+    fn session_path(&self, id: &str) -> Result<PathBuf, AppError> {
+        // Session ids become file names, so only accept the uuid alphabet.
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Err(AppError::ChatHistoryError(format!(
+                "Invalid chat session id '{}'",
+                id
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+pub fn new_message(
+    role: &str,
+    content: String,
+    reasoning: Option<String>,
+    model: Option<String>,
+    provider: Option<String>,
+) -> StoredChatMessage {
+    StoredChatMessage {
+        id: Uuid::new_v4().to_string(),
+        role: role.to_string(),
+        content,
+        reasoning,
+        model,
+        provider,
+        created_at: Utc::now(),
+    }
+}
+
+fn hold(lock: &Mutex<()>) -> Result<MutexGuard<'_, ()>, AppError> {
+    lock.lock()
+        .map_err(|_| AppError::ChatHistoryError("Chat session lock is unavailable".to_string()))
+}
+
+fn read_session(path: &Path) -> Result<ChatSession, AppError> {
+    let data = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn title_from(content: &str) -> String {
+    let line = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let title: String = line.trim().chars().take(TITLE_MAX_CHARS).collect();
+    if title.is_empty() {
+        DEFAULT_SESSION_TITLE.to_string()
+    } else if line.trim().chars().count() > TITLE_MAX_CHARS {
+        format!("{}...", title)
+    } else {
+        title
+    }
+}
+
+/// Cuts a snippet around a match found in the lowercased copy of `original`.
+/// Lowercasing can change byte lengths, so positions are mapped by character.
+fn snippet_around(original: &str, lowered: &str, byte_position: usize) -> String {
+    let char_position = lowered[..byte_position].chars().count();
+    let start = char_position.saturating_sub(SNIPPET_RADIUS);
+    let total = original.chars().count();
+    let end = (char_position + SNIPPET_RADIUS).min(total);
+    let start = start.min(end);
+
+    let mut snippet: String = original.chars().skip(start).take(end - start).collect();
+    if start > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if end < total {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn concurrent_appends_keep_every_message() {
+        let dir = std::env::temp_dir().join(format!("chat-store-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = Arc::new(ChatSessionStore::new(dir.clone()));
+        let session = store.create(None).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let store = Arc::clone(&store);
+                let id = session.id.clone();
+                std::thread::spawn(move || {
+                    for j in 0..10 {
+                        let message = new_message("user", format!("{} {}", i, j), None, None, None);
+                        store.append(&id, vec![message]).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let messages = store.load(&session.id).unwrap().messages;
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(messages.len(), 80);
+    }
+}
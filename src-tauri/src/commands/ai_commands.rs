@@ -1,25 +1,24 @@
 // src-tauri/src/commands/ai_commands.rs
-use super::models::{AiChatRequest, AiChatResponse, ConnectionTestResponse};
-use crate::llm::LLMClient;
-use crate::llm::factory::get_llm_client;
+use super::models::{
+    AiChatRequest, AiChatResponse, ComputeEmbeddingsRequest, ComputeEmbeddingsResponse,
+    ConnectionTestResponse,
+};
+use crate::chat_history::session_store;
+use crate::chat_history::store::new_message;
+use crate::llm::{EmbeddingOptions, LLMClient};
+use crate::prompts::render_template;
+use crate::secrets::state::VaultState;
+use crate::settings::client_for_profile;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub async fn test_ai_connection(
-    api_key: String,
-    base_url: Option<String>,
-    model: Option<String>,
-    timeout: Option<u64>,
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    profile: Option<String>,
 ) -> Result<ConnectionTestResponse, String> {
-    if api_key.trim().is_empty() || api_key == "your-api-key-here" {
-        return Ok(ConnectionTestResponse {
-            success: false,
-            message: None,
-            error: Some("Please provide a valid API key".to_string()),
-        });
-    }
-
-    let ai_client = match get_llm_client(api_key, base_url, model, Some(20), Some(0.1), timeout) {
-        Ok(client) => client,
+    let ai_client = match client_for_profile(&app, &vault, profile.as_deref()) {
+        Ok((_, client)) => client,
         Err(e) => {
             return Ok(ConnectionTestResponse {
                 success: false,
@@ -44,54 +43,138 @@ pub async fn test_ai_connection(
 }
 
 #[tauri::command]
-pub async fn process_ai_chat(chat_request: AiChatRequest) -> Result<AiChatResponse, String> {
-    if chat_request.api_key.trim().is_empty() || chat_request.api_key == "your-api-key-here" {
-        return Ok(AiChatResponse {
-            response: None,
-            success: false,
-            error: Some("Please configure a valid API key in settings".to_string()),
-        });
-    }
+pub async fn process_ai_chat(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    chat_request: AiChatRequest,
+) -> Result<AiChatResponse, String> {
+    let prompt = match &chat_request.template {
+        Some(template) => {
+            let variables = chat_request.variables.clone().unwrap_or_default();
+            match render_template(&app, template, &variables) {
+                Ok(prompt) => prompt,
+                Err(e) => {
+                    return Ok(AiChatResponse {
+                        response: None,
+                        success: false,
+                        error: Some(e.to_string()),
+                        warning: None,
+                    });
+                }
+            }
+        }
+        None => chat_request.prompt.clone(),
+    };
 
-    if chat_request.prompt.trim().is_empty() {
+    if prompt.trim().is_empty() {
         return Ok(AiChatResponse {
             response: None,
             success: false,
             error: Some("Prompt cannot be empty".to_string()),
+            warning: None,
         });
     }
 
-    let ai_client = match get_llm_client(
-        chat_request.api_key,
-        chat_request.base_url,
-        chat_request.model,
-        chat_request.max_tokens,
-        chat_request.temperature,
-        chat_request.timeout,
-    ) {
-        Ok(client) => client,
+    let ai_client = match client_for_profile(&app, &vault, chat_request.profile.as_deref()) {
+        Ok((_, client)) => client,
         Err(e) => {
             return Ok(AiChatResponse {
                 response: None,
                 success: false,
                 error: Some(e.to_string()),
+                warning: None,
             });
         }
     };
 
-    match ai_client.chat(&chat_request.prompt).await {
+    let session_store = match &chat_request.session_id {
+        Some(session_id) => match session_store(&app).and_then(|store| {
+            store.load(session_id)?;
+            Ok(store)
+        }) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                return Ok(AiChatResponse {
+                    response: None,
+                    success: false,
+                    error: Some(e.to_string()),
+                    warning: None,
+                });
+            }
+        },
+        None => None,
+    };
+
+    match ai_client.chat(&prompt).await {
         Ok(mut response) => {
+            let mut warning = None;
+            if let (Some(store), Some(session_id)) = (&session_store, &chat_request.session_id) {
+                let messages = vec![
+                    new_message("user", prompt, None, None, None),
+                    new_message(
+                        "assistant",
+                        response.output.clone(),
+                        response.reasoning.clone(),
+                        Some(ai_client.model().to_string()),
+                        Some(ai_client.provider().to_string()),
+                    ),
+                ];
+                if let Err(e) = store.append(session_id, messages) {
+                    warning = Some(format!("The reply was not saved to the chat: {}", e));
+                }
+            }
+
             response.reasoning = None;
             Ok(AiChatResponse {
                 response: Some(response),
                 success: true,
                 error: None,
+                warning,
             })
         }
         Err(e) => Ok(AiChatResponse {
             response: None,
             success: false,
             error: Some(e.to_string()),
+            warning: None,
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn compute_embeddings(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    embeddings_request: ComputeEmbeddingsRequest,
+) -> Result<ComputeEmbeddingsResponse, String> {
+    let (profile, ai_client) =
+        match client_for_profile(&app, &vault, embeddings_request.profile.as_deref()) {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ComputeEmbeddingsResponse {
+                    embeddings: None,
+                    success: false,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+    let options = EmbeddingOptions {
+        model: embeddings_request.model.or(profile.embedding_model),
+        dimensions: embeddings_request.dimensions,
+        batch_size: embeddings_request.batch_size,
+    };
+
+    match ai_client.embed(&embeddings_request.inputs, &options).await {
+        Ok(embeddings) => Ok(ComputeEmbeddingsResponse {
+            embeddings: Some(embeddings),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(ComputeEmbeddingsResponse {
+            embeddings: None,
+            success: false,
+            error: Some(e.to_string()),
         }),
     }
 }
@@ -0,0 +1,44 @@
+// src-tauri/src/commands/chat_commands.rs
+use crate::chat_history::models::{ChatSearchResult, ChatSession, ChatSessionSummary};
+use crate::chat_history::session_store;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn create_chat_session(
+    app: AppHandle,
+    title: Option<String>,
+) -> Result<ChatSession, String> {
+    Ok(session_store(&app)?.create(title)?)
+}
+
+#[tauri::command]
+pub async fn list_chat_sessions(app: AppHandle) -> Result<Vec<ChatSessionSummary>, String> {
+    Ok(session_store(&app)?.list()?)
+}
+
+#[tauri::command]
+pub async fn load_chat_session(app: AppHandle, session_id: String) -> Result<ChatSession, String> {
+    Ok(session_store(&app)?.load(&session_id)?)
+}
+
+#[tauri::command]
+pub async fn rename_chat_session(
+    app: AppHandle,
+    session_id: String,
+    title: String,
+) -> Result<ChatSession, String> {
+    Ok(session_store(&app)?.rename(&session_id, &title)?)
+}
+
+#[tauri::command]
+pub async fn delete_chat_session(app: AppHandle, session_id: String) -> Result<(), String> {
+    Ok(session_store(&app)?.delete(&session_id)?)
+}
+
+#[tauri::command]
+pub async fn search_chat_sessions(
+    app: AppHandle,
+    query: String,
+) -> Result<Vec<ChatSearchResult>, String> {
+    Ok(session_store(&app)?.search(&query)?)
+}
@@ -0,0 +1,116 @@
+// src-tauri/src/commands/comment_commands.rs
+use super::models::{
+    CommentAssistRequest, CommentAssistResponse, CommentTriageRequest, CommentTriageResponse,
+};
+use crate::comments::anchors::collect_anchors;
+use crate::comments::assistant::{build_assist_prompt, parse_suggestion, CommentSuggestion};
+use crate::comments::models::CommentThread;
+use crate::errors::AppError;
+use crate::llm::{LLMClient, LlmClient};
+use crate::secrets::state::VaultState;
+use crate::settings::client_for_profile;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn assist_comment_thread(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    assist_request: CommentAssistRequest,
+) -> Result<CommentAssistResponse, String> {
+    let thread_id = assist_request.thread.id.clone();
+
+    let ai_client = match client_for_profile(&app, &vault, assist_request.profile.as_deref()) {
+        Ok((_, client)) => client,
+        Err(e) => return Ok(assist_response(thread_id, Err(e))),
+    };
+
+    let suggestion = suggest_for_thread(
+        &ai_client,
+        &assist_request.thread,
+        &assist_request.anchored_text,
+        assist_request.context.as_deref(),
+    )
+    .await;
+
+    Ok(assist_response(thread_id, suggestion))
+}
+
+/// Suggests a next step for every open thread anchored in the document.
+#[tauri::command]
+pub async fn triage_comment_threads(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    triage_request: CommentTriageRequest,
+) -> Result<CommentTriageResponse, String> {
+    let ai_client = match client_for_profile(&app, &vault, triage_request.profile.as_deref()) {
+        Ok((_, client)) => client,
+        Err(e) => {
+            return Ok(CommentTriageResponse {
+                results: Vec::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    let anchors = collect_anchors(&triage_request.html);
+    let mut results = Vec::new();
+
+    for thread in triage_request.threads.iter().filter(|t| t.is_open()) {
+        let suggestion = match anchors
+            .get(&thread.id)
+            .filter(|a| !a.text.trim().is_empty())
+        {
+            Some(anchor) => {
+                suggest_for_thread(&ai_client, thread, &anchor.text, Some(&anchor.context)).await
+            }
+            None => Err(AppError::AiError(
+                "The commented text is no longer in the document".to_string(),
+            )),
+        };
+        results.push(assist_response(thread.id.clone(), suggestion));
+    }
+
+    Ok(CommentTriageResponse {
+        results,
+        success: true,
+        error: None,
+    })
+}
+
+async fn suggest_for_thread(
+    ai_client: &LlmClient,
+    thread: &CommentThread,
+    anchored_text: &str,
+    context: Option<&str>,
+) -> Result<CommentSuggestion, AppError> {
+    if !thread.is_open() {
+        return Err(AppError::AiError(
+            "The thread is resolved or has no comments".to_string(),
+        ));
+    }
+
+    let prompt = build_assist_prompt(thread, anchored_text, context);
+    let response = ai_client.chat(&prompt).await?;
+    parse_suggestion(&response.output)
+}
+
+fn assist_response(
+    thread_id: String,
+    suggestion: Result<CommentSuggestion, AppError>,
+) -> CommentAssistResponse {
+    match suggestion {
+        Ok(suggestion) => CommentAssistResponse {
+            thread_id,
+            suggestion: Some(suggestion),
+            success: true,
+            error: None,
+        },
+        Err(e) => CommentAssistResponse {
+            thread_id,
+            suggestion: None,
+            success: false,
+            error: Some(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,305 @@
+// src-tauri/src/commands/document_commands.rs
+use super::models::{
+    AskDocumentRequest, AskDocumentResponse, DocumentCitation, DocumentOutlineRequest,
+    DocumentOutlineResponse, DocumentPassage, DocumentSummaryRequest, DocumentSummaryResponse,
+    ExportPdfRequest, ExportPdfResponse, TranslateDocumentRequest, TranslateDocumentResponse,
+};
+use crate::editing::outline::{build_outline_prompt, parse_outline};
+use crate::editing::translation::{
+    translate_batch, TranslationPlan, TranslationProgress, TRANSLATION_PROGRESS_EVENT,
+};
+use crate::errors::AppError;
+use crate::export;
+use crate::html::text::{html_to_text, split_sections, TextSection};
+use crate::llm::{truncate_text, LLMClient};
+use crate::retrieval::index::{DocumentIndex, SourceFingerprint};
+use crate::retrieval::qa::{
+    build_question_prompt, excerpt, parse_citations, CitationUnit, CITATION_EXCERPT_CHARS,
+    DEFAULT_TOP_K, MAX_TOP_K,
+};
+use crate::secrets::state::VaultState;
+use crate::settings::{client_for_profile, load_settings};
+use std::fs;
+use tauri::{AppHandle, Emitter, State};
+
+/// Translates a document while keeping its markup, emitting
+/// `translate-document-progress` after each batch.
+#[tauri::command]
+pub async fn translate_document(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    translate_request: TranslateDocumentRequest,
+) -> Result<TranslateDocumentResponse, String> {
+    match translate(&app, &vault, &translate_request).await {
+        Ok((html, total_segments, untranslated_segments)) => Ok(TranslateDocumentResponse {
+            html: Some(html),
+            total_segments,
+            untranslated_segments,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(TranslateDocumentResponse {
+            html: None,
+            total_segments: 0,
+            untranslated_segments: 0,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn translate(
+    app: &AppHandle,
+    vault: &VaultState,
+    request: &TranslateDocumentRequest,
+) -> Result<(String, usize, usize), AppError> {
+    let language = request.target_language.trim();
+    if language.is_empty() {
+        return Err(AppError::AiError(
+            "A target language is required".to_string(),
+        ));
+    }
+
+    let html = match (&request.html, &request.file_path) {
+        (Some(html), _) => html.clone(),
+        (None, Some(path)) => fs::read_to_string(path)?,
+        (None, None) => {
+            return Err(AppError::AiError(
+                "Provide the document HTML or a file path".to_string(),
+            ))
+        }
+    };
+
+    let (_, ai_client) = client_for_profile(app, vault, request.profile.as_deref())?;
+
+    let plan = TranslationPlan::new(&html);
+    let segments = plan.segments();
+    let batches = plan.batches(ai_client.max_input_chars());
+    let mut translations = Vec::with_capacity(segments.len());
+
+    for (index, batch) in batches.iter().enumerate() {
+        let batch_segments: Vec<_> = batch.iter().map(|&i| &segments[i]).collect();
+        translations.extend(translate_batch(&ai_client, &batch_segments, language).await?);
+
+        let _ = app.emit(
+            TRANSLATION_PROGRESS_EVENT,
+            TranslationProgress {
+                completed_batches: index + 1,
+                total_batches: batches.len(),
+                total_segments: segments.len(),
+            },
+        );
+    }
+
+    let translated = plan.render(&translations);
+    if let Some(output_path) = &request.output_path {
+        fs::write(output_path, &translated)?;
+    }
+
+    let untranslated = translations.iter().filter(|t| t.is_none()).count();
+    Ok((translated, segments.len(), untranslated))
+}
+
+#[tauri::command]
+pub async fn summarize_document(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    summary_request: DocumentSummaryRequest,
+) -> Result<DocumentSummaryResponse, String> {
+    match summarize(&app, &vault, summary_request).await {
+        Ok(summary) => Ok(DocumentSummaryResponse {
+            summary,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(DocumentSummaryResponse {
+            summary: String::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Writes the document as a PDF, with its comment threads as annotations
+/// or endnotes when `options.comments` asks for them.
+#[tauri::command]
+pub async fn export_pdf(export_request: ExportPdfRequest) -> Result<ExportPdfResponse, String> {
+    // Font loading, subsetting and layout take a while for long documents.
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        export::export_pdf(
+            &export_request.html,
+            &export_request.threads,
+            &export_request.options,
+            &export_request.output_path,
+        )
+        .map(|summary| (summary, export_request.output_path))
+    })
+    .await
+    .map_err(|e| AppError::ExportError(format!("Export task failed: {}", e)))
+    .and_then(|result| result);
+
+    match result {
+        Ok((summary, path)) => Ok(ExportPdfResponse {
+            summary: Some(summary),
+            path: Some(path),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(ExportPdfResponse {
+            summary: None,
+            path: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn summarize(
+    app: &AppHandle,
+    vault: &VaultState,
+    request: DocumentSummaryRequest,
+) -> Result<String, AppError> {
+    let text = document_text(&request.html)?;
+    let prompt = match request.prompt.filter(|p| !p.trim().is_empty()) {
+        Some(prompt) => prompt,
+        None => load_settings(app)?.summary_prompt,
+    };
+
+    let (_, ai_client) = client_for_profile(app, vault, request.profile.as_deref())?;
+    ai_client.summarize(&text, &prompt).await
+}
+
+#[tauri::command]
+pub async fn outline_document(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    outline_request: DocumentOutlineRequest,
+) -> Result<DocumentOutlineResponse, String> {
+    let outline = async {
+        let text = document_text(&outline_request.html)?;
+        let (_, ai_client) = client_for_profile(&app, &vault, outline_request.profile.as_deref())?;
+        let prompt = build_outline_prompt(&truncate_text(&text, ai_client.max_input_chars()));
+        let response = ai_client.chat(&prompt).await?;
+        parse_outline(&response.output)
+    };
+
+    match outline.await {
+        Ok(outline) => Ok(DocumentOutlineResponse {
+            outline,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(DocumentOutlineResponse {
+            outline: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Answers a question about the open document from its best-matching
+/// passages, citing sections (the text under each heading).
+#[tauri::command]
+pub async fn ask_document(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    ask_request: AskDocumentRequest,
+) -> Result<AskDocumentResponse, String> {
+    if ask_request.question.trim().is_empty() {
+        return Ok(AskDocumentResponse {
+            answer: String::new(),
+            citations: Vec::new(),
+            passages: Vec::new(),
+            success: false,
+            error: Some("Question cannot be empty".to_string()),
+        });
+    }
+
+    match answer_document_question(&app, &vault, ask_request).await {
+        Ok(response) => Ok(response),
+        Err(e) => Ok(AskDocumentResponse {
+            answer: String::new(),
+            citations: Vec::new(),
+            passages: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn answer_document_question(
+    app: &AppHandle,
+    vault: &VaultState,
+    request: AskDocumentRequest,
+) -> Result<AskDocumentResponse, AppError> {
+    let text = document_text(&request.html)?;
+    let sections = split_sections(&text);
+    let heading = |number: u32| -> Option<String> {
+        sections
+            .iter()
+            .find(|s| s.number == number)
+            .and_then(|s| s.heading.clone())
+    };
+
+    let pages: Vec<(u32, String)> = sections.iter().map(section_text).collect();
+    let index = DocumentIndex::build(SourceFingerprint::for_text(&text), &pages);
+
+    let top_k = request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+    let passages = index.search(&request.question, None, top_k);
+    if passages.is_empty() {
+        return Err(AppError::AiError(
+            "No passages in the document match this question".to_string(),
+        ));
+    }
+
+    let (_, ai_client) = client_for_profile(app, vault, request.profile.as_deref())?;
+    let prompt = build_question_prompt(&request.question, &passages, CitationUnit::Section);
+    let answer = ai_client.chat(&prompt).await?.output;
+
+    let citations = parse_citations(&answer, CitationUnit::Section)
+        .into_iter()
+        .filter_map(|section| {
+            passages
+                .iter()
+                .find(|p| p.chunk.page == section)
+                .map(|p| DocumentCitation {
+                    section,
+                    heading: heading(section),
+                    excerpt: excerpt(&p.chunk.text, CITATION_EXCERPT_CHARS),
+                })
+        })
+        .collect();
+
+    Ok(AskDocumentResponse {
+        answer,
+        citations,
+        passages: passages
+            .into_iter()
+            .map(|p| DocumentPassage {
+                section: p.chunk.page,
+                heading: heading(p.chunk.page),
+                text: p.chunk.text,
+                score: p.score,
+            })
+            .collect(),
+        success: true,
+        error: None,
+    })
+}
+
+fn document_text(html: &str) -> Result<String, AppError> {
+    let text = html_to_text(html);
+    if text.trim().is_empty() {
+        return Err(AppError::AiError("The document is empty".to_string()));
+    }
+    Ok(text)
+}
+
+/// Section text as indexed, with the heading kept so it can match queries.
+fn section_text(section: &TextSection) -> (u32, String) {
+    let text = match &section.heading {
+        Some(heading) => format!("{}\n{}", heading, section.text),
+        None => section.text.clone(),
+    };
+    (section.number, text)
+}
@@ -0,0 +1,257 @@
+// src-tauri/src/commands/editing_commands.rs
+use super::models::{
+    AutocompleteRequest, AutocompleteResponse, GrammarCheckRequest, GrammarCheckResponse,
+    TransformTextRequest, TransformTextResponse,
+};
+use crate::editing::autocomplete::{
+    cache_key, head_chars, tail_chars, AutocompleteState, DEFAULT_COMPLETION_TOKENS,
+    MAX_COMPLETION_TOKENS, MAX_PREFIX_CHARS, MAX_SUFFIX_CHARS,
+};
+use crate::editing::diff::word_diff;
+use crate::editing::grammar::{
+    anchor_suggestions, build_grammar_prompt, grammar_schema, GrammarReply,
+};
+use crate::editing::transform::{build_transform_prompt, clean_transform_output};
+use crate::llm::completion::CompletionRequest;
+use crate::llm::structured::parse_json_output;
+use crate::llm::LLMClient;
+use crate::secrets::state::VaultState;
+use crate::settings::client_for_profile;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn transform_text(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    transform_request: TransformTextRequest,
+) -> Result<TransformTextResponse, String> {
+    let TransformTextRequest {
+        text,
+        operation,
+        context,
+        profile,
+    } = transform_request;
+
+    if text.trim().is_empty() {
+        return Ok(TransformTextResponse {
+            original: text,
+            rewritten: None,
+            diff: Vec::new(),
+            success: false,
+            error: Some("Select some text to transform".to_string()),
+        });
+    }
+
+    let prompt = match build_transform_prompt(&text, &operation, context.as_deref()) {
+        Ok(prompt) => prompt,
+        Err(e) => {
+            return Ok(TransformTextResponse {
+                original: text,
+                rewritten: None,
+                diff: Vec::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    let ai_client = match client_for_profile(&app, &vault, profile.as_deref()) {
+        Ok((_, client)) => client,
+        Err(e) => {
+            return Ok(TransformTextResponse {
+                original: text,
+                rewritten: None,
+                diff: Vec::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    match ai_client.chat(&prompt).await {
+        Ok(response) => {
+            let rewritten = clean_transform_output(&response.output, &text);
+            let diff = word_diff(&text, &rewritten);
+            Ok(TransformTextResponse {
+                original: text,
+                rewritten: Some(rewritten),
+                diff,
+                success: true,
+                error: None,
+            })
+        }
+        Err(e) => Ok(TransformTextResponse {
+            original: text,
+            rewritten: None,
+            diff: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn check_grammar(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    grammar_request: GrammarCheckRequest,
+) -> Result<GrammarCheckResponse, String> {
+    if grammar_request.text.trim().is_empty() {
+        return Ok(GrammarCheckResponse {
+            suggestions: Vec::new(),
+            success: true,
+            error: None,
+        });
+    }
+
+    let ai_client = match client_for_profile(&app, &vault, grammar_request.profile.as_deref()) {
+        Ok((_, client)) => client,
+        Err(e) => {
+            return Ok(GrammarCheckResponse {
+                suggestions: Vec::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    let prompt = build_grammar_prompt(&grammar_request.text);
+    let issues = match ai_client.chat_structured(&prompt, &grammar_schema()).await {
+        Ok(response) => {
+            parse_json_output::<GrammarReply>(&response.output).map(GrammarReply::into_issues)
+        }
+        Err(e) => Err(e),
+    };
+
+    match issues {
+        Ok(issues) => Ok(GrammarCheckResponse {
+            suggestions: anchor_suggestions(&grammar_request.text, issues, grammar_request.offset),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(GrammarCheckResponse {
+            suggestions: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Ghost-text completion at the cursor. Each call cancels the previous one
+/// still in flight, which then returns with `cancelled` set.
+#[tauri::command]
+pub async fn autocomplete(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    autocomplete_state: State<'_, AutocompleteState>,
+    autocomplete_request: AutocompleteRequest,
+) -> Result<AutocompleteResponse, String> {
+    let superseded = match autocomplete_state.begin() {
+        Ok(superseded) => superseded,
+        Err(e) => {
+            return Ok(AutocompleteResponse {
+                completion: None,
+                cached: false,
+                cancelled: false,
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    let prefix = tail_chars(&autocomplete_request.prefix, MAX_PREFIX_CHARS);
+    let suffix = autocomplete_request
+        .suffix
+        .as_deref()
+        .map(|s| head_chars(s, MAX_SUFFIX_CHARS));
+
+    if prefix.trim().is_empty() {
+        return Ok(AutocompleteResponse {
+            completion: None,
+            cached: false,
+            cancelled: false,
+            success: true,
+            error: None,
+        });
+    }
+
+    let (profile, ai_client) =
+        match client_for_profile(&app, &vault, autocomplete_request.profile.as_deref()) {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(AutocompleteResponse {
+                    completion: None,
+                    cached: false,
+                    cancelled: false,
+                    success: false,
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+    let key = cache_key(&profile.name, ai_client.model(), prefix, suffix);
+    match autocomplete_state.cached(&key) {
+        Ok(Some(completion)) => {
+            return Ok(AutocompleteResponse {
+                completion: Some(completion),
+                cached: true,
+                cancelled: false,
+                success: true,
+                error: None,
+            });
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return Ok(AutocompleteResponse {
+                completion: None,
+                cached: false,
+                cancelled: false,
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    }
+
+    let request = CompletionRequest {
+        prefix: prefix.to_string(),
+        suffix: suffix.map(str::to_string),
+        max_tokens: autocomplete_request
+            .max_tokens
+            .unwrap_or(DEFAULT_COMPLETION_TOKENS)
+            .clamp(1, MAX_COMPLETION_TOKENS),
+        stop: autocomplete_request
+            .stop
+            .unwrap_or_else(|| vec!["\n\n".to_string()]),
+    };
+
+    tokio::select! {
+        result = ai_client.complete(&request) => match result.and_then(|completion| {
+            autocomplete_state.remember(key, completion.clone())?;
+            Ok(completion)
+        }) {
+            Ok(completion) => {
+                Ok(AutocompleteResponse {
+                    completion: Some(completion),
+                    cached: false,
+                    cancelled: false,
+                    success: true,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(AutocompleteResponse {
+                completion: None,
+                cached: false,
+                cancelled: false,
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        },
+        _ = superseded => Ok(AutocompleteResponse {
+            completion: None,
+            cached: false,
+            cancelled: true,
+            success: false,
+            error: None,
+        }),
+    }
+}
@@ -1,4 +1,11 @@
 // src-tauri/src/commands/mod.rs
 pub mod ai_commands;
+pub mod chat_commands;
+pub mod comment_commands;
+pub mod document_commands;
+pub mod editing_commands;
 pub mod pdf_commands;
+pub mod prompt_commands;
+pub mod settings_commands;
 pub mod models;
+pub mod vault_commands;
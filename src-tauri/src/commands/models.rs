@@ -1,21 +1,37 @@
 // src-tauri/src/commands/models.rs
-use crate::llm::ReasoningResponse;
+use crate::comments::assistant::CommentSuggestion;
+use crate::comments::models::CommentThread;
+use crate::editing::diff::DiffHunk;
+use crate::editing::grammar::GrammarSuggestion;
+use crate::editing::outline::OutlineEntry;
+use crate::editing::transform::TransformOperation;
+use crate::export::{ExportOptions, ExportSummary};
+use crate::llm::{Embeddings, ReasoningResponse};
+use crate::pdf::pdf_extractor::{ExtractedDocument, ExtractedPage, ExtractionSummary};
+use crate::pdf::pdf_html::PdfHtmlDocument;
+use crate::pdf::pdf_images::PdfImage;
+use crate::pdf::pdf_info::{FontInfo, PageSize};
+use crate::pdf::pdf_loader::PdfPermissions;
+use crate::pdf::pdf_metadata::DocumentMetadata;
+use crate::pdf::pdf_outline::TocEntry;
+use crate::pdf::pdf_tables::PdfTable;
+use crate::pdf::pdf_validation::PdfHealthReport;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 pub struct PdfSummarizationRequest {
     #[serde(rename = "filePath")]
     pub file_path: String,
-    pub prompt: String,
-    #[serde(rename = "apiKey")]
-    pub api_key: String,
-    #[serde(rename = "baseUrl")]
-    pub base_url: Option<String>,
-    pub model: Option<String>,
-    #[serde(rename = "maxTokens")]
-    pub max_tokens: Option<u32>,
-    pub temperature: Option<f32>,
-    pub timeout: Option<u64>,
+    /// Falls back to the summary prompt from settings when omitted.
+    pub prompt: Option<String>,
+    pub profile: Option<String>,
+    pub template: Option<String>,
+    pub variables: Option<HashMap<String, String>>,
+    /// Needed only for PDFs that do not open with an empty password.
+    pub password: Option<String>,
+    /// Pages to summarize, such as "3-5, 9"; all pages when omitted.
+    pub pages: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -34,6 +50,104 @@ pub struct PdfAnalysisResponse {
     pub has_text: bool,
     #[serde(rename = "fileSize")]
     pub file_size: String,
+    pub version: String,
+    pub encrypted: bool,
+    /// What the author allows; `None` for unencrypted files.
+    pub permissions: Option<PdfPermissions>,
+    /// Set when the PDF could not be opened without a (correct) password,
+    /// so the UI can ask for one.
+    #[serde(rename = "passwordRequired")]
+    pub password_required: bool,
+    /// The cross-reference table was broken and had to be rebuilt.
+    pub repaired: bool,
+    pub tagged: bool,
+    pub linearized: bool,
+    /// Info dictionary entries, with XMP filling any gaps.
+    pub metadata: DocumentMetadata,
+    #[serde(rename = "hasXmp")]
+    pub has_xmp: bool,
+    #[serde(rename = "pageSizes")]
+    pub page_sizes: Vec<PageSize>,
+    pub fonts: Vec<FontInfo>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfHealthResponse {
+    pub report: Option<PdfHealthReport>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Payload of the `pdf-page-extracted` event.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfPageProgress {
+    pub file_path: String,
+    pub page: ExtractedPage,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+pub struct PdfStreamResponse {
+    pub summary: Option<ExtractionSummary>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfTablesResponse {
+    pub tables: Vec<PdfTable>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfImagesResponse {
+    pub images: Vec<PdfImage>,
+    /// Where the image files were written.
+    pub directory: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfDocumentResponse {
+    pub document: Option<PdfHtmlDocument>,
+    /// The saved HTML file, which `load_document` opens.
+    pub path: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfAnnotationsResponse {
+    pub threads: Vec<CommentThread>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertPdfTableRequest {
+    /// The current document.
+    pub html: String,
+    pub file_path: String,
+    pub password: Option<String>,
+    pub page_number: u32,
+    /// The table's `index` from `extract_pdf_tables`.
+    pub table_index: usize,
+    /// Number of top-level blocks to insert after; the end of the
+    /// document when omitted.
+    pub position: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct InsertPdfTableResponse {
+    pub html: Option<String>,
+    pub table: Option<PdfTable>,
     pub success: bool,
     pub error: Option<String>,
 }
@@ -47,21 +161,337 @@ pub struct ConnectionTestResponse {
 
 #[derive(Deserialize)]
 pub struct AiChatRequest {
+    #[serde(default)]
     pub prompt: String,
-    #[serde(rename = "apiKey")]
-    pub api_key: String,
-    #[serde(rename = "baseUrl")]
-    pub base_url: Option<String>,
+    pub template: Option<String>,
+    pub variables: Option<HashMap<String, String>>,
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AiChatResponse {
+    pub response: Option<ReasoningResponse>,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Set when the reply succeeded but could not be saved to its session.
+    pub warning: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ComputeEmbeddingsRequest {
+    pub inputs: Vec<String>,
+    pub profile: Option<String>,
     pub model: Option<String>,
+    pub dimensions: Option<u32>,
+    #[serde(rename = "batchSize")]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct ComputeEmbeddingsResponse {
+    pub embeddings: Option<Embeddings>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AskPdfRequest {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    pub question: String,
+    pub profile: Option<String>,
+    #[serde(rename = "topK")]
+    pub top_k: Option<usize>,
+    #[serde(rename = "embeddingModel")]
+    pub embedding_model: Option<String>,
+    pub password: Option<String>,
+    /// Restricts answers to these pages, such as "3-5"; all pages when
+    /// omitted.
+    pub pages: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfPassage {
+    pub page: u32,
+    pub text: String,
+    pub score: f32,
+}
+
+#[derive(Serialize)]
+pub struct PdfCitation {
+    pub page: u32,
+    pub excerpt: String,
+}
+
+#[derive(Serialize)]
+pub struct AskPdfResponse {
+    pub answer: String,
+    pub citations: Vec<PdfCitation>,
+    pub passages: Vec<PdfPassage>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VaultStatus {
+    pub exists: bool,
+    pub unlocked: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TransformTextRequest {
+    pub text: String,
+    pub operation: TransformOperation,
+    pub context: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TransformTextResponse {
+    pub original: String,
+    pub rewritten: Option<String>,
+    pub diff: Vec<DiffHunk>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GrammarCheckRequest {
+    pub text: String,
+    /// Position of the text in the document; added to every returned range.
+    #[serde(default)]
+    pub offset: usize,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GrammarCheckResponse {
+    pub suggestions: Vec<GrammarSuggestion>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CommentAssistRequest {
+    pub thread: CommentThread,
+    #[serde(rename = "anchoredText")]
+    pub anchored_text: String,
+    pub context: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CommentAssistResponse {
+    #[serde(rename = "threadId")]
+    pub thread_id: String,
+    pub suggestion: Option<CommentSuggestion>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CommentTriageRequest {
+    /// Document HTML containing the comment marks.
+    pub html: String,
+    pub threads: Vec<CommentThread>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CommentTriageResponse {
+    pub results: Vec<CommentAssistResponse>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AutocompleteRequest {
+    pub prefix: String,
+    pub suffix: Option<String>,
     #[serde(rename = "maxTokens")]
     pub max_tokens: Option<u32>,
-    pub temperature: Option<f32>,
-    pub timeout: Option<u64>,
+    /// Defaults to stopping at a paragraph break.
+    pub stop: Option<Vec<String>>,
+    pub profile: Option<String>,
 }
 
 #[derive(Serialize)]
-pub struct AiChatResponse {
-    pub response: Option<ReasoningResponse>,
+pub struct AutocompleteResponse {
+    pub completion: Option<String>,
+    pub cached: bool,
+    /// Set when a newer request superseded this one.
+    pub cancelled: bool,
     pub success: bool,
     pub error: Option<String>,
 }
+
+#[derive(Deserialize)]
+pub struct TranslateDocumentRequest {
+    /// A document saved by `save_document`; ignored when `html` is given.
+    #[serde(rename = "filePath")]
+    pub file_path: Option<String>,
+    pub html: Option<String>,
+    #[serde(rename = "targetLanguage")]
+    pub target_language: String,
+    /// Where to write the translated document, if anywhere.
+    #[serde(rename = "outputPath")]
+    pub output_path: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TranslateDocumentResponse {
+    pub html: Option<String>,
+    #[serde(rename = "totalSegments")]
+    pub total_segments: usize,
+    /// Segments left in the original language because the model broke
+    /// their markup.
+    #[serde(rename = "untranslatedSegments")]
+    pub untranslated_segments: usize,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DocumentSummaryRequest {
+    pub html: String,
+    /// Defaults to the summary prompt from settings.
+    pub prompt: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DocumentSummaryResponse {
+    pub summary: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DocumentOutlineRequest {
+    pub html: String,
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DocumentOutlineResponse {
+    pub outline: Vec<OutlineEntry>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AskDocumentRequest {
+    pub html: String,
+    pub question: String,
+    pub profile: Option<String>,
+    #[serde(rename = "topK")]
+    pub top_k: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct DocumentPassage {
+    pub section: u32,
+    pub heading: Option<String>,
+    pub text: String,
+    pub score: f32,
+}
+
+#[derive(Serialize)]
+pub struct DocumentCitation {
+    pub section: u32,
+    pub heading: Option<String>,
+    pub excerpt: String,
+}
+
+#[derive(Serialize)]
+pub struct AskDocumentResponse {
+    pub answer: String,
+    pub citations: Vec<DocumentCitation>,
+    pub passages: Vec<DocumentPassage>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPdfRequest {
+    pub html: String,
+    pub output_path: String,
+    /// The document's comment threads, for `options.comments`.
+    #[serde(default)]
+    pub threads: Vec<CommentThread>,
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+#[derive(Serialize)]
+pub struct ExportPdfResponse {
+    pub summary: Option<ExportSummary>,
+    pub path: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfPagesResponse {
+    pub document: Option<ExtractedDocument>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfOutlineResponse {
+    pub outline: Vec<TocEntry>,
+    #[serde(rename = "pageCount")]
+    pub page_count: usize,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PdfSectionSummaryRequest {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    /// Outline entry ids to summarize; defaults to every top-level entry.
+    #[serde(rename = "sectionIds")]
+    pub section_ids: Option<Vec<String>>,
+    /// Summarize the chosen sections together instead of one by one.
+    #[serde(default)]
+    pub combined: bool,
+    /// Defaults to the summary prompt from settings.
+    pub prompt: Option<String>,
+    pub profile: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionSummary {
+    pub id: String,
+    pub title: String,
+    pub start_page: Option<u32>,
+    pub end_page: Option<u32>,
+    pub summary: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PdfSectionSummaryResponse {
+    /// One summary per section; empty when `combined` was requested.
+    pub sections: Vec<SectionSummary>,
+    /// The summary of all chosen sections when `combined` was requested.
+    pub summary: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionSummaryProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub section_id: String,
+}
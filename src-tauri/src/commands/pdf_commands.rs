@@ -1,45 +1,138 @@
 // src-tauri/src/commands/pdf_commands.rs
-use super::models::{PdfAnalysisResponse, PdfSummarizationResponse};
-use crate::llm::LLMClient;
-use crate::llm::factory::get_llm_client;
+use super::models::{
+    AskPdfRequest, AskPdfResponse, InsertPdfTableRequest, InsertPdfTableResponse,
+    PdfAnalysisResponse, PdfAnnotationsResponse, PdfCitation, PdfDocumentResponse,
+    PdfHealthResponse, PdfImagesResponse, PdfOutlineResponse, PdfPageProgress, PdfPagesResponse,
+    PdfPassage, PdfSectionSummaryRequest, PdfSectionSummaryResponse, PdfStreamResponse,
+    PdfSummarizationRequest, PdfSummarizationResponse, PdfTablesResponse, SectionSummary,
+    SectionSummaryProgress,
+};
+use crate::app_dirs::app_data_subdir;
+use crate::comments::pdf_import::threads_from_annotations;
+use crate::errors::AppError;
+use crate::html::insert_block;
+use crate::llm::{truncate_text, EmbeddingOptions, LLMClient, LlmClient};
+use crate::pdf::pdf_metadata::DocumentMetadata;
+use crate::pdf::pdf_outline::{section_text, TocEntry};
+use crate::pdf::pdf_page_range::PageSelection;
+use crate::pdf::pdf_validation::format_file_size;
 use crate::pdf_processor::PdfProcessor;
+use crate::prompts::library_path;
+use crate::prompts::store::PromptLibrary;
+use crate::retrieval::cache::IndexCache;
+use crate::retrieval::index::{index_file_path, ChunkEmbeddings, DocumentIndex, SourceFingerprint};
+use crate::retrieval::qa::{
+    build_question_prompt, excerpt, parse_citations, CitationUnit, CITATION_EXCERPT_CHARS,
+    DEFAULT_TOP_K, MAX_TOP_K,
+};
+use crate::secrets::state::VaultState;
+use crate::settings::models::AppSettings;
+use crate::settings::{client_for_profile, load_settings};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+
+const PDF_INDEX_DIR: &str = "pdf_indexes";
+const PDF_ASSET_DIR: &str = "assets";
+const PDF_DOCUMENT_DIR: &str = "documents";
+const SECTION_SUMMARY_PROGRESS_EVENT: &str = "pdf-section-summary-progress";
+const PAGE_EXTRACTED_EVENT: &str = "pdf-page-extracted";
+
+/// Parses the requested pages and checks the file is small enough to open.
+/// Reading parts of a document is not held to the file size limit.
+fn open_selection(
+    app: &AppHandle,
+    file_path: &str,
+    pages: Option<&str>,
+) -> Result<PageSelection, AppError> {
+    let pages = PageSelection::parse(pages)?;
+    PdfProcessor::validate_open_size(file_path, load_settings(app)?.max_pdf_open_bytes)?;
+    Ok(pages)
+}
+
+/// Limits for requests that send text to the model. Whole documents are
+/// held to the size limit; page ranges may come from larger files up to
+/// the open size limit.
+fn check_pdf_limits(
+    settings: &AppSettings,
+    file_path: &str,
+    pages: &PageSelection,
+) -> Result<(), AppError> {
+    PdfProcessor::validate_open_size(file_path, settings.max_pdf_open_bytes)?;
+    if pages.is_all() {
+        PdfProcessor::validate_file_size(file_path, settings.max_pdf_size_bytes)?;
+    }
+    Ok(())
+}
+
+/// Runs PDF work on a blocking thread instead of the async runtime's
+/// workers: loading parses the whole file, and text extraction may OCR
+/// scanned pages for minutes.
+async fn run_blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::PdfError(format!("PDF task failed: {}", e)))?
+}
 
 #[tauri::command]
 pub async fn process_pdf_summarization(
-    file_path: String,
-    prompt: String,
-    api_key: String,
-    base_url: Option<String>,
-    model: Option<String>,
-    max_tokens: Option<u32>,
-    temperature: Option<f32>,
-    timeout: Option<u64>,
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    summarization_request: PdfSummarizationRequest,
 ) -> Result<PdfSummarizationResponse, String> {
-    if api_key.trim().is_empty() || api_key == "your-api-key-here" {
-        return Ok(PdfSummarizationResponse {
-            summary: String::new(),
-            success: false,
-            error: Some("Please configure a valid API key in settings".to_string()),
-        });
-    }
+    let PdfSummarizationRequest {
+        file_path,
+        prompt,
+        profile,
+        template,
+        variables,
+        password,
+        pages,
+    } = summarization_request;
 
-    if prompt.trim().is_empty() {
-        return Ok(PdfSummarizationResponse {
-            summary: String::new(),
-            success: false,
-            error: Some("Prompt cannot be empty".to_string()),
-        });
-    }
+    let settings = match load_settings(&app) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return Ok(PdfSummarizationResponse {
+                summary: String::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
 
-    if let Err(e) = PdfProcessor::validate_file_size(&file_path, 10) {
+    let pages = match PageSelection::parse(pages.as_deref())
+        .and_then(|pages| check_pdf_limits(&settings, &file_path, &pages).map(|_| pages))
+    {
+        Ok(pages) => pages,
+        Err(e) => {
+            return Ok(PdfSummarizationResponse {
+                summary: String::new(),
+                success: false,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+
+    let prompt = prompt
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(settings.summary_prompt);
+
+    if template.is_none() && prompt.trim().is_empty() {
         return Ok(PdfSummarizationResponse {
             summary: String::new(),
             success: false,
-            error: Some(e.to_string()),
+            error: Some("Prompt cannot be empty".to_string()),
         });
     }
 
-    let text = match PdfProcessor::extract_text(&file_path) {
+    let text = match run_blocking(move || {
+        PdfProcessor::extract_text(&file_path, password.as_deref(), &pages)
+    })
+    .await
+    {
         Ok(text) => text,
         Err(e) => {
             return Ok(PdfSummarizationResponse {
@@ -50,9 +143,8 @@ pub async fn process_pdf_summarization(
         }
     };
 
-    let ai_client = match get_llm_client(api_key, base_url, model, max_tokens, temperature, timeout)
-    {
-        Ok(client) => client,
+    let ai_client = match client_for_profile(&app, &vault, profile.as_deref()) {
+        Ok((_, client)) => client,
         Err(e) => {
             return Ok(PdfSummarizationResponse {
                 summary: String::new(),
@@ -62,7 +154,14 @@ pub async fn process_pdf_summarization(
         }
     };
 
-    match ai_client.summarize(&text, &prompt).await {
+    let result = match template {
+        Some(template) => {
+            summarize_with_template(&app, &ai_client, &template, variables, &text).await
+        }
+        None => ai_client.summarize(&text, &prompt).await,
+    };
+
+    match result {
         Ok(summary) => Ok(PdfSummarizationResponse {
             summary,
             success: true,
@@ -76,26 +175,71 @@ pub async fn process_pdf_summarization(
     }
 }
 
+/// Renders a stored template for the PDF. Templates that place the text
+/// themselves via `{{pdf_text}}` are sent as-is; others are used as the
+/// summarization instruction.
+async fn summarize_with_template(
+    app: &AppHandle,
+    ai_client: &LlmClient,
+    template: &str,
+    variables: Option<HashMap<String, String>>,
+    text: &str,
+) -> Result<String, AppError> {
+    let library = PromptLibrary::load(&library_path(app)?)?;
+    let template = library.get(template)?;
+    let mut values = variables.unwrap_or_default();
+
+    if template.uses_variable("pdf_text") {
+        values.insert(
+            "pdf_text".to_string(),
+            truncate_text(text, ai_client.max_input_chars()),
+        );
+        let prompt = template.render(&values)?;
+        ai_client.chat(&prompt).await.map(|r| r.output)
+    } else {
+        let prompt = template.render(&values)?;
+        ai_client.summarize(text, &prompt).await
+    }
+}
+
 #[tauri::command]
-pub async fn analyze_pdf(file_path: String) -> Result<PdfAnalysisResponse, String> {
-    match PdfProcessor::get_pdf_info(&file_path) {
+pub async fn analyze_pdf(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfAnalysisResponse, String> {
+    let info = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            let path = file_path.clone();
+            run_blocking(move || PdfProcessor::get_pdf_info(&path, password.as_deref(), &pages))
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match info {
         Ok(info) => {
             let metadata = std::fs::metadata(&file_path)
                 .map_err(|e| format!("Failed to get file info: {}", e))?;
-
-            let file_size = if metadata.len() < 1024 {
-                format!("{} bytes", metadata.len())
-            } else if metadata.len() < 1024 * 1024 {
-                format!("{:.1} KB", metadata.len() as f64 / 1024.0)
-            } else {
-                format!("{:.1} MB", metadata.len() as f64 / (1024.0 * 1024.0))
-            };
+            let file_size = format_file_size(metadata.len());
 
             Ok(PdfAnalysisResponse {
                 page_count: info.page_count,
                 title: info.title,
                 has_text: info.has_text,
                 file_size,
+                version: info.version,
+                encrypted: info.encrypted,
+                permissions: info.permissions,
+                password_required: false,
+                repaired: info.repaired,
+                tagged: info.tagged,
+                linearized: info.linearized,
+                metadata: info.metadata,
+                has_xmp: info.has_xmp,
+                page_sizes: info.page_sizes,
+                fonts: info.fonts,
                 success: true,
                 error: None,
             })
@@ -105,6 +249,17 @@ pub async fn analyze_pdf(file_path: String) -> Result<PdfAnalysisResponse, Strin
             title: "Unknown".to_string(),
             has_text: false,
             file_size: "Unknown".to_string(),
+            version: String::new(),
+            encrypted: matches!(e, AppError::PdfPasswordRequired(_)),
+            permissions: None,
+            password_required: matches!(e, AppError::PdfPasswordRequired(_)),
+            repaired: false,
+            tagged: false,
+            linearized: false,
+            metadata: DocumentMetadata::default(),
+            has_xmp: false,
+            page_sizes: Vec::new(),
+            fonts: Vec::new(),
             success: false,
             error: Some(e.to_string()),
         }),
@@ -112,8 +267,23 @@ pub async fn analyze_pdf(file_path: String) -> Result<PdfAnalysisResponse, Strin
 }
 
 #[tauri::command]
-pub async fn extract_pdf_text(file_path: String) -> Result<serde_json::Value, String> {
-    match PdfProcessor::extract_text(&file_path) {
+pub async fn extract_pdf_text(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let text = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::extract_text(&file_path, password.as_deref(), &pages)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match text {
         Ok(text) => Ok(serde_json::json!({
             "content": text,
             "success": true,
@@ -126,3 +296,660 @@ pub async fn extract_pdf_text(file_path: String) -> Result<serde_json::Value, St
         })),
     }
 }
+
+/// Per-page text with paragraph breaks and any page-level extraction
+/// errors; `extract_pdf_text` returns the same text flattened.
+#[tauri::command]
+pub async fn extract_pdf_pages(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfPagesResponse, String> {
+    let document = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::extract_document(&file_path, password.as_deref(), &pages)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match document {
+        Ok(document) => Ok(PdfPagesResponse {
+            document: Some(document),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfPagesResponse {
+            document: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Extracts pages one at a time, emitting each as a `pdf-page-extracted`
+/// event as soon as it is ready. Only totals are returned, so the text of
+/// the pages is never collected; the document itself is still loaded
+/// whole, within the open size limit.
+#[tauri::command]
+pub async fn stream_pdf_pages(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfStreamResponse, String> {
+    let summary = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::for_each_page(
+                    &file_path,
+                    password.as_deref(),
+                    &pages,
+                    |page, completed, total| {
+                        let _ = app.emit(
+                            PAGE_EXTRACTED_EVENT,
+                            PdfPageProgress {
+                                file_path: file_path.clone(),
+                                page,
+                                completed,
+                                total,
+                            },
+                        );
+                    },
+                )
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match summary {
+        Ok(summary) => Ok(PdfStreamResponse {
+            summary: Some(summary),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfStreamResponse {
+            summary: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Structural health of the file: header, EOF marker, cross-reference
+/// table, missing objects and unreadable pages. Damaged files are repaired
+/// in memory so the rest of the checks can run.
+#[tauri::command]
+pub async fn check_pdf_health(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfHealthResponse, String> {
+    let report = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::check_health(&file_path, password.as_deref(), &pages)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match report {
+        Ok(report) => Ok(PdfHealthResponse {
+            report: Some(report),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfHealthResponse {
+            report: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn ask_pdf(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    index_cache: State<'_, IndexCache>,
+    ask_request: AskPdfRequest,
+) -> Result<AskPdfResponse, String> {
+    if ask_request.question.trim().is_empty() {
+        return Ok(AskPdfResponse {
+            answer: String::new(),
+            citations: Vec::new(),
+            passages: Vec::new(),
+            success: false,
+            error: Some("Question cannot be empty".to_string()),
+        });
+    }
+
+    match answer_pdf_question(&app, &vault, &index_cache, ask_request).await {
+        Ok(response) => Ok(response),
+        Err(e) => Ok(AskPdfResponse {
+            answer: String::new(),
+            citations: Vec::new(),
+            passages: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn answer_pdf_question(
+    app: &AppHandle,
+    vault: &VaultState,
+    index_cache: &IndexCache,
+    request: AskPdfRequest,
+) -> Result<AskPdfResponse, AppError> {
+    let pages = PageSelection::parse(request.pages.as_deref())?;
+    check_pdf_limits(&load_settings(app)?, &request.file_path, &pages)?;
+
+    let (profile, ai_client) = client_for_profile(app, vault, request.profile.as_deref())?;
+
+    let source = SourceFingerprint::for_file(&request.file_path)?.with_pages(&pages);
+    let index_path = index_file_path(&app_data_subdir(app, PDF_INDEX_DIR)?, &source);
+
+    // The index holds the decrypted text, so for password-protected files it
+    // is kept in memory for the session and never written to disk.
+    let stored = match &request.password {
+        Some(password) => index_cache.take(&source, password)?,
+        None => DocumentIndex::load(&index_path, &source),
+    };
+    let (mut index, mut dirty) = match stored {
+        Some(index) => (index, false),
+        None => {
+            let file_path = request.file_path.clone();
+            let password = request.password.clone();
+            let pages = run_blocking(move || {
+                PdfProcessor::extract_pages(&file_path, password.as_deref(), &pages)
+            })
+            .await?;
+            (DocumentIndex::build(source, &pages), true)
+        }
+    };
+
+    let mut query_embedding = None;
+    let embedding_model = request.embedding_model.or(profile.embedding_model);
+    if let Some(model) = embedding_model.filter(|m| !m.trim().is_empty()) {
+        let options = EmbeddingOptions {
+            model: Some(model.clone()),
+            ..Default::default()
+        };
+
+        if !index.has_embeddings_for(&model) {
+            let texts: Vec<String> = index.chunks.iter().map(|c| c.text.clone()).collect();
+            match ai_client.embed(&texts, &options).await {
+                Ok(embeddings) => {
+                    index.embeddings = Some(ChunkEmbeddings {
+                        model: model.clone(),
+                        vectors: embeddings.vectors,
+                    });
+                    dirty = true;
+                }
+                Err(e) => eprintln!("Warning: Falling back to keyword search: {}", e),
+            }
+        }
+
+        if index.has_embeddings_for(&model) {
+            match ai_client
+                .embed(std::slice::from_ref(&request.question), &options)
+                .await
+            {
+                Ok(embeddings) => query_embedding = embeddings.vectors.into_iter().next(),
+                Err(e) => eprintln!("Warning: Falling back to keyword search: {}", e),
+            }
+        }
+    }
+
+    let top_k = request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+    let passages = index.search(&request.question, query_embedding.as_deref(), top_k);
+
+    match request.password {
+        Some(password) => index_cache.store(index, password)?,
+        None if dirty => {
+            if let Err(e) = index.save(&index_path) {
+                eprintln!("Warning: Failed to save PDF index: {}", e);
+            }
+        }
+        None => {}
+    }
+
+    if passages.is_empty() {
+        return Err(AppError::PdfError(
+            "No passages in the PDF match this question".to_string(),
+        ));
+    }
+
+    let prompt = build_question_prompt(&request.question, &passages, CitationUnit::Page);
+    let answer = ai_client.chat(&prompt).await?.output;
+
+    let citations = parse_citations(&answer, CitationUnit::Page)
+        .into_iter()
+        .filter_map(|page| {
+            passages
+                .iter()
+                .find(|p| p.chunk.page == page)
+                .map(|p| PdfCitation {
+                    page,
+                    excerpt: excerpt(&p.chunk.text, CITATION_EXCERPT_CHARS),
+                })
+        })
+        .collect();
+
+    Ok(AskPdfResponse {
+        answer,
+        citations,
+        passages: passages
+            .into_iter()
+            .map(|p| PdfPassage {
+                page: p.chunk.page,
+                text: p.chunk.text,
+                score: p.score,
+            })
+            .collect(),
+        success: true,
+        error: None,
+    })
+}
+
+/// The PDF's bookmarks as a nested table of contents with page ranges.
+#[tauri::command]
+pub async fn extract_pdf_outline(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfOutlineResponse, String> {
+    let outline = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || PdfProcessor::get_outline(&file_path, password.as_deref(), &pages))
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match outline {
+        Ok(outline) => Ok(PdfOutlineResponse {
+            outline: outline.entries,
+            page_count: outline.page_count,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfOutlineResponse {
+            outline: Vec::new(),
+            page_count: 0,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Summarizes outline sections one by one, emitting
+/// `pdf-section-summary-progress` after each, or all chosen sections
+/// together when `combined` is set.
+#[tauri::command]
+pub async fn summarize_pdf_sections(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    section_request: PdfSectionSummaryRequest,
+) -> Result<PdfSectionSummaryResponse, String> {
+    match summarize_sections(&app, &vault, section_request).await {
+        Ok((sections, summary)) => Ok(PdfSectionSummaryResponse {
+            sections,
+            summary,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfSectionSummaryResponse {
+            sections: Vec::new(),
+            summary: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+async fn summarize_sections(
+    app: &AppHandle,
+    vault: &VaultState,
+    request: PdfSectionSummaryRequest,
+) -> Result<(Vec<SectionSummary>, Option<String>), AppError> {
+    let settings = load_settings(app)?;
+    PdfProcessor::validate_open_size(&request.file_path, settings.max_pdf_open_bytes)?;
+    if request.section_ids.as_ref().is_none_or(Vec::is_empty) {
+        PdfProcessor::validate_file_size(&request.file_path, settings.max_pdf_size_bytes)?;
+    }
+    let file_path = request.file_path.clone();
+    let password = request.password.clone();
+    let outline = run_blocking(move || {
+        PdfProcessor::get_outline(&file_path, password.as_deref(), &PageSelection::all())
+    })
+    .await?
+    .entries;
+    if outline.is_empty() {
+        return Err(AppError::PdfError(
+            "PDF has no outline (bookmarks) to split into sections".to_string(),
+        ));
+    }
+
+    let chosen: Vec<&TocEntry> = match &request.section_ids {
+        Some(ids) if !ids.is_empty() => ids
+            .iter()
+            .map(|id| {
+                TocEntry::find(&outline, id)
+                    .ok_or_else(|| AppError::PdfError(format!("Unknown section '{}'", id)))
+            })
+            .collect::<Result<_, _>>()?,
+        _ => outline.iter().collect(),
+    };
+
+    let prompt = request
+        .prompt
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(settings.summary_prompt);
+    // Only the pages the chosen sections span are read.
+    let ranges: Vec<(u32, u32)> = chosen
+        .iter()
+        .filter_map(|entry| Some((entry.start_page?, entry.end_page?)))
+        .collect();
+    let pages = if ranges.is_empty() {
+        Vec::new()
+    } else {
+        let file_path = request.file_path.clone();
+        let password = request.password.clone();
+        run_blocking(move || {
+            PdfProcessor::extract_pages(
+                &file_path,
+                password.as_deref(),
+                &PageSelection::from_ranges(ranges),
+            )
+        })
+        .await?
+    };
+    let (_, ai_client) = client_for_profile(app, vault, request.profile.as_deref())?;
+
+    // The entry after each section, for trimming a shared last page.
+    let flat = TocEntry::flatten(&outline);
+    let next_of = |entry: &TocEntry| {
+        flat.iter()
+            .skip_while(|e| e.id != entry.id)
+            .skip(1)
+            .find(|e| e.level <= entry.level)
+            .copied()
+    };
+
+    if request.combined {
+        let text = chosen
+            .iter()
+            .map(|entry| {
+                format!(
+                    "## {}\n\n{}",
+                    entry.title,
+                    section_text(&pages, entry, next_of(entry))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let summary = ai_client.summarize(&text, &prompt).await?;
+        return Ok((Vec::new(), Some(summary)));
+    }
+
+    let mut sections = Vec::with_capacity(chosen.len());
+    for (index, entry) in chosen.iter().enumerate() {
+        let text = section_text(&pages, entry, next_of(entry));
+        let result = if text.is_empty() {
+            Err(AppError::PdfError(
+                "Section has no readable text".to_string(),
+            ))
+        } else {
+            let section_prompt = format!(
+                "{}\n\nThe text is the section \"{}\" of a longer document; summarize only this section.",
+                prompt, entry.title
+            );
+            ai_client.summarize(&text, &section_prompt).await
+        };
+
+        let (summary, error) = match result {
+            Ok(summary) => (summary, None),
+            Err(e) => (String::new(), Some(e.to_string())),
+        };
+        sections.push(SectionSummary {
+            id: entry.id.clone(),
+            title: entry.title.clone(),
+            start_page: entry.start_page,
+            end_page: entry.end_page,
+            summary,
+            error,
+        });
+
+        let _ = app.emit(
+            SECTION_SUMMARY_PROGRESS_EVENT,
+            SectionSummaryProgress {
+                completed: index + 1,
+                total: chosen.len(),
+                section_id: entry.id.clone(),
+            },
+        );
+    }
+
+    Ok((sections, None))
+}
+
+/// Saves the images on the selected pages to the app's asset directory
+/// and lists every image found, with the reason for any it could not save.
+#[tauri::command]
+pub async fn extract_pdf_images(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfImagesResponse, String> {
+    let result = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                let directory = app_data_subdir(&app, PDF_ASSET_DIR)?;
+                let images = PdfProcessor::extract_images(
+                    &file_path,
+                    password.as_deref(),
+                    &pages,
+                    &directory,
+                )?;
+                Ok((images, directory))
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((images, directory)) => Ok(PdfImagesResponse {
+            images,
+            directory: Some(directory.to_string_lossy().to_string()),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfImagesResponse {
+            images: Vec::new(),
+            directory: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Detects tables on the selected pages and returns their cells along with
+/// TipTap table HTML.
+#[tauri::command]
+pub async fn extract_pdf_tables(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfTablesResponse, String> {
+    let tables = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::extract_tables(&file_path, password.as_deref(), &pages)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match tables {
+        Ok(tables) => Ok(PdfTablesResponse {
+            tables,
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfTablesResponse {
+            tables: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Inserts one of the tables found by `extract_pdf_tables` into the
+/// document HTML and returns the updated document.
+#[tauri::command]
+pub async fn insert_pdf_table(
+    app: AppHandle,
+    insert_request: InsertPdfTableRequest,
+) -> Result<InsertPdfTableResponse, String> {
+    let table = match open_selection(&app, &insert_request.file_path, None) {
+        Ok(_) => {
+            let file_path = insert_request.file_path.clone();
+            let password = insert_request.password.clone();
+            let page_number = insert_request.page_number;
+            let table_index = insert_request.table_index;
+            run_blocking(move || {
+                PdfProcessor::find_table(&file_path, password.as_deref(), page_number, table_index)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match table {
+        Ok(table) => Ok(InsertPdfTableResponse {
+            html: Some(insert_block(
+                &insert_request.html,
+                &table.html,
+                insert_request.position,
+            )),
+            table: Some(table),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(InsertPdfTableResponse {
+            html: None,
+            table: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Reads the highlights and notes on the selected pages as comment
+/// threads, with replies grouped under the annotation they answer.
+#[tauri::command]
+pub async fn import_pdf_annotations(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+) -> Result<PdfAnnotationsResponse, String> {
+    let annotations = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                PdfProcessor::read_annotations(&file_path, password.as_deref(), &pages)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match annotations {
+        Ok(annotations) => Ok(PdfAnnotationsResponse {
+            threads: threads_from_annotations(&annotations),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfAnnotationsResponse {
+            threads: Vec::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Converts the selected pages into editor HTML and saves it to
+/// `output_path`, or to a new file in the app's document directory named
+/// after the PDF. The saved file opens through `load_document`.
+#[tauri::command]
+pub async fn convert_pdf_to_document(
+    app: AppHandle,
+    file_path: String,
+    password: Option<String>,
+    pages: Option<String>,
+    output_path: Option<String>,
+) -> Result<PdfDocumentResponse, String> {
+    let result = match open_selection(&app, &file_path, pages.as_deref()) {
+        Ok(pages) => {
+            run_blocking(move || {
+                let document =
+                    PdfProcessor::convert_to_html(&file_path, password.as_deref(), &pages)?;
+                let path = match &output_path {
+                    Some(path) => PathBuf::from(path),
+                    None => {
+                        new_document_path(&app_data_subdir(&app, PDF_DOCUMENT_DIR)?, &file_path)
+                    }
+                };
+                std::fs::write(&path, &document.html)?;
+                Ok((document, path))
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((document, path)) => Ok(PdfDocumentResponse {
+            document: Some(document),
+            path: Some(path.to_string_lossy().to_string()),
+            success: true,
+            error: None,
+        }),
+        Err(e) => Ok(PdfDocumentResponse {
+            document: None,
+            path: None,
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// `<stem>.html` in `dir`, numbered to avoid replacing a document
+/// converted earlier.
+fn new_document_path(dir: &Path, file_path: &str) -> PathBuf {
+    let stem = Path::new(file_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "document".to_string());
+    let mut path = dir.join(format!("{}.html", stem));
+    let mut number = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.html", stem, number));
+        number += 1;
+    }
+    path
+}
@@ -0,0 +1,90 @@
+// src-tauri/src/commands/prompt_commands.rs
+use crate::prompts::library_path;
+use crate::prompts::store::{ImportSummary, PromptLibrary};
+use crate::prompts::template::PromptTemplate;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_prompt_templates(app: AppHandle) -> Result<Vec<PromptTemplate>, String> {
+    let library = PromptLibrary::load(&library_path(&app)?)?;
+    Ok(library.templates)
+}
+
+#[tauri::command]
+pub async fn create_prompt_template(
+    app: AppHandle,
+    name: String,
+    description: Option<String>,
+    content: String,
+) -> Result<PromptTemplate, String> {
+    let template = PromptTemplate::new(name, description, content)?;
+    Ok(PromptLibrary::modify(&library_path(&app)?, |library| {
+        Ok(library.create(template)?.clone())
+    })?)
+}
+
+#[tauri::command]
+pub async fn update_prompt_template(
+    app: AppHandle,
+    name: String,
+    new_name: Option<String>,
+    description: Option<String>,
+    content: Option<String>,
+) -> Result<PromptTemplate, String> {
+    Ok(PromptLibrary::modify(&library_path(&app)?, |library| {
+        Ok(library
+            .update(&name, new_name, description, content)?
+            .clone())
+    })?)
+}
+
+#[tauri::command]
+pub async fn delete_prompt_template(app: AppHandle, name: String) -> Result<(), String> {
+    Ok(PromptLibrary::modify(&library_path(&app)?, |library| {
+        library.delete(&name)
+    })?)
+}
+
+#[tauri::command]
+pub async fn render_prompt_template(
+    app: AppHandle,
+    name: String,
+    variables: HashMap<String, String>,
+) -> Result<String, String> {
+    let library = PromptLibrary::load(&library_path(&app)?)?;
+    Ok(library.get(&name)?.render(&variables)?)
+}
+
+#[tauri::command]
+pub async fn export_prompt_templates(
+    app: AppHandle,
+    file_path: Option<String>,
+) -> Result<String, String> {
+    let library = PromptLibrary::load(&library_path(&app)?)?;
+    let json = library.export_json()?;
+    if let Some(file_path) = file_path {
+        std::fs::write(&file_path, &json)
+            .map_err(|e| format!("Failed to export templates: {}", e))?;
+    }
+    Ok(json)
+}
+
+#[tauri::command]
+pub async fn import_prompt_templates(
+    app: AppHandle,
+    json: Option<String>,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<ImportSummary, String> {
+    let json = match (json, file_path) {
+        (Some(json), _) => json,
+        (None, Some(file_path)) => std::fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read templates: {}", e))?,
+        (None, None) => return Err("Provide either template JSON or a file path".to_string()),
+    };
+
+    Ok(PromptLibrary::modify(&library_path(&app)?, |library| {
+        library.import_json(&json, overwrite.unwrap_or(false))
+    })?)
+}
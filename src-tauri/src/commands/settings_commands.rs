@@ -0,0 +1,76 @@
+// src-tauri/src/commands/settings_commands.rs
+use crate::settings::models::{AppSettings, ProviderProfile};
+use crate::settings::{load_settings, settings_path};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn get_ai_settings(app: AppHandle) -> Result<AppSettings, String> {
+    Ok(load_settings(&app)?)
+}
+
+#[tauri::command]
+pub async fn save_ai_profile(
+    app: AppHandle,
+    profile: ProviderProfile,
+    original_name: Option<String>,
+) -> Result<AppSettings, String> {
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.upsert_profile(profile, original_name.as_deref())?;
+    settings.save(&path)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn delete_ai_profile(app: AppHandle, name: String) -> Result<AppSettings, String> {
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.delete_profile(&name)?;
+    settings.save(&path)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn set_active_ai_profile(app: AppHandle, name: String) -> Result<AppSettings, String> {
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.set_active_profile(&name)?;
+    settings.save(&path)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn set_summary_prompt(app: AppHandle, prompt: String) -> Result<AppSettings, String> {
+    if prompt.trim().is_empty() {
+        return Err("Prompt cannot be empty".to_string());
+    }
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.summary_prompt = prompt;
+    settings.save(&path)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn set_max_pdf_size(app: AppHandle, max_bytes: u64) -> Result<AppSettings, String> {
+    if max_bytes == 0 {
+        return Err("The PDF size limit must be positive".to_string());
+    }
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.max_pdf_size_bytes = max_bytes;
+    settings.save(&path)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn set_max_pdf_open_size(app: AppHandle, max_bytes: u64) -> Result<AppSettings, String> {
+    if max_bytes == 0 {
+        return Err("The PDF open size limit must be positive".to_string());
+    }
+    let path = settings_path(&app)?;
+    let mut settings = AppSettings::load(&path)?;
+    settings.max_pdf_open_bytes = max_bytes;
+    settings.save(&path)?;
+    Ok(settings)
+}
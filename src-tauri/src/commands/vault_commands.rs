@@ -0,0 +1,78 @@
+// src-tauri/src/commands/vault_commands.rs
+use super::models::VaultStatus;
+use crate::errors::AppError;
+use crate::secrets::state::VaultState;
+use crate::secrets::vault::UnlockedVault;
+use crate::secrets::vault_path;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_vault_status(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+) -> Result<VaultStatus, String> {
+    Ok(VaultStatus {
+        exists: vault_path(&app)?.exists(),
+        unlocked: vault.is_unlocked(),
+    })
+}
+
+/// Unlocks the vault, creating it with this passphrase on first use. The
+/// key derivation is deliberately slow, so it runs on a blocking thread.
+#[tauri::command]
+pub async fn unlock_vault(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    passphrase: String,
+) -> Result<VaultStatus, String> {
+    let path = vault_path(&app)?;
+    let unlocked = tauri::async_runtime::spawn_blocking(move || {
+        if path.exists() {
+            UnlockedVault::open(&path, &passphrase)
+        } else {
+            UnlockedVault::create(&path, &passphrase)
+        }
+    })
+    .await
+    .map_err(|e| AppError::VaultError(format!("Unlocking the vault failed: {}", e)))??;
+    vault.unlock(unlocked);
+
+    Ok(VaultStatus {
+        exists: true,
+        unlocked: true,
+    })
+}
+
+#[tauri::command]
+pub async fn lock_vault(vault: State<'_, VaultState>) -> Result<(), String> {
+    vault.lock();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_api_key(
+    vault: State<'_, VaultState>,
+    name: String,
+    value: String,
+) -> Result<Vec<String>, String> {
+    Ok(vault.with_vault(|v| {
+        v.set(&name, &value)?;
+        Ok(v.names())
+    })?)
+}
+
+#[tauri::command]
+pub async fn remove_api_key(
+    vault: State<'_, VaultState>,
+    name: String,
+) -> Result<Vec<String>, String> {
+    Ok(vault.with_vault(|v| {
+        v.remove(&name)?;
+        Ok(v.names())
+    })?)
+}
+
+#[tauri::command]
+pub async fn list_api_key_names(vault: State<'_, VaultState>) -> Result<Vec<String>, String> {
+    Ok(vault.with_vault(|v| Ok(v.names()))?)
+}
@@ -0,0 +1,115 @@
+// src-tauri/src/comments/anchors.rs
+use crate::html::tokenizer::{tokenize, Token};
+use crate::html::{is_block_element, is_void_element};
+use std::collections::HashMap;
+
+/// The text a thread's comment marks cover and the blocks they sit in.
+#[derive(Debug, Default, Clone)]
+pub struct ThreadAnchor {
+    pub text: String,
+    pub context: String,
+}
+
+/// Finds every `span[data-thread-id]` comment mark in the document HTML,
+/// joining the text of marks that share a thread.
+pub fn collect_anchors(html: &str) -> HashMap<String, ThreadAnchor> {
+    let mut anchors: HashMap<String, ThreadAnchor> = HashMap::new();
+    let mut open: Vec<(String, Option<String>)> = Vec::new();
+    let mut block = String::new();
+    let mut block_threads: Vec<String> = Vec::new();
+
+    let flush = |block: &mut String,
+                 block_threads: &mut Vec<String>,
+                 anchors: &mut HashMap<String, ThreadAnchor>| {
+        let text = block.trim();
+        for thread_id in block_threads.drain(..) {
+            let anchor = anchors.entry(thread_id).or_default();
+            if !anchor.context.is_empty() {
+                anchor.context.push('\n');
+            }
+            anchor.context.push_str(text);
+        }
+        block.clear();
+    };
+
+    for token in tokenize(html) {
+        match token {
+            Token::StartTag {
+                ref name,
+                self_closing,
+                ..
+            } => {
+                if is_block_element(name) {
+                    flush(&mut block, &mut block_threads, &mut anchors);
+                }
+                if name == "br" {
+                    block.push('\n');
+                    push_to_threads(&open, &mut anchors, "\n");
+                }
+                if self_closing || is_void_element(name) {
+                    continue;
+                }
+                let thread_id = token
+                    .attribute("data-thread-id")
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string);
+                if let Some(thread_id) = &thread_id {
+                    // Separate disjoint marks of the same thread.
+                    let reopened = !active_threads(&open).contains(&thread_id);
+                    if let Some(anchor) = anchors.get_mut(thread_id).filter(|_| reopened) {
+                        if !anchor.text.is_empty() {
+                            anchor.text.push_str(" … ");
+                        }
+                    }
+                }
+                open.push((name.clone(), thread_id));
+            }
+            Token::EndTag { name } => {
+                if let Some(index) = open.iter().rposition(|(open_name, _)| *open_name == name) {
+                    open.truncate(index);
+                }
+                if is_block_element(&name) {
+                    flush(&mut block, &mut block_threads, &mut anchors);
+                }
+            }
+            Token::Text(text) => {
+                block.push_str(&text);
+                push_to_threads(&open, &mut anchors, &text);
+                for thread_id in active_threads(&open) {
+                    if !block_threads.contains(thread_id) {
+                        block_threads.push(thread_id.clone());
+                    }
+                }
+            }
+            Token::Other(_) => {}
+        }
+    }
+    flush(&mut block, &mut block_threads, &mut anchors);
+
+    anchors
+}
+
+fn push_to_threads(
+    open: &[(String, Option<String>)],
+    anchors: &mut HashMap<String, ThreadAnchor>,
+    text: &str,
+) {
+    for thread_id in active_threads(open) {
+        anchors
+            .entry(thread_id.clone())
+            .or_default()
+            .text
+            .push_str(text);
+    }
+}
+
+/// Thread ids of the open comment marks, each once even when marks nest.
+fn active_threads(open: &[(String, Option<String>)]) -> Vec<&String> {
+    let mut threads: Vec<&String> = Vec::new();
+    for thread_id in open.iter().filter_map(|(_, id)| id.as_ref()) {
+        if !threads.contains(&thread_id) {
+            threads.push(thread_id);
+        }
+    }
+    threads
+}
@@ -0,0 +1,102 @@
+// src-tauri/src/comments/assistant.rs
+use super::models::CommentThread;
+use crate::errors::AppError;
+use crate::llm::structured::parse_json_output;
+use crate::llm::truncate_text;
+use serde::{Deserialize, Serialize};
+
+const MAX_CONTEXT_CHARS: usize = 4000;
+
+/// What the assistant recommends doing with a thread.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum CommentSuggestion {
+    /// Post `content` as a reply in the thread.
+    Reply { content: String },
+    /// Replace the commented text with `replacement`, optionally replying.
+    Edit {
+        replacement: String,
+        reply: Option<String>,
+    },
+    /// The discussion is settled and the thread can be resolved.
+    Resolve { reason: String },
+}
+
+#[derive(Deserialize)]
+struct ModelSuggestion {
+    action: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    replacement: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+pub fn build_assist_prompt(
+    thread: &CommentThread,
+    anchored_text: &str,
+    context: Option<&str>,
+) -> String {
+    let mut prompt = String::from(
+        "You are helping an author work through review comments on their document. \
+Read the comment thread and decide on the single most useful next step:\n\
+- \"reply\": answer the comment or ask a clarifying question\n\
+- \"edit\": rewrite the commented text so the comment is addressed\n\
+- \"resolve\": the thread is already addressed or needs no action\n\
+Respond with one JSON object only, in one of these forms:\n\
+{\"action\": \"reply\", \"content\": \"<reply>\"}\n\
+{\"action\": \"edit\", \"replacement\": \"<new text for the commented passage>\", \"content\": \"<optional short reply explaining the change>\"}\n\
+{\"action\": \"resolve\", \"reason\": \"<why the thread can be resolved>\"}\n\n",
+    );
+
+    if let Some(context) = context.filter(|c| !c.trim().is_empty()) {
+        prompt.push_str(&format!(
+            "Surrounding document context:\n{}\n\n",
+            truncate_text(context.trim(), MAX_CONTEXT_CHARS)
+        ));
+    }
+
+    prompt.push_str(&format!(
+        "Commented text:\n{}\n\nThread:\n",
+        anchored_text.trim()
+    ));
+    for comment in thread.visible_comments() {
+        prompt.push_str(&format!(
+            "[{} at {}]: {}\n",
+            comment.user_id,
+            comment.created_at.format("%Y-%m-%d %H:%M"),
+            comment.content.trim()
+        ));
+    }
+
+    prompt
+}
+
+pub fn parse_suggestion(output: &str) -> Result<CommentSuggestion, AppError> {
+    let suggestion: ModelSuggestion = parse_json_output(output)?;
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    match suggestion.action.trim().to_lowercase().as_str() {
+        "reply" => non_empty(suggestion.content)
+            .map(|content| CommentSuggestion::Reply { content })
+            .ok_or_else(|| AppError::AiError("The suggested reply was empty".to_string())),
+        "edit" => non_empty(suggestion.replacement)
+            .map(|replacement| CommentSuggestion::Edit {
+                replacement,
+                reply: non_empty(suggestion.content),
+            })
+            .ok_or_else(|| AppError::AiError("The suggested edit was empty".to_string())),
+        "resolve" => Ok(CommentSuggestion::Resolve {
+            reason: non_empty(suggestion.reason).unwrap_or_default(),
+        }),
+        other => Err(AppError::AiError(format!(
+            "Unknown comment action suggested: {}",
+            other
+        ))),
+    }
+}
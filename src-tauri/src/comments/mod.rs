@@ -0,0 +1,5 @@
+// src-tauri/src/comments/mod.rs
+pub mod anchors;
+pub mod assistant;
+pub mod models;
+pub mod pdf_import;
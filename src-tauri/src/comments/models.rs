@@ -0,0 +1,46 @@
+// src-tauri/src/comments/models.rs
+//! Mirrors the `Comment` and `CommentThread` types of
+//! `@tiptaptoe/extension-comments`.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub content: String,
+    pub thread_id: String,
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+}
+
+impl CommentThread {
+    pub fn visible_comments(&self) -> impl Iterator<Item = &Comment> {
+        self.comments.iter().filter(|c| c.deleted_at.is_none())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.resolved_at.is_none() && self.visible_comments().next().is_some()
+    }
+}
@@ -0,0 +1,197 @@
+// src-tauri/src/comments/pdf_import.rs
+//! Turns PDF review annotations into comment threads. Each highlight or
+//! note starts a thread; replies (annotations with `/IRT`) join their
+//! parent's thread, and review-state replies resolve it.
+use super::models::{Comment, CommentThread};
+use crate::pdf::pdf_annotations::{AnnotationKind, PdfAnnotation};
+use chrono::{DateTime, Utc};
+use lopdf::ObjectId;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Used as the comment's user when the annotation names no author.
+const UNKNOWN_AUTHOR: &str = "unknown";
+/// Review states that close a thread.
+const RESOLVING_STATES: [&str; 2] = ["Accepted", "Completed"];
+/// Reply chains are followed at most this far to find their thread.
+const MAX_REPLY_DEPTH: usize = 32;
+
+pub fn threads_from_annotations(annotations: &[PdfAnnotation]) -> Vec<CommentThread> {
+    let by_id: HashMap<ObjectId, &PdfAnnotation> = annotations.iter().map(|a| (a.id, a)).collect();
+    let root_of = |annotation: &PdfAnnotation| {
+        let mut current = annotation;
+        for _ in 0..MAX_REPLY_DEPTH {
+            match current.in_reply_to.and_then(|id| by_id.get(&id)) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current.id
+    };
+
+    let mut replies: HashMap<ObjectId, Vec<&PdfAnnotation>> = HashMap::new();
+    let mut roots = Vec::new();
+    for annotation in annotations {
+        let root = root_of(annotation);
+        if root == annotation.id {
+            roots.push(annotation);
+        } else {
+            replies.entry(root).or_default().push(annotation);
+        }
+    }
+
+    roots
+        .into_iter()
+        .filter_map(|root| {
+            let mut thread_replies = replies.remove(&root.id).unwrap_or_default();
+            thread_replies.sort_by_key(|reply| date_of(reply));
+            build_thread(root, &thread_replies)
+        })
+        .collect()
+}
+
+fn build_thread(root: &PdfAnnotation, replies: &[&PdfAnnotation]) -> Option<CommentThread> {
+    let thread_id = Uuid::new_v4().to_string();
+    let created_at = date_of(root).unwrap_or_else(Utc::now);
+
+    let (state_changes, discussion): (Vec<&PdfAnnotation>, Vec<&PdfAnnotation>) =
+        replies.iter().partition(|reply| reply.state.is_some());
+    // Markup without a note opens its thread with the marked text, since a
+    // thread with no comments counts as closed and would never be shown.
+    let root_content = root.contents.clone().or_else(|| {
+        root.kind.is_text_markup().then(|| {
+            root.quoted_text
+                .clone()
+                .filter(|text| !text.trim().is_empty())
+                .unwrap_or_else(|| markup_label(root.kind).to_string())
+        })
+    });
+    let comments: Vec<Comment> = root_content
+        .map(|content| comment(root, content, &thread_id, created_at))
+        .into_iter()
+        .chain(discussion.into_iter().filter_map(|reply| {
+            let content = reply.contents.clone()?;
+            Some(comment(reply, content, &thread_id, created_at))
+        }))
+        .collect();
+    // A note with no text and no replies has nothing to show.
+    if comments.is_empty() {
+        return None;
+    }
+
+    // The latest review state decides whether the thread is resolved.
+    let resolution = state_changes
+        .last()
+        .filter(|change| RESOLVING_STATES.contains(&change.state.as_deref().unwrap_or_default()));
+
+    Some(CommentThread {
+        id: thread_id,
+        created_at,
+        resolved_at: resolution.map(|change| date_of(change).unwrap_or(created_at)),
+        resolved_by: resolution.and_then(|change| change.author.clone()),
+        data: Some(json!({
+            "source": "pdf",
+            "kind": root.kind,
+            "page": root.page_number,
+            "quotedText": root.quoted_text,
+            "color": root.color,
+            "author": root.author,
+        })),
+        comments,
+    })
+}
+
+fn comment(
+    annotation: &PdfAnnotation,
+    content: String,
+    thread_id: &str,
+    fallback_date: DateTime<Utc>,
+) -> Comment {
+    Comment {
+        id: Uuid::new_v4().to_string(),
+        content,
+        thread_id: thread_id.to_string(),
+        user_id: annotation
+            .author
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_AUTHOR.to_string()),
+        created_at: annotation.created.unwrap_or(fallback_date),
+        // A modification date only counts as an edit next to a creation date.
+        updated_at: annotation.modified.filter(|modified| {
+            annotation
+                .created
+                .is_some_and(|created| created != *modified)
+        }),
+        deleted_at: None,
+        data: Some(json!({ "author": annotation.author })),
+    }
+}
+
+/// Stands in for the comment of markup whose text could not be read.
+fn markup_label(kind: AnnotationKind) -> &'static str {
+    match kind {
+        AnnotationKind::Underline => "Underlined",
+        AnnotationKind::StrikeOut => "Struck out",
+        AnnotationKind::Squiggly => "Marked",
+        _ => "Highlighted",
+    }
+}
+
+fn date_of(annotation: &PdfAnnotation) -> Option<DateTime<Utc>> {
+    annotation.created.or(annotation.modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(number: u32, kind: AnnotationKind) -> PdfAnnotation {
+        PdfAnnotation {
+            id: (number, 0),
+            page_number: 1,
+            kind,
+            author: Some("Ana".to_string()),
+            contents: None,
+            created: None,
+            modified: None,
+            quoted_text: None,
+            color: None,
+            in_reply_to: None,
+            state: None,
+        }
+    }
+
+    #[test]
+    fn highlight_without_a_note_opens_with_the_quoted_text() {
+        let mut quoted = annotation(1, AnnotationKind::Highlight);
+        quoted.quoted_text = Some("marked words".to_string());
+        let bare = annotation(2, AnnotationKind::Underline);
+
+        let threads = threads_from_annotations(&[quoted, bare]);
+        assert_eq!(threads.len(), 2);
+        assert!(threads.iter().all(CommentThread::is_open));
+        assert_eq!(threads[0].comments[0].content, "marked words");
+        assert_eq!(threads[0].comments[0].user_id, "Ana");
+        assert_eq!(threads[1].comments[0].content, "Underlined");
+    }
+
+    #[test]
+    fn empty_notes_are_dropped_and_replies_join_their_thread() {
+        let empty_note = annotation(1, AnnotationKind::Text);
+        let mut note = annotation(2, AnnotationKind::Text);
+        note.contents = Some("Check this".to_string());
+        let mut reply = annotation(3, AnnotationKind::Text);
+        reply.contents = Some("Done".to_string());
+        reply.in_reply_to = Some((2, 0));
+
+        let threads = threads_from_annotations(&[empty_note, note, reply]);
+        assert_eq!(threads.len(), 1);
+        let contents: Vec<&str> = threads[0]
+            .comments
+            .iter()
+            .map(|c| c.content.as_str())
+            .collect();
+        assert_eq!(contents, ["Check this", "Done"]);
+    }
+}
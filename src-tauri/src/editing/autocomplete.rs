@@ -0,0 +1,86 @@
+// src-tauri/src/editing/autocomplete.rs
+use crate::errors::AppError;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use tokio::sync::oneshot;
+
+const CACHE_CAPACITY: usize = 64;
+
+/// Characters of context sent around the cursor; more only adds latency.
+pub const MAX_PREFIX_CHARS: usize = 2000;
+pub const MAX_SUFFIX_CHARS: usize = 500;
+
+pub const DEFAULT_COMPLETION_TOKENS: u32 = 32;
+pub const MAX_COMPLETION_TOKENS: u32 = 128;
+
+/// Tracks the in-flight completion so a newer keystroke cancels it, and
+/// remembers recent completions by prefix.
+#[derive(Default)]
+pub struct AutocompleteState {
+    in_flight: Mutex<Option<oneshot::Sender<()>>>,
+    cache: Mutex<VecDeque<(String, String)>>,
+}
+
+impl AutocompleteState {
+    /// Registers a new request, cancelling the previous one. The returned
+    /// receiver fires when this request is superseded in turn.
+    pub fn begin(&self) -> Result<oneshot::Receiver<()>, AppError> {
+        let (sender, receiver) = oneshot::channel();
+        if let Some(previous) = lock(&self.in_flight)?.replace(sender) {
+            let _ = previous.send(());
+        }
+        Ok(receiver)
+    }
+
+    pub fn cached(&self, key: &str) -> Result<Option<String>, AppError> {
+        let mut cache = lock(&self.cache)?;
+        let index = cache.iter().position(|(k, _)| k == key);
+        let Some(entry) = index.and_then(|index| cache.remove(index)) else {
+            return Ok(None);
+        };
+        let completion = entry.1.clone();
+        cache.push_front(entry);
+        Ok(Some(completion))
+    }
+
+    pub fn remember(&self, key: String, completion: String) -> Result<(), AppError> {
+        let mut cache = lock(&self.cache)?;
+        cache.retain(|(k, _)| *k != key);
+        cache.push_front((key, completion));
+        cache.truncate(CACHE_CAPACITY);
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, AppError> {
+    mutex
+        .lock()
+        .map_err(|_| AppError::AiError("Autocomplete state is unavailable".to_string()))
+}
+
+pub fn cache_key(profile: &str, model: &str, prefix: &str, suffix: Option<&str>) -> String {
+    format!(
+        "{}\u{0}{}\u{0}{}\u{0}{}",
+        profile,
+        model,
+        prefix,
+        suffix.unwrap_or_default()
+    )
+}
+
+/// The last `max_chars` characters of `text`.
+pub fn tail_chars(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    match text.char_indices().nth(count.saturating_sub(max_chars)) {
+        Some((start, _)) if count > max_chars => &text[start..],
+        _ => text,
+    }
+}
+
+/// The first `max_chars` characters of `text`.
+pub fn head_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
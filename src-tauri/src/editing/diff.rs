@@ -0,0 +1,220 @@
+// src-tauri/src/editing/diff.rs
+use regex::Regex;
+use serde::Serialize;
+
+/// Above this many token pairs the LCS table gets too large and the
+/// differing middle is reported as a single change.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DiffHunk {
+    Equal {
+        text: String,
+    },
+    Change {
+        id: usize,
+        original: String,
+        replacement: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Word-level diff of `original` against `revised`. Words, whitespace runs
+/// and punctuation are separate tokens, and adjacent edits separated only
+/// by whitespace are merged so each change reads as one phrase.
+pub fn word_diff(original: &str, revised: &str) -> Vec<DiffHunk> {
+    let old_tokens = tokenize(original);
+    let new_tokens = tokenize(revised);
+
+    let ops = diff_tokens(&old_tokens, &new_tokens);
+    build_hunks(&ops)
+}
+
+fn tokenize(text: &str) -> Vec<&str> {
+    let token = Regex::new(r"\w+|\s+|[^\w\s]").unwrap();
+    token.find_iter(text).map(|m| m.as_str()).collect()
+}
+
+fn diff_tokens<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(Op, &str)> = old[..prefix].iter().map(|t| (Op::Equal, *t)).collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().map(|t| (Op::Delete, *t)));
+        ops.extend(new_mid.iter().map(|t| (Op::Insert, *t)));
+    } else {
+        ops.extend(lcs_ops(old_mid, new_mid));
+    }
+
+    ops.extend(old[old.len() - suffix..].iter().map(|t| (Op::Equal, *t)));
+    ops
+}
+
+fn lcs_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let width = m + 1;
+    // lengths[i * width + j] = LCS length of old[i..] and new[j..]
+    let mut lengths = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((Op::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            ops.push((Op::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|t| (Op::Delete, *t)));
+    ops.extend(new[j..].iter().map(|t| (Op::Insert, *t)));
+    ops
+}
+
+fn build_hunks(ops: &[(Op, &str)]) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut index = 0;
+
+    while index < ops.len() {
+        if ops[index].0 == Op::Equal {
+            let start = index;
+            while index < ops.len() && ops[index].0 == Op::Equal {
+                index += 1;
+            }
+            let text: String = ops[start..index].iter().map(|(_, t)| *t).collect();
+
+            // Whitespace between two edits is folded into a single change.
+            let between_edits = start > 0 && index < ops.len();
+            if between_edits && text.trim().is_empty() {
+                if let Some(DiffHunk::Change {
+                    original,
+                    replacement,
+                    ..
+                }) = hunks.last_mut()
+                {
+                    original.push_str(&text);
+                    replacement.push_str(&text);
+                    continue;
+                }
+            }
+            hunks.push(DiffHunk::Equal { text });
+        } else {
+            let mut original = String::new();
+            let mut replacement = String::new();
+            while index < ops.len() && ops[index].0 != Op::Equal {
+                match ops[index].0 {
+                    Op::Delete => original.push_str(ops[index].1),
+                    _ => replacement.push_str(ops[index].1),
+                }
+                index += 1;
+            }
+
+            match hunks.last_mut() {
+                Some(DiffHunk::Change {
+                    original: previous_original,
+                    replacement: previous_replacement,
+                    ..
+                }) => {
+                    previous_original.push_str(&original);
+                    previous_replacement.push_str(&replacement);
+                }
+                _ => {
+                    let id = hunks
+                        .iter()
+                        .filter(|h| matches!(h, DiffHunk::Change { .. }))
+                        .count();
+                    hunks.push(DiffHunk::Change {
+                        id,
+                        original,
+                        replacement,
+                    });
+                }
+            }
+        }
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equal(text: &str) -> DiffHunk {
+        DiffHunk::Equal {
+            text: text.to_string(),
+        }
+    }
+
+    fn change(id: usize, original: &str, replacement: &str) -> DiffHunk {
+        DiffHunk::Change {
+            id,
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn changed_words_become_numbered_hunks() {
+        assert_eq!(
+            word_diff("The cat sat on the mat.", "The dog sat on a mat."),
+            [
+                equal("The "),
+                change(0, "cat", "dog"),
+                equal(" sat on "),
+                change(1, "the", "a"),
+                equal(" mat."),
+            ]
+        );
+    }
+
+    #[test]
+    fn edits_separated_by_whitespace_merge() {
+        assert_eq!(
+            word_diff("a quick brown fox", "a slow red fox"),
+            [
+                equal("a "),
+                change(0, "quick brown", "slow red"),
+                equal(" fox")
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_and_empty_texts() {
+        assert_eq!(word_diff("same text", "same text"), [equal("same text")]);
+        assert_eq!(word_diff("", "new"), [change(0, "", "new")]);
+        assert!(word_diff("", "").is_empty());
+    }
+}
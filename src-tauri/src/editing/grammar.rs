@@ -0,0 +1,260 @@
+// src-tauri/src/editing/grammar.rs
+use crate::llm::structured::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const CATEGORIES: &[&str] = &[
+    "spelling",
+    "grammar",
+    "punctuation",
+    "style",
+    "clarity",
+    "word-choice",
+];
+
+/// An issue as reported by the model, before it is anchored in the text.
+#[derive(Deserialize, Debug)]
+pub struct ReportedIssue {
+    pub original: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// The model's reply: `{"issues": [...]}` as asked, or a bare array from
+/// models that ignore the wrapper.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum GrammarReply {
+    Wrapped { issues: Vec<ReportedIssue> },
+    Bare(Vec<ReportedIssue>),
+}
+
+impl GrammarReply {
+    pub fn into_issues(self) -> Vec<ReportedIssue> {
+        match self {
+            GrammarReply::Wrapped { issues } | GrammarReply::Bare(issues) => issues,
+        }
+    }
+}
+
+/// A suggestion anchored to `[from, to)` in the checked text. Offsets are
+/// UTF-16 code units (JavaScript string indices) shifted by the caller's
+/// base offset.
+#[derive(Serialize, Debug)]
+pub struct GrammarSuggestion {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub original: String,
+    pub replacement: String,
+    pub category: String,
+    pub explanation: String,
+}
+
+pub fn build_grammar_prompt(text: &str) -> String {
+    format!(
+        "You are a meticulous copy editor. Find spelling, grammar, punctuation, style, \
+clarity and word-choice problems in the text below.\n\
+Respond with a JSON object only, of the form {{\"issues\": [...]}}. Each issue must be an object with:\n\
+- \"original\": the exact, verbatim substring of the text that is wrong (as short as possible)\n\
+- \"replacement\": the corrected substring\n\
+- \"context\": a verbatim phrase of a few words from the text that contains \"original\"\n\
+- \"category\": one of {}\n\
+- \"explanation\": one short sentence explaining the fix\n\
+Return {{\"issues\": []}} if there are no problems. Do not rewrite text that is already correct.\n\n\
+Text:\n{}",
+        CATEGORIES.join(", "),
+        text
+    )
+}
+
+/// The reply shape in [`build_grammar_prompt`], for providers that can
+/// enforce it. Every field is required, as strict schemas demand.
+pub fn grammar_schema() -> JsonSchema {
+    let text = json!({ "type": "string" });
+    JsonSchema {
+        name: "report_issues",
+        description: "Report the problems found in the text",
+        schema: json!({
+            "type": "object",
+            "properties": {
+                "issues": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "original": text,
+                            "replacement": text,
+                            "context": text,
+                            "category": { "type": "string", "enum": CATEGORIES },
+                            "explanation": text,
+                        },
+                        "required": ["original", "replacement", "context", "category", "explanation"],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+            "required": ["issues"],
+            "additionalProperties": false,
+        }),
+    }
+}
+
+/// Anchors reported issues in `text`, dropping any whose `original` does
+/// not occur verbatim, that change nothing, or that overlap an earlier one.
+pub fn anchor_suggestions(
+    text: &str,
+    issues: Vec<ReportedIssue>,
+    base_offset: usize,
+) -> Vec<GrammarSuggestion> {
+    let mut taken: Vec<(usize, usize)> = Vec::new();
+    let mut suggestions = Vec::new();
+
+    for issue in issues {
+        if issue.original.is_empty() || issue.original == issue.replacement {
+            continue;
+        }
+
+        let Some((start, end)) = locate(text, &issue, &taken) else {
+            continue;
+        };
+        taken.push((start, end));
+
+        let category = issue
+            .category
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| CATEGORIES.contains(&c.as_str()))
+            .unwrap_or_else(|| "other".to_string());
+
+        suggestions.push(GrammarSuggestion {
+            id: 0,
+            from: base_offset + utf16_len(&text[..start]),
+            to: base_offset + utf16_len(&text[..end]),
+            original: issue.original,
+            replacement: issue.replacement,
+            category,
+            explanation: issue.explanation.unwrap_or_default().trim().to_string(),
+        });
+    }
+
+    suggestions.sort_by_key(|s| s.from);
+    for (id, suggestion) in suggestions.iter_mut().enumerate() {
+        suggestion.id = id;
+    }
+    suggestions
+}
+
+/// Returns the byte range of `issue.original`, preferring the occurrence
+/// inside the quoted context and skipping ranges already claimed.
+fn locate(text: &str, issue: &ReportedIssue, taken: &[(usize, usize)]) -> Option<(usize, usize)> {
+    let original = issue.original.as_str();
+    let is_free = |start: usize| {
+        let end = start + original.len();
+        !taken.iter().any(|&(s, e)| start < e && s < end)
+    };
+
+    if let Some(context) = issue.context.as_deref().filter(|c| c.contains(original)) {
+        for (context_start, _) in text.match_indices(context) {
+            for (inner, _) in context.match_indices(original) {
+                let start = context_start + inner;
+                if is_free(start) {
+                    return Some((start, start + original.len()));
+                }
+            }
+        }
+    }
+
+    text.match_indices(original)
+        .map(|(start, _)| start)
+        .find(|&start| is_free(start))
+        .map(|start| (start, start + original.len()))
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::structured::parse_json_output;
+
+    #[test]
+    fn replies_parse_with_or_without_the_wrapper() {
+        let issue = r#"{"original": "teh", "replacement": "the"}"#;
+        for reply in [
+            format!(r#"{{"issues": [{}]}}"#, issue),
+            format!("```json\n[{}]\n```", issue),
+        ] {
+            let issues = parse_json_output::<GrammarReply>(&reply)
+                .unwrap()
+                .into_issues();
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].replacement, "the");
+        }
+        let empty = parse_json_output::<GrammarReply>(r#"{"issues": []}"#).unwrap();
+        assert!(empty.into_issues().is_empty());
+    }
+
+    fn issue(original: &str, replacement: &str, context: Option<&str>) -> ReportedIssue {
+        ReportedIssue {
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+            context: context.map(str::to_string),
+            category: Some("Spelling".to_string()),
+            explanation: Some(" Typo. ".to_string()),
+        }
+    }
+
+    #[test]
+    fn suggestions_anchor_in_utf16_offsets() {
+        let text = "Caf\u{e9} 😀 teh end";
+        let suggestions = anchor_suggestions(text, vec![issue("teh", "the", None)], 100);
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = &suggestions[0];
+        // "Café " is 5 UTF-16 units and the emoji plus its space are 3.
+        assert_eq!((suggestion.from, suggestion.to), (108, 111));
+        assert_eq!(suggestion.category, "spelling");
+        assert_eq!(suggestion.explanation, "Typo.");
+    }
+
+    #[test]
+    fn context_picks_the_right_occurrence() {
+        let text = "its fine, its late";
+        let issues = vec![issue("its", "it's", Some("its late"))];
+        let suggestions = anchor_suggestions(text, issues, 0);
+        assert_eq!((suggestions[0].from, suggestions[0].to), (10, 13));
+    }
+
+    #[test]
+    fn unmatched_unchanged_and_overlapping_issues_are_dropped() {
+        let text = "a bad sentence";
+        let issues = vec![
+            issue("missing", "x", None),
+            issue("bad", "bad", None),
+            issue("bad sentence", "good sentence", None),
+            issue("sentence", "line", None),
+        ];
+        let suggestions = anchor_suggestions(text, issues, 0);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].original, "bad sentence");
+        assert_eq!(suggestions[0].id, 0);
+    }
+
+    #[test]
+    fn schema_requires_every_issue_field() {
+        let schema = grammar_schema().schema;
+        let item = &schema["properties"]["issues"]["items"];
+        let properties = item["properties"].as_object().unwrap();
+        let required = item["required"].as_array().unwrap();
+        assert_eq!(properties.len(), required.len());
+        assert!(required
+            .iter()
+            .all(|field| properties.contains_key(field.as_str().unwrap())));
+    }
+}
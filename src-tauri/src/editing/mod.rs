@@ -0,0 +1,7 @@
+// src-tauri/src/editing/mod.rs
+pub mod autocomplete;
+pub mod diff;
+pub mod grammar;
+pub mod outline;
+pub mod transform;
+pub mod translation;
@@ -0,0 +1,48 @@
+// src-tauri/src/editing/outline.rs
+use crate::errors::AppError;
+use crate::llm::structured::parse_json_output;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutlineEntry {
+    pub level: u8,
+    pub title: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+pub fn build_outline_prompt(text: &str) -> String {
+    format!(
+        "Create a hierarchical outline of the document below. Follow its existing headings \
+where they make sense and add structure where the text has none.\n\
+Respond with a JSON array only. Each element must be an object with:\n\
+- \"level\": 1 for top-level entries, 2 or 3 for nested ones\n\
+- \"title\": a short title for the part\n\
+- \"summary\": one sentence describing what that part covers\n\n\
+Document:\n{}",
+        text
+    )
+}
+
+pub fn parse_outline(output: &str) -> Result<Vec<OutlineEntry>, AppError> {
+    let entries: Vec<OutlineEntry> = parse_json_output(output)?;
+    let outline: Vec<OutlineEntry> = entries
+        .into_iter()
+        .filter(|e| !e.title.trim().is_empty())
+        .map(|e| OutlineEntry {
+            level: e.level.clamp(1, 6),
+            title: e.title.trim().to_string(),
+            summary: e
+                .summary
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+        })
+        .collect();
+
+    if outline.is_empty() {
+        return Err(AppError::AiError(
+            "The AI returned an empty outline".to_string(),
+        ));
+    }
+    Ok(outline)
+}
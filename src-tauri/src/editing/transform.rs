@@ -0,0 +1,108 @@
+// src-tauri/src/editing/transform.rs
+use crate::errors::AppError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TransformOperation {
+    Rewrite,
+    Shorten,
+    Expand,
+    Simplify,
+    ChangeTone { tone: String },
+    FixGrammar,
+    Translate { language: String },
+}
+
+impl TransformOperation {
+    fn instruction(&self) -> Result<String, AppError> {
+        let instruction = match self {
+            TransformOperation::Rewrite => {
+                "Rewrite the text to improve clarity and flow while keeping its meaning."
+                    .to_string()
+            }
+            TransformOperation::Shorten => {
+                "Shorten the text substantially while keeping every key point.".to_string()
+            }
+            TransformOperation::Expand => {
+                "Expand the text with more detail and explanation, keeping the same style."
+                    .to_string()
+            }
+            TransformOperation::Simplify => {
+                "Simplify the text so it is easy to read, using plain words and short sentences."
+                    .to_string()
+            }
+            TransformOperation::ChangeTone { tone } => {
+                let tone = required(tone, "A tone is required")?;
+                format!("Rewrite the text in a {} tone, keeping its meaning.", tone)
+            }
+            TransformOperation::FixGrammar => {
+                "Fix spelling, grammar and punctuation mistakes. Change nothing else.".to_string()
+            }
+            TransformOperation::Translate { language } => {
+                let language = required(language, "A target language is required")?;
+                format!(
+                    "Translate the text into {}, preserving meaning and formatting.",
+                    language
+                )
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+pub fn build_transform_prompt(
+    text: &str,
+    operation: &TransformOperation,
+    context: Option<&str>,
+) -> Result<String, AppError> {
+    let mut prompt = format!(
+        "{}\nReply with the resulting text only: no preamble, quotes or explanations. \
+         Keep line breaks where they make sense.\n\n",
+        operation.instruction()?
+    );
+
+    if let Some(context) = context.filter(|c| !c.trim().is_empty()) {
+        prompt.push_str(&format!(
+            "Surrounding document context (do not include it in your reply):\n{}\n\n",
+            context.trim()
+        ));
+    }
+
+    prompt.push_str(&format!("Text:\n{}", text));
+    Ok(prompt)
+}
+
+/// Removes wrappers models like to add around a bare-text answer, such as
+/// code fences or a pair of quotes that the original did not have.
+pub fn clean_transform_output(output: &str, original: &str) -> String {
+    let mut text = output.trim();
+
+    if let Some(inner) = text.strip_prefix("```") {
+        let inner = inner
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or(inner);
+        text = inner.strip_suffix("```").unwrap_or(inner).trim();
+    }
+
+    let original = original.trim();
+    for (open, close) in [('"', '"'), ('\u{201C}', '\u{201D}'), ('\'', '\'')] {
+        let wrapped = text.len() >= 2 && text.starts_with(open) && text.ends_with(close);
+        if wrapped && !(original.starts_with(open) && original.ends_with(close)) {
+            text = text[open.len_utf8()..text.len() - close.len_utf8()].trim();
+            break;
+        }
+    }
+
+    text.to_string()
+}
+
+fn required<'a>(value: &'a str, message: &str) -> Result<&'a str, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        Err(AppError::AiError(message.to_string()))
+    } else {
+        Ok(value)
+    }
+}
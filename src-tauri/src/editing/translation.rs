@@ -0,0 +1,384 @@
+// src-tauri/src/editing/translation.rs
+//! Translates editor HTML block by block. Inline tags inside a block are
+//! swapped for numbered placeholders (`<t1>…</t1>`, `<t2/>`) so the model
+//! can reorder words across marks, links and comment anchors without
+//! touching the markup itself.
+use crate::errors::AppError;
+use crate::html::tokenizer::{tokenize, Token};
+use crate::html::{escape_text, is_block_element, is_void_element, render};
+use crate::llm::structured::parse_json_output;
+use crate::llm::{LLMClient, LlmClient};
+use serde::Serialize;
+
+pub const TRANSLATION_PROGRESS_EVENT: &str = "translate-document-progress";
+
+const MAX_BATCH_SEGMENTS: usize = 20;
+const MAX_BATCH_CHARS: usize = 4000;
+const MIN_BATCH_CHARS: usize = 200;
+
+/// Elements whose content is never translated.
+fn is_verbatim_element(name: &str) -> bool {
+    matches!(name, "pre" | "code" | "script" | "style")
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationProgress {
+    pub completed_batches: usize,
+    pub total_batches: usize,
+    pub total_segments: usize,
+}
+
+enum Placeholder {
+    /// An inline element kept around translated text.
+    Pair { open: Token, close: Token },
+    /// Markup that is moved as a unit, such as `<br>` or inline code.
+    Atom(Vec<Token>),
+}
+
+/// The inline content of one block, as sent to the model.
+pub struct Segment {
+    start: usize,
+    end: usize,
+    leading: String,
+    trailing: String,
+    source: String,
+    placeholders: Vec<Placeholder>,
+}
+
+impl Segment {
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Turns a translated string back into tokens, checking that every
+    /// placeholder survived exactly once and in a valid nesting.
+    fn restore(&self, translated: &str) -> Option<Vec<Token>> {
+        let mut tokens = vec![Token::Text(self.leading.clone())];
+        let mut used = vec![false; self.placeholders.len()];
+        let mut open: Vec<usize> = Vec::new();
+
+        for token in tokenize(translated.trim()) {
+            match token {
+                Token::Text(text) => tokens.push(Token::Text(text)),
+                Token::StartTag {
+                    name, self_closing, ..
+                } => {
+                    let id = placeholder_id(&name, self.placeholders.len())?;
+                    if std::mem::replace(&mut used[id], true) {
+                        return None;
+                    }
+                    match &self.placeholders[id] {
+                        Placeholder::Pair { open: tag, .. } if !self_closing => {
+                            tokens.push(tag.clone());
+                            open.push(id);
+                        }
+                        Placeholder::Atom(atom) => tokens.extend(atom.iter().cloned()),
+                        Placeholder::Pair { .. } => return None,
+                    }
+                }
+                Token::EndTag { name } => {
+                    let id = placeholder_id(&name, self.placeholders.len())?;
+                    match &self.placeholders[id] {
+                        Placeholder::Pair { close, .. } if open.last() == Some(&id) => {
+                            tokens.push(close.clone());
+                            open.pop();
+                        }
+                        _ => return None,
+                    }
+                }
+                Token::Other(_) => return None,
+            }
+        }
+
+        if !open.is_empty() || used.contains(&false) {
+            return None;
+        }
+        tokens.push(Token::Text(self.trailing.clone()));
+        Some(tokens)
+    }
+}
+
+fn placeholder_id(name: &str, count: usize) -> Option<usize> {
+    let number: usize = name.strip_prefix('t')?.parse().ok()?;
+    (1..=count).contains(&number).then(|| number - 1)
+}
+
+/// A parsed document and the segments that need translating.
+pub struct TranslationPlan {
+    tokens: Vec<Token>,
+    segments: Vec<Segment>,
+}
+
+impl TranslationPlan {
+    pub fn new(html: &str) -> Self {
+        let tokens = tokenize(html);
+        let mut segments = Vec::new();
+        let mut verbatim_depth = 0usize;
+        let mut run_start: Option<usize> = None;
+
+        for (index, token) in tokens.iter().enumerate() {
+            let is_boundary = match token {
+                Token::StartTag { name, .. } | Token::EndTag { name } => is_block_element(name),
+                Token::Other(_) => true,
+                Token::Text(_) => false,
+            };
+
+            if is_boundary {
+                if let Some(start) = run_start.take() {
+                    segments.extend(build_segment(&tokens, start, index));
+                }
+            } else if verbatim_depth == 0 && run_start.is_none() {
+                run_start = Some(index);
+            }
+
+            match token {
+                Token::StartTag {
+                    name,
+                    self_closing: false,
+                    ..
+                } if name == "pre" => verbatim_depth += 1,
+                Token::EndTag { name } if name == "pre" => {
+                    verbatim_depth = verbatim_depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = run_start {
+            segments.extend(build_segment(&tokens, start, tokens.len()));
+        }
+
+        Self { tokens, segments }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Groups segment indices into batches that fit the model's budget.
+    pub fn batches(&self, max_input_chars: usize) -> Vec<Vec<usize>> {
+        let budget = (max_input_chars / 2).clamp(MIN_BATCH_CHARS, MAX_BATCH_CHARS);
+        let mut batches: Vec<Vec<usize>> = Vec::new();
+        let mut current: Vec<usize> = Vec::new();
+        let mut current_chars = 0;
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let chars = segment.source.chars().count();
+            if !current.is_empty()
+                && (current_chars + chars > budget || current.len() >= MAX_BATCH_SEGMENTS)
+            {
+                batches.push(std::mem::take(&mut current));
+                current_chars = 0;
+            }
+            current.push(index);
+            current_chars += chars;
+        }
+        if !current.is_empty() {
+            batches.push(current);
+        }
+        batches
+    }
+
+    /// Reassembles the document, keeping the original of any segment
+    /// without a translation.
+    pub fn render(&self, translations: &[Option<Vec<Token>>]) -> String {
+        let mut output = Vec::with_capacity(self.tokens.len());
+        let mut position = 0;
+
+        for (segment, translation) in self.segments.iter().zip(translations) {
+            output.extend(self.tokens[position..segment.start].iter().cloned());
+            match translation {
+                Some(tokens) => output.extend(tokens.iter().cloned()),
+                None => output.extend(self.tokens[segment.start..segment.end].iter().cloned()),
+            }
+            position = segment.end;
+        }
+        output.extend(self.tokens[position..].iter().cloned());
+
+        render(&output)
+    }
+}
+
+fn build_segment(tokens: &[Token], start: usize, end: usize) -> Option<Segment> {
+    let run = &tokens[start..end];
+    let has_words = run.iter().any(|token| match token {
+        Token::Text(text) => text.chars().any(char::is_alphabetic),
+        _ => false,
+    });
+    if !has_words {
+        return None;
+    }
+
+    // Pair each start tag with its end tag inside the run.
+    let mut partner: Vec<Option<usize>> = vec![None; run.len()];
+    let mut stack: Vec<(usize, &str)> = Vec::new();
+    for (index, token) in run.iter().enumerate() {
+        match token {
+            Token::StartTag {
+                name,
+                self_closing: false,
+                ..
+            } if !is_void_element(name) => stack.push((index, name)),
+            Token::EndTag { name } => {
+                if let Some(position) = stack.iter().rposition(|(_, open)| open == name) {
+                    let (open_index, _) = stack[position];
+                    stack.truncate(position);
+                    partner[open_index] = Some(index);
+                    partner[index] = Some(open_index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut source = String::new();
+    let mut placeholders = Vec::new();
+    let mut close_ids: Vec<Option<usize>> = vec![None; run.len()];
+    let mut index = 0;
+
+    while index < run.len() {
+        let token = &run[index];
+        match token {
+            Token::Text(text) => source.push_str(&escape_text(text)),
+            Token::StartTag { name, .. } => match partner[index] {
+                Some(close) if is_verbatim_element(name) => {
+                    placeholders.push(Placeholder::Atom(run[index..=close].to_vec()));
+                    source.push_str(&format!("<t{}/>", placeholders.len()));
+                    index = close;
+                }
+                Some(close) => {
+                    placeholders.push(Placeholder::Pair {
+                        open: token.clone(),
+                        close: run[close].clone(),
+                    });
+                    close_ids[close] = Some(placeholders.len());
+                    source.push_str(&format!("<t{}>", placeholders.len()));
+                }
+                None => {
+                    placeholders.push(Placeholder::Atom(vec![token.clone()]));
+                    source.push_str(&format!("<t{}/>", placeholders.len()));
+                }
+            },
+            Token::EndTag { .. } => match close_ids[index] {
+                Some(id) => source.push_str(&format!("</t{}>", id)),
+                None => {
+                    placeholders.push(Placeholder::Atom(vec![token.clone()]));
+                    source.push_str(&format!("<t{}/>", placeholders.len()));
+                }
+            },
+            Token::Other(_) => {}
+        }
+        index += 1;
+    }
+
+    let trimmed = source.trim();
+    let leading_len = source.len() - source.trim_start().len();
+    let leading = source[..leading_len].to_string();
+    let trailing = source[leading_len + trimmed.len()..].to_string();
+
+    Some(Segment {
+        start,
+        end,
+        leading,
+        trailing,
+        source: trimmed.to_string(),
+        placeholders,
+    })
+}
+
+fn build_translation_prompt(sources: &[&str], language: &str) -> Result<String, AppError> {
+    Ok(format!(
+        "Translate each string in the JSON array below into {}.\n\
+Tags such as <t1>...</t1> and <t2/> stand for formatting. Keep every tag exactly once, \
+place it around the corresponding translated words, and never add new tags. \
+Keep HTML entities such as &amp; unchanged.\n\
+Respond with a JSON array of the same length and order that contains only the translations.\n\n{}",
+        language,
+        serde_json::to_string(sources)?
+    ))
+}
+
+/// Translates one batch of segments. Malformed batches are retried one
+/// segment at a time; a segment whose placeholders cannot be restored is
+/// returned as `None` and left untranslated.
+pub async fn translate_batch(
+    client: &LlmClient,
+    segments: &[&Segment],
+    language: &str,
+) -> Result<Vec<Option<Vec<Token>>>, AppError> {
+    let sources: Vec<&str> = segments.iter().map(|s| s.source()).collect();
+    let translated = request_translations(client, &sources, language).await?;
+
+    if let Some(translated) = translated.filter(|t| t.len() == segments.len()) {
+        let restored: Vec<Option<Vec<Token>>> = segments
+            .iter()
+            .zip(&translated)
+            .map(|(segment, text)| segment.restore(text))
+            .collect();
+        if segments.len() == 1 || restored.iter().all(Option::is_some) {
+            return Ok(restored);
+        }
+    }
+
+    let mut restored = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let translated = request_translations(client, &[segment.source()], language).await?;
+        restored.push(
+            translated
+                .and_then(|t| t.into_iter().next())
+                .and_then(|text| segment.restore(&text)),
+        );
+    }
+    Ok(restored)
+}
+
+/// Returns `None` when the reply is not a JSON array of strings; request
+/// failures are passed on.
+async fn request_translations(
+    client: &LlmClient,
+    sources: &[&str],
+    language: &str,
+) -> Result<Option<Vec<String>>, AppError> {
+    let prompt = build_translation_prompt(sources, language)?;
+    let response = client.chat(&prompt).await?;
+    Ok(parse_json_output::<Vec<String>>(&response.output).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_markup_becomes_placeholders() {
+        let plan = TranslationPlan::new("<p>Hello <strong>bold</strong> world<br>again</p>");
+        let sources: Vec<&str> = plan.segments().iter().map(Segment::source).collect();
+        assert_eq!(sources, ["Hello <t1>bold</t1> world<t2/>again"]);
+    }
+
+    #[test]
+    fn restored_translations_keep_the_markup() {
+        let plan = TranslationPlan::new("<p>Hello <strong>bold</strong> world</p><p>Bye</p>");
+        let first = plan.segments()[0].restore("<t1>Fett</t1> hallo Welt");
+        assert!(first.is_some());
+        assert_eq!(
+            plan.render(&[first, None]),
+            "<p><strong>Fett</strong> hallo Welt</p><p>Bye</p>"
+        );
+    }
+
+    #[test]
+    fn damaged_placeholders_are_rejected() {
+        let plan = TranslationPlan::new("<p>a <em>b</em> <strong>c</strong></p>");
+        let segment = &plan.segments()[0];
+        assert!(segment.restore("x <t1>y</t1> <t2>z</t2>").is_some());
+        for damaged in [
+            "x <t1>y</t1> z",
+            "x <t1>y</t1> <t2>z</t2> <t1>w</t1>",
+            "x <t1>y <t2>z</t1></t2>",
+            "x <t1>y</t1> <t3>z</t3>",
+            "x <t1/> <t2>z</t2>",
+        ] {
+            assert!(segment.restore(damaged).is_none(), "{}", damaged);
+        }
+    }
+}
@@ -4,9 +4,30 @@ use thiserror::Error;
 pub enum AppError {
     #[error("PDF processing error: {0}")]
     PdfError(String),
+
+    #[error("PDF is password protected: {0}")]
+    PdfPasswordRequired(String),
     
     #[error("AI service error: {0}")]
     AiError(String),
+
+    #[error("Unsupported capability: {0}")]
+    CapabilityError(String),
+
+    #[error("Prompt template error: {0}")]
+    TemplateError(String),
+
+    #[error("Chat history error: {0}")]
+    ChatHistoryError(String),
+
+    #[error("Secret vault error: {0}")]
+    VaultError(String),
+
+    #[error("Settings error: {0}")]
+    SettingsError(String),
+
+    #[error("Export error: {0}")]
+    ExportError(String),
     
     #[error("Network error: {0}")]
     NetworkError(#[from] reqwest::Error),
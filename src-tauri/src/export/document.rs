@@ -0,0 +1,527 @@
+// src-tauri/src/export/document.rs
+//! Reads editor HTML into the blocks and styled text spans the PDF layout
+//! works from. Unknown elements are looked through, so their text is kept
+//! even when their meaning is not.
+use crate::html::tokenizer::{tokenize, Token};
+
+/// The widest `colspan` HTML allows; wider spans are clamped to it.
+const MAX_COLSPAN: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Script {
+    #[default]
+    Normal,
+    Super,
+    Sub,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+    pub code: bool,
+    pub script: Script,
+    /// Background color of highlighted text, as RGB from 0 to 1.
+    pub highlight: Option<[f32; 3]>,
+    pub link: Option<String>,
+    /// Comment threads whose marks cover the text.
+    pub threads: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: TextStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextKind {
+    Paragraph,
+    Heading(u8),
+    /// Preformatted text; whitespace and line breaks are kept.
+    Code,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Text {
+        kind: TextKind,
+        align: Align,
+        spans: Vec<Span>,
+    },
+    List {
+        ordered: bool,
+        start: u32,
+        items: Vec<ListItem>,
+    },
+    Quote(Vec<Block>),
+    Table(Vec<Vec<TableCell>>),
+    Image {
+        src: String,
+        alt: Option<String>,
+    },
+    Rule,
+    PageBreak,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListItem {
+    /// `Some` for task list items.
+    pub checked: Option<bool>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableCell {
+    pub header: bool,
+    pub colspan: usize,
+    pub blocks: Vec<Block>,
+}
+
+pub fn parse_document(html: &str) -> Vec<Block> {
+    let tokens = tokenize(html);
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+    };
+    parser.blocks(None)
+}
+
+/// Elements whose contents are inline text.
+fn is_inline(name: &str) -> bool {
+    matches!(
+        name,
+        "a" | "b"
+            | "strong"
+            | "i"
+            | "em"
+            | "u"
+            | "s"
+            | "strike"
+            | "del"
+            | "code"
+            | "sup"
+            | "sub"
+            | "mark"
+            | "span"
+            | "br"
+            | "label"
+            | "small"
+    )
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Reads blocks until the end tag `until`, or the end of the input.
+    fn blocks(&mut self, until: Option<&str>) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        while let Some(token) = self.tokens.get(self.position) {
+            match token {
+                Token::EndTag { name } => {
+                    self.position += 1;
+                    if Some(name.as_str()) == until {
+                        break;
+                    }
+                }
+                Token::Text(text) if text.trim().is_empty() => self.position += 1,
+                Token::Text(_) => blocks.push(self.loose_text(until)),
+                Token::Other(_) => self.position += 1,
+                Token::StartTag { name, .. } => {
+                    let name = name.as_str();
+                    if is_inline(name) {
+                        blocks.push(self.loose_text(until));
+                        continue;
+                    }
+                    self.position += 1;
+                    let align = align_of(token);
+                    match name {
+                        "p" => blocks.push(self.text_block(TextKind::Paragraph, align, "p")),
+                        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                            let level = name[1..].parse().unwrap_or(1);
+                            blocks.push(self.text_block(TextKind::Heading(level), align, name));
+                        }
+                        "pre" => blocks.push(self.text_block(TextKind::Code, align, "pre")),
+                        "ul" | "ol" => {
+                            let start = token
+                                .attribute("start")
+                                .and_then(|start| start.parse().ok())
+                                .unwrap_or(1);
+                            let items = self.list_items(name);
+                            blocks.push(Block::List {
+                                ordered: name == "ol",
+                                start,
+                                items,
+                            });
+                        }
+                        "blockquote" => blocks.push(Block::Quote(self.blocks(Some("blockquote")))),
+                        "table" => blocks.push(Block::Table(self.table_rows())),
+                        "img" => {
+                            if let Some(src) = token.attribute("src").filter(|s| !s.is_empty()) {
+                                blocks.push(Block::Image {
+                                    src: src.to_string(),
+                                    alt: token.attribute("alt").map(str::to_string),
+                                });
+                            }
+                        }
+                        "hr" if token.attribute("data-page-break").is_some() => {
+                            blocks.push(Block::PageBreak)
+                        }
+                        "hr" => blocks.push(Block::Rule),
+                        // Containers are looked through.
+                        _ => {}
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Inline content outside any paragraph, read up to the next block
+    /// element.
+    fn loose_text(&mut self, until: Option<&str>) -> Block {
+        let start = self.position;
+        let mut end = start;
+        while let Some(token) = self.tokens.get(end) {
+            let inline = match token {
+                Token::StartTag { name, .. } => is_inline(name),
+                Token::EndTag { name } => is_inline(name) && Some(name.as_str()) != until,
+                Token::Text(_) | Token::Other(_) => true,
+            };
+            if !inline {
+                break;
+            }
+            end += 1;
+        }
+        self.position = end;
+        Block::Text {
+            kind: TextKind::Paragraph,
+            align: Align::Left,
+            spans: spans(&self.tokens[start..end], false),
+        }
+    }
+
+    fn text_block(&mut self, kind: TextKind, align: Align, tag: &str) -> Block {
+        let start = self.position;
+        let mut end = self.tokens.len();
+        let mut depth = 0usize;
+        while let Some(token) = self.tokens.get(self.position) {
+            self.position += 1;
+            match token {
+                Token::StartTag {
+                    name, self_closing, ..
+                } if name == tag && !self_closing => depth += 1,
+                Token::EndTag { name } if name == tag => {
+                    if depth == 0 {
+                        end = self.position - 1;
+                        break;
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+        Block::Text {
+            kind,
+            align,
+            spans: spans(&self.tokens[start..end], kind == TextKind::Code),
+        }
+    }
+
+    fn list_items(&mut self, tag: &str) -> Vec<ListItem> {
+        let mut items = Vec::new();
+        while let Some(token) = self.tokens.get(self.position) {
+            self.position += 1;
+            match token {
+                Token::EndTag { name } if name == tag => break,
+                Token::StartTag { name, .. } if name == "li" => {
+                    let checked = token.attribute("data-checked").map(|c| c == "true");
+                    items.push(ListItem {
+                        checked,
+                        blocks: self.blocks(Some("li")),
+                    });
+                }
+                _ => {}
+            }
+        }
+        items
+    }
+
+    fn table_rows(&mut self) -> Vec<Vec<TableCell>> {
+        let mut rows: Vec<Vec<TableCell>> = Vec::new();
+        while let Some(token) = self.tokens.get(self.position) {
+            self.position += 1;
+            match token {
+                Token::EndTag { name } if name == "table" => break,
+                Token::StartTag { name, .. } if name == "tr" => rows.push(Vec::new()),
+                Token::StartTag { name, .. } if name == "td" || name == "th" => {
+                    let colspan = token
+                        .attribute("colspan")
+                        .and_then(|span| span.parse().ok())
+                        .unwrap_or(1usize)
+                        .clamp(1, MAX_COLSPAN);
+                    let header = name == "th";
+                    let blocks = self.blocks(Some(name));
+                    if rows.is_empty() {
+                        rows.push(Vec::new());
+                    }
+                    if let Some(row) = rows.last_mut() {
+                        row.push(TableCell {
+                            header,
+                            colspan,
+                            blocks,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        rows.retain(|row| !row.is_empty());
+        rows
+    }
+}
+
+fn align_of(token: &Token) -> Align {
+    let style = token.attribute("style").unwrap_or_default();
+    let value = css_value(style, "text-align").unwrap_or_default();
+    match value.as_str() {
+        "center" => Align::Center,
+        "right" | "end" => Align::Right,
+        "justify" => Align::Justify,
+        _ => Align::Left,
+    }
+}
+
+fn css_value(style: &str, property: &str) -> Option<String> {
+    style.split(';').find_map(|declaration| {
+        let (name, value) = declaration.split_once(':')?;
+        (name.trim().eq_ignore_ascii_case(property)).then(|| value.trim().to_lowercase())
+    })
+}
+
+/// Styled text from inline tokens. Tags are matched by name, so a
+/// stray end tag only closes the formatting it names.
+fn spans(tokens: &[Token], preformatted: bool) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut open: Vec<(String, TextStyle)> = Vec::new();
+    let current = |open: &[(String, TextStyle)]| {
+        open.last()
+            .map(|(_, style)| style.clone())
+            .unwrap_or_default()
+    };
+
+    for token in tokens {
+        match token {
+            Token::Text(text) => push_text(&mut spans, text, current(&open)),
+            Token::StartTag {
+                name, self_closing, ..
+            } => {
+                if name == "br" {
+                    push_text(&mut spans, "\n", current(&open));
+                    continue;
+                }
+                if *self_closing || crate::html::is_void_element(name) {
+                    continue;
+                }
+                let mut style = current(&open);
+                match name.as_str() {
+                    "b" | "strong" => style.bold = true,
+                    "i" | "em" => style.italic = true,
+                    "u" => style.underline = true,
+                    "s" | "strike" | "del" => style.strike = true,
+                    "code" if !preformatted => style.code = true,
+                    "sup" => style.script = Script::Super,
+                    "sub" => style.script = Script::Sub,
+                    "a" => style.link = token.attribute("href").map(str::to_string),
+                    "mark" => {
+                        style.highlight = token
+                            .attribute("data-color")
+                            .and_then(parse_color)
+                            .or_else(|| {
+                                token
+                                    .attribute("style")
+                                    .and_then(|s| css_value(s, "background-color"))
+                                    .and_then(|c| parse_color(&c))
+                            })
+                            .or(Some([1.0, 0.95, 0.5]));
+                    }
+                    _ => {}
+                }
+                if let Some(thread) = token.attribute("data-thread-id").filter(|t| !t.is_empty()) {
+                    style.threads.push(thread.to_string());
+                }
+                open.push((name.clone(), style));
+            }
+            Token::EndTag { name } => {
+                if let Some(index) = open.iter().rposition(|(open_name, _)| open_name == name) {
+                    open.truncate(index);
+                }
+            }
+            Token::Other(_) => {}
+        }
+    }
+
+    if !preformatted {
+        // Line breaks in the markup are only whitespace.
+        for span in &mut spans {
+            if span.text != "\n" {
+                span.text = span.text.replace(['\n', '\r', '\t'], " ");
+            }
+        }
+    }
+    spans
+}
+
+fn push_text(spans: &mut Vec<Span>, text: &str, style: TextStyle) {
+    match spans.last_mut() {
+        Some(last) if last.style == style && last.text != "\n" && text != "\n" => {
+            last.text.push_str(text)
+        }
+        _ => spans.push(Span {
+            text: text.to_string(),
+            style,
+        }),
+    }
+}
+
+/// Parses "#rgb", "#rrggbb" and "rgb(r, g, b)" colors.
+fn parse_color(value: &str) -> Option<[f32; 3]> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        let [r, g, b] = match digits.as_slice() {
+            [r, g, b] => [r * 17, g * 17, b * 17],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2],
+            _ => return None,
+        };
+        return Some([r, g, b].map(|c| f32::from(c) / 255.0));
+    }
+
+    let inner = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))?
+        .strip_suffix(')')?;
+    let channels: Vec<f32> = inner
+        .split(',')
+        .take(3)
+        .map(|c| c.trim().parse::<f32>().ok())
+        .collect::<Option<_>>()?;
+    match channels.as_slice() {
+        [r, g, b] => Some([r, g, b].map(|c| c.clamp(0.0, 255.0) / 255.0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph(text: &str) -> Block {
+        Block::Text {
+            kind: TextKind::Paragraph,
+            align: Align::Left,
+            spans: vec![Span {
+                text: text.to_string(),
+                style: TextStyle::default(),
+            }],
+        }
+    }
+
+    #[test]
+    fn inline_marks_become_span_styles() {
+        let blocks = parse_document(
+            "<h2>Title</h2><p>Plain <strong>bold</strong> <a href=\"https://example.org\">link</a></p>",
+        );
+        let [Block::Text { kind, .. }, Block::Text { spans, .. }] = blocks.as_slice() else {
+            panic!("unexpected blocks: {blocks:?}");
+        };
+        assert_eq!(*kind, TextKind::Heading(2));
+        let texts: Vec<&str> = spans.iter().map(|span| span.text.as_str()).collect();
+        assert_eq!(texts, ["Plain ", "bold", " ", "link"]);
+        assert!(spans[1].style.bold);
+        assert_eq!(spans[3].style.link.as_deref(), Some("https://example.org"));
+    }
+
+    #[test]
+    fn lists_keep_their_start_and_task_state() {
+        let blocks = parse_document(
+            "<ol start=\"3\"><li><p>Three</p></li></ol>\
+             <ul data-type=\"taskList\"><li data-checked=\"true\"><p>Done</p></li></ul>",
+        );
+        assert_eq!(
+            blocks,
+            [
+                Block::List {
+                    ordered: true,
+                    start: 3,
+                    items: vec![ListItem {
+                        checked: None,
+                        blocks: vec![paragraph("Three")],
+                    }],
+                },
+                Block::List {
+                    ordered: false,
+                    start: 1,
+                    items: vec![ListItem {
+                        checked: Some(true),
+                        blocks: vec![paragraph("Done")],
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_clamp_colspan_and_unknown_elements_keep_text() {
+        let blocks = parse_document(
+            "<table><tr><th colspan=\"5000\">H</th></tr><tr><td>1</td></tr></table>\
+             <hr><custom>kept</custom>",
+        );
+        assert_eq!(
+            blocks,
+            [
+                Block::Table(vec![
+                    vec![TableCell {
+                        header: true,
+                        colspan: MAX_COLSPAN,
+                        blocks: vec![paragraph("H")],
+                    }],
+                    vec![TableCell {
+                        header: false,
+                        colspan: 1,
+                        blocks: vec![paragraph("1")],
+                    }],
+                ]),
+                Block::Rule,
+                paragraph("kept"),
+            ]
+        );
+    }
+
+    #[test]
+    fn preformatted_text_keeps_its_whitespace() {
+        let blocks = parse_document("<pre><code>a\n  b</code></pre>");
+        let [Block::Text { kind, spans, .. }] = blocks.as_slice() else {
+            panic!("unexpected blocks: {blocks:?}");
+        };
+        assert_eq!(*kind, TextKind::Code);
+        assert_eq!(spans[0].text, "a\n  b");
+    }
+}
@@ -0,0 +1,174 @@
+// src-tauri/src/export/endnotes.rs
+//! Comment threads as endnotes: a superscript number after the text each
+//! thread's marks cover, and a closing "Comments" section that quotes the
+//! marked text and lists the discussion.
+use super::document::{parse_document, Align, Block, Script, Span, TextKind, TextStyle};
+use crate::comments::anchors::collect_anchors;
+use crate::comments::models::CommentThread;
+use std::collections::HashMap;
+
+/// Numbers the threads' marks and appends the comments section. Threads
+/// whose marks are gone are listed after the others.
+pub fn add_endnotes(blocks: &mut Vec<Block>, html: &str, threads: &[CommentThread]) {
+    let threads: Vec<&CommentThread> = threads
+        .iter()
+        .filter(|thread| thread.visible_comments().next().is_some())
+        .collect();
+    if threads.is_empty() {
+        return;
+    }
+
+    let known: Vec<&str> = threads.iter().map(|thread| thread.id.as_str()).collect();
+    let mut numbers: HashMap<String, usize> = HashMap::new();
+    number_blocks(blocks, &known, &mut numbers);
+    for thread in &threads {
+        let next = numbers.len() + 1;
+        numbers.entry(thread.id.clone()).or_insert(next);
+    }
+
+    let mut ordered = threads;
+    ordered.sort_by_key(|thread| numbers[&thread.id]);
+    let anchors = collect_anchors(html);
+
+    blocks.push(text_block(
+        TextKind::Heading(2),
+        vec![plain("Comments".to_string())],
+    ));
+    for thread in ordered {
+        let mut heading = vec![Span {
+            text: format!("{}. ", numbers[&thread.id]),
+            style: TextStyle {
+                bold: true,
+                ..TextStyle::default()
+            },
+        }];
+        if let Some(anchor) = anchors
+            .get(&thread.id)
+            .filter(|a| !a.text.trim().is_empty())
+        {
+            heading.push(Span {
+                text: format!("“{}”", anchor.text.trim()),
+                style: TextStyle {
+                    italic: true,
+                    ..TextStyle::default()
+                },
+            });
+        }
+        if let Some(resolved_at) = thread.resolved_at {
+            let by = thread
+                .resolved_by
+                .as_deref()
+                .map(|user| format!(" by {}", user))
+                .unwrap_or_default();
+            heading.push(plain(format!(
+                " (resolved{} on {})",
+                by,
+                resolved_at.format("%Y-%m-%d")
+            )));
+        }
+        blocks.push(text_block(TextKind::Paragraph, heading));
+
+        let mut discussion = Vec::new();
+        for comment in thread.visible_comments() {
+            let edited = if comment.updated_at.is_some() {
+                ", edited"
+            } else {
+                ""
+            };
+            discussion.push(text_block(
+                TextKind::Paragraph,
+                vec![
+                    Span {
+                        text: comment.user_id.clone(),
+                        style: TextStyle {
+                            bold: true,
+                            ..TextStyle::default()
+                        },
+                    },
+                    plain(format!(
+                        " · {}{}",
+                        comment.created_at.format("%Y-%m-%d %H:%M UTC"),
+                        edited
+                    )),
+                ],
+            ));
+            discussion.extend(parse_document(&comment.content));
+        }
+        blocks.push(Block::Quote(discussion));
+    }
+}
+
+fn plain(text: String) -> Span {
+    Span {
+        text,
+        style: TextStyle::default(),
+    }
+}
+
+fn text_block(kind: TextKind, spans: Vec<Span>) -> Block {
+    Block::Text {
+        kind,
+        align: Align::Left,
+        spans,
+    }
+}
+
+fn number_blocks(blocks: &mut [Block], known: &[&str], numbers: &mut HashMap<String, usize>) {
+    for block in blocks {
+        match block {
+            Block::Text { spans, .. } => number_spans(spans, known, numbers),
+            Block::List { items, .. } => {
+                for item in items {
+                    number_blocks(&mut item.blocks, known, numbers);
+                }
+            }
+            Block::Quote(blocks) => number_blocks(blocks, known, numbers),
+            Block::Table(rows) => {
+                for cell in rows.iter_mut().flatten() {
+                    number_blocks(&mut cell.blocks, known, numbers);
+                }
+            }
+            Block::Image { .. } | Block::Rule | Block::PageBreak => {}
+        }
+    }
+}
+
+/// Puts the thread numbers after the last span of each mark.
+fn number_spans(spans: &mut Vec<Span>, known: &[&str], numbers: &mut HashMap<String, usize>) {
+    let mut index = 0;
+    while index < spans.len() {
+        let ending: Vec<String> = spans[index]
+            .style
+            .threads
+            .iter()
+            .filter(|thread| known.contains(&thread.as_str()))
+            .filter(|thread| {
+                !spans
+                    .get(index + 1)
+                    .is_some_and(|next| next.style.threads.contains(thread))
+            })
+            .cloned()
+            .collect();
+        if !ending.is_empty() {
+            let references: Vec<String> = ending
+                .into_iter()
+                .map(|thread| {
+                    let next = numbers.len() + 1;
+                    numbers.entry(thread).or_insert(next).to_string()
+                })
+                .collect();
+            spans.insert(
+                index + 1,
+                Span {
+                    text: references.join(","),
+                    style: TextStyle {
+                        script: Script::Super,
+                        ..TextStyle::default()
+                    },
+                },
+            );
+            index += 1;
+        }
+        index += 1;
+    }
+}
@@ -0,0 +1,380 @@
+// src-tauri/src/export/fonts.rs
+//! Fonts for PDF export. TrueType and OpenType fonts are looked up among
+//! the system fonts, or taken from a given file, and embedded as CID fonts
+//! cut down to the glyphs the document uses (see `subset`). Characters the
+//! styled font lacks come from the next font in the fallback list.
+use crate::errors::AppError;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use ttf_parser::{name_id, Face, GlyphId, Permissions};
+
+/// Directories searched for the font files below, in order.
+const FONT_DIRS: [&str; 14] = [
+    "/usr/share/fonts/truetype/dejavu",
+    "/usr/share/fonts/dejavu-sans-fonts",
+    "/usr/share/fonts/dejavu-sans-mono-fonts",
+    "/usr/share/fonts/TTF",
+    "/usr/share/fonts/truetype/liberation",
+    "/usr/share/fonts/liberation-sans",
+    "/usr/share/fonts/liberation-mono",
+    "/usr/share/fonts/truetype/noto",
+    "/usr/share/fonts/noto",
+    "/usr/share/fonts/truetype/droid",
+    "/usr/share/fonts/google-droid-sans-fonts",
+    "/System/Library/Fonts/Supplemental",
+    "/Library/Fonts",
+    "C:\\Windows\\Fonts",
+];
+
+/// Regular, bold, italic and bold italic files of a family.
+type Family = [&'static str; 4];
+
+const TEXT_FAMILIES: [Family; 6] = [
+    [
+        "DejaVuSans.ttf",
+        "DejaVuSans-Bold.ttf",
+        "DejaVuSans-Oblique.ttf",
+        "DejaVuSans-BoldOblique.ttf",
+    ],
+    [
+        "LiberationSans-Regular.ttf",
+        "LiberationSans-Bold.ttf",
+        "LiberationSans-Italic.ttf",
+        "LiberationSans-BoldItalic.ttf",
+    ],
+    [
+        "NotoSans-Regular.ttf",
+        "NotoSans-Bold.ttf",
+        "NotoSans-Italic.ttf",
+        "NotoSans-BoldItalic.ttf",
+    ],
+    [
+        "Arial.ttf",
+        "Arial Bold.ttf",
+        "Arial Italic.ttf",
+        "Arial Bold Italic.ttf",
+    ],
+    ["arial.ttf", "arialbd.ttf", "ariali.ttf", "arialbi.ttf"],
+    [
+        "segoeui.ttf",
+        "segoeuib.ttf",
+        "segoeuii.ttf",
+        "segoeuiz.ttf",
+    ],
+];
+
+const MONO_FAMILIES: [Family; 4] = [
+    [
+        "DejaVuSansMono.ttf",
+        "DejaVuSansMono-Bold.ttf",
+        "DejaVuSansMono-Oblique.ttf",
+        "DejaVuSansMono-BoldOblique.ttf",
+    ],
+    [
+        "LiberationMono-Regular.ttf",
+        "LiberationMono-Bold.ttf",
+        "LiberationMono-Italic.ttf",
+        "LiberationMono-BoldItalic.ttf",
+    ],
+    [
+        "Courier New.ttf",
+        "Courier New Bold.ttf",
+        "Courier New Italic.ttf",
+        "Courier New Bold Italic.ttf",
+    ],
+    [
+        "consola.ttf",
+        "consolab.ttf",
+        "consolai.ttf",
+        "consolaz.ttf",
+    ],
+];
+
+/// Wide-coverage fonts tried for characters the text fonts lack.
+const FALLBACK_FONTS: [&str; 8] = [
+    "DejaVuSans.ttf",
+    "NotoSans-Regular.ttf",
+    "NotoSansSymbols2-Regular.ttf",
+    "DroidSansFallbackFull.ttf",
+    "DroidSansFallback.ttf",
+    "Arial Unicode.ttf",
+    "seguisym.ttf",
+    "malgun.ttf",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FontStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub mono: bool,
+}
+
+/// A font file loaded for embedding, with the glyphs written so far.
+pub struct FontFace {
+    pub data: Vec<u8>,
+    pub postscript_name: String,
+    /// Whether the outlines are CFF rather than TrueType.
+    pub cff: bool,
+    pub italic: bool,
+    pub monospaced: bool,
+    /// The metrics below are in thousandths of the font size.
+    pub ascent: f32,
+    pub descent: f32,
+    pub cap_height: f32,
+    pub bbox: [f32; 4],
+    pub italic_angle: f32,
+    units_per_em: f32,
+    glyphs: HashMap<char, Option<(u16, f32)>>,
+    /// Glyphs used in the document, with their widths and the text they
+    /// stand for.
+    pub used: BTreeMap<u16, (f32, String)>,
+}
+
+impl FontFace {
+    fn load(path: &Path) -> Option<FontFace> {
+        let data = std::fs::read(path).ok()?;
+        // Collections cannot be embedded as a single font file.
+        if data.starts_with(b"ttcf") {
+            return None;
+        }
+        let face = Face::parse(&data, 0).ok()?;
+        if face.permissions() == Some(Permissions::Restricted) {
+            return None;
+        }
+
+        let scale = 1000.0 / f32::from(face.units_per_em());
+        let postscript_name = face
+            .names()
+            .into_iter()
+            .find(|name| name.name_id == name_id::POST_SCRIPT_NAME)
+            .and_then(|name| name.to_string())
+            .unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            })
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        let bbox = face.global_bounding_box();
+
+        Some(FontFace {
+            postscript_name,
+            cff: face.tables().cff.is_some(),
+            italic: face.is_italic(),
+            monospaced: face.is_monospaced(),
+            ascent: f32::from(face.ascender()) * scale,
+            descent: f32::from(face.descender()) * scale,
+            cap_height: f32::from(face.capital_height().unwrap_or(face.ascender())) * scale,
+            bbox: [
+                f32::from(bbox.x_min) * scale,
+                f32::from(bbox.y_min) * scale,
+                f32::from(bbox.x_max) * scale,
+                f32::from(bbox.y_max) * scale,
+            ],
+            italic_angle: face.italic_angle(),
+            units_per_em: f32::from(face.units_per_em()),
+            glyphs: HashMap::new(),
+            used: BTreeMap::new(),
+            data,
+        })
+    }
+
+    /// The glyph for `c` and its advance in thousandths of the font size.
+    fn glyph(&mut self, c: char) -> Option<(u16, f32)> {
+        if let Some(glyph) = self.glyphs.get(&c) {
+            return *glyph;
+        }
+        let glyph = Face::parse(&self.data, 0).ok().and_then(|face| {
+            let id = face.glyph_index(c)?;
+            let advance = face.glyph_hor_advance(id).unwrap_or(0);
+            Some((id.0, f32::from(advance) * 1000.0 / self.units_per_em))
+        });
+        self.glyphs.insert(c, glyph);
+        glyph
+    }
+
+    fn notdef_width(&self) -> f32 {
+        Face::parse(&self.data, 0)
+            .ok()
+            .and_then(|face| face.glyph_hor_advance(GlyphId(0)))
+            .map_or(500.0, |advance| {
+                f32::from(advance) * 1000.0 / self.units_per_em
+            })
+    }
+}
+
+/// Text set in one face: the glyph ids to write and their total width in
+/// thousandths of the font size.
+#[derive(Debug, Clone)]
+pub struct GlyphRun {
+    pub face: usize,
+    pub glyphs: Vec<u16>,
+    pub width: f32,
+}
+
+pub struct FontSet {
+    pub faces: Vec<FontFace>,
+    /// Indexes into `faces` for regular, bold, italic and bold italic.
+    text: [usize; 4],
+    mono: [usize; 4],
+    fallbacks: Vec<usize>,
+    /// Characters no font could show.
+    pub missing: usize,
+}
+
+impl FontSet {
+    /// Finds the text, monospace and fallback fonts. With `font_path`, that
+    /// font is used for all body text.
+    pub fn load(font_path: Option<&str>) -> Result<FontSet, AppError> {
+        let dirs = font_dirs();
+        let mut faces: Vec<FontFace> = Vec::new();
+        let mut loaded: HashMap<PathBuf, usize> = HashMap::new();
+        let mut load = |path: PathBuf, faces: &mut Vec<FontFace>| -> Option<usize> {
+            if let Some(index) = loaded.get(&path) {
+                return Some(*index);
+            }
+            let face = FontFace::load(&path)?;
+            faces.push(face);
+            loaded.insert(path, faces.len() - 1);
+            Some(faces.len() - 1)
+        };
+
+        let text = match font_path {
+            Some(path) => {
+                let index = load(PathBuf::from(path), &mut faces).ok_or_else(|| {
+                    AppError::ExportError(format!(
+                        "{} is not a TrueType or OpenType font that can be embedded",
+                        path
+                    ))
+                })?;
+                [index; 4]
+            }
+            None => load_family(&TEXT_FAMILIES, &dirs, &mut faces, &mut load).ok_or_else(|| {
+                AppError::ExportError(
+                    "No TrueType font found to embed; choose a font file for the export"
+                        .to_string(),
+                )
+            })?,
+        };
+        let mono = load_family(&MONO_FAMILIES, &dirs, &mut faces, &mut load).unwrap_or(text);
+        let fallbacks = FALLBACK_FONTS
+            .iter()
+            .filter_map(|name| find_font(&dirs, name))
+            .filter_map(|path| load(path, &mut faces))
+            .collect();
+
+        Ok(FontSet {
+            faces,
+            text,
+            mono,
+            fallbacks,
+            missing: 0,
+        })
+    }
+
+    fn styled(&self, style: FontStyle) -> usize {
+        let family = if style.mono { &self.mono } else { &self.text };
+        family[usize::from(style.bold) + usize::from(style.italic) * 2]
+    }
+
+    /// The face for one character: the styled face if it has the glyph,
+    /// else the first fallback that does, else the styled face's missing
+    /// glyph box.
+    fn face_for(&mut self, c: char, style: FontStyle) -> (usize, u16, f32) {
+        let styled = self.styled(style);
+        let candidates = std::iter::once(styled)
+            .chain(std::iter::once(self.text[0]))
+            .chain(self.fallbacks.clone());
+        for face in candidates {
+            if let Some((glyph, width)) = self.faces[face].glyph(c) {
+                return (face, glyph, width);
+            }
+        }
+        (styled, 0, self.faces[styled].notdef_width())
+    }
+
+    /// Width of `text` in points.
+    pub fn measure(&mut self, text: &str, style: FontStyle, size: f32) -> f32 {
+        text.chars()
+            .filter(|c| !c.is_control())
+            .map(|c| self.face_for(c, style).2)
+            .sum::<f32>()
+            * size
+            / 1000.0
+    }
+
+    /// Maps `text` to glyphs, split into runs of one face each, and
+    /// records them for embedding.
+    pub fn encode(&mut self, text: &str, style: FontStyle) -> Vec<GlyphRun> {
+        let mut runs: Vec<GlyphRun> = Vec::new();
+        for c in text.chars().filter(|c| !c.is_control()) {
+            let (face, glyph, width) = self.face_for(c, style);
+            if glyph == 0 {
+                self.missing += 1;
+            }
+            self.faces[face]
+                .used
+                .entry(glyph)
+                .or_insert_with(|| (width, c.to_string()));
+
+            match runs.last_mut().filter(|run| run.face == face) {
+                Some(run) => {
+                    run.glyphs.push(glyph);
+                    run.width += width;
+                }
+                None => runs.push(GlyphRun {
+                    face,
+                    glyphs: vec![glyph],
+                    width,
+                }),
+            }
+        }
+        runs
+    }
+
+    /// Whether some font has a glyph for every character of `text`.
+    pub fn covers(&mut self, text: &str, style: FontStyle) -> bool {
+        text.chars().all(|c| self.face_for(c, style).1 != 0)
+    }
+}
+
+fn font_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = FONT_DIRS.iter().map(PathBuf::from).collect();
+    if let Some(home) = std::env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        dirs.push(home.join(".local/share/fonts"));
+        dirs.push(home.join(".fonts"));
+        dirs.push(home.join("Library/Fonts"));
+    }
+    if let Some(windows) = std::env::var_os("WINDIR") {
+        dirs.push(PathBuf::from(windows).join("Fonts"));
+    }
+    dirs
+}
+
+fn find_font(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The first family whose regular face is installed. Missing bold or
+/// italic faces fall back to the regular one.
+fn load_family(
+    families: &[Family],
+    dirs: &[PathBuf],
+    faces: &mut Vec<FontFace>,
+    load: &mut impl FnMut(PathBuf, &mut Vec<FontFace>) -> Option<usize>,
+) -> Option<[usize; 4]> {
+    families.iter().find_map(|family| {
+        let regular = load(find_font(dirs, family[0])?, faces)?;
+        let mut styles = [regular; 4];
+        for (slot, name) in styles.iter_mut().zip(family).skip(1) {
+            if let Some(index) = find_font(dirs, name).and_then(|path| load(path, faces)) {
+                *slot = index;
+            }
+        }
+        Some(styles)
+    })
+}
@@ -0,0 +1,195 @@
+// src-tauri/src/export/images.rs
+//! Loads the images a document shows, from data URIs or local files, in
+//! a form the PDF writer can embed. JPEG data is embedded as is; PNG is
+//! decoded into samples with a separate alpha channel.
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::io::Cursor;
+
+pub enum ImageData {
+    Jpeg {
+        data: Vec<u8>,
+        components: u8,
+        /// Adobe's CMYK JPEGs store inverted values.
+        inverted: bool,
+    },
+    Samples {
+        /// One byte per component, gray or RGB.
+        pixels: Vec<u8>,
+        components: u8,
+        alpha: Option<Vec<u8>>,
+    },
+}
+
+pub struct LoadedImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: ImageData,
+}
+
+/// Loads the image an `img` element's `src` points at. The error says why
+/// the image is left out.
+pub fn load_image(src: &str) -> Result<LoadedImage, String> {
+    let bytes = read_source(src)?;
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        let (width, height, components, inverted) =
+            jpeg_info(&bytes).ok_or_else(|| "The JPEG image is damaged".to_string())?;
+        if !matches!(components, 1 | 3 | 4) {
+            return Err(format!(
+                "JPEG images with {} channels are not supported",
+                components
+            ));
+        }
+        Ok(LoadedImage {
+            width,
+            height,
+            data: ImageData::Jpeg {
+                data: bytes,
+                components,
+                inverted: inverted && components == 4,
+            },
+        })
+    } else if bytes.starts_with(b"\x89PNG") {
+        decode_png(&bytes)
+    } else {
+        Err("Only JPEG and PNG images can be exported".to_string())
+    }
+}
+
+fn read_source(src: &str) -> Result<Vec<u8>, String> {
+    if let Some(data) = src.strip_prefix("data:") {
+        let (header, payload) = data
+            .split_once(',')
+            .ok_or_else(|| "The data URI has no data".to_string())?;
+        if !header.ends_with(";base64") {
+            return Err("Only base64 data URIs are supported".to_string());
+        }
+        let payload: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+        return BASE64
+            .decode(payload)
+            .map_err(|e| format!("The data URI is not valid base64: {}", e));
+    }
+
+    // Local files, as written or as Tauri's asset protocol serves them.
+    let path = [
+        "file://",
+        "asset://localhost/",
+        "http://asset.localhost/",
+        "https://asset.localhost/",
+    ]
+    .iter()
+    .find_map(|prefix| src.strip_prefix(prefix))
+    .map(percent_decode);
+    let path = match path {
+        Some(path) => path,
+        None if src.starts_with("http://") || src.starts_with("https://") => {
+            return Err("Remote images are not downloaded".to_string())
+        }
+        None => src.to_string(),
+    };
+    std::fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path, e))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Width, height, component count and Adobe marker of a JPEG, from its
+/// frame header.
+fn jpeg_info(data: &[u8]) -> Option<(u32, u32, u8, bool)> {
+    let mut adobe = false;
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+            i += 2;
+            continue;
+        }
+
+        let length = usize::from(u16::from_be_bytes([data[i + 2], data[i + 3]]));
+        if marker == 0xEE && data.get(i + 4..i + 9) == Some(b"Adobe".as_slice()) {
+            adobe = true;
+        }
+        // Start-of-frame markers, leaving out DHT, JPG and DAC.
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let frame = data.get(i + 4..i + 10)?;
+            let height = u32::from(u16::from_be_bytes([frame[1], frame[2]]));
+            let width = u32::from(u16::from_be_bytes([frame[3], frame[4]]));
+            return Some((width, height, frame[5], adobe));
+        }
+        i += 2 + length;
+    }
+    None
+}
+
+fn decode_png(bytes: &[u8]) -> Result<LoadedImage, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("The PNG image cannot be read: {}", e))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|e| format!("The PNG image cannot be read: {}", e))?;
+    buffer.truncate(info.buffer_size());
+
+    let (components, has_alpha) = match info.color_type {
+        png::ColorType::Grayscale => (1, false),
+        png::ColorType::GrayscaleAlpha => (1, true),
+        png::ColorType::Rgb => (3, false),
+        png::ColorType::Rgba => (3, true),
+        png::ColorType::Indexed => return Err("The PNG palette could not be expanded".to_string()),
+    };
+    let (pixels, alpha) = if has_alpha {
+        let stride = components + 1;
+        let pixels = buffer
+            .chunks_exact(stride)
+            .flat_map(|pixel| pixel[..components].to_vec())
+            .collect();
+        let alpha: Vec<u8> = buffer
+            .chunks_exact(stride)
+            .map(|pixel| pixel[components])
+            .collect();
+        // A fully opaque alpha channel is left out.
+        (pixels, alpha.iter().any(|a| *a < 255).then_some(alpha))
+    } else {
+        (buffer, None)
+    };
+
+    Ok(LoadedImage {
+        width: info.width,
+        height: info.height,
+        data: ImageData::Samples {
+            pixels,
+            components: components as u8,
+            alpha,
+        },
+    })
+}
@@ -0,0 +1,1313 @@
+// src-tauri/src/export/layout.rs
+//! Lays document blocks out on pages. Blocks are cut into pieces that are
+//! never split across pages (a line of text, a table row, an image), and
+//! the pieces are stacked down each page, starting a new page when the
+//! next piece does not fit. Table rows taller than a page are cut between
+//! the lines of their cells first.
+use super::document::{Align, Block, ListItem, Script, Span, TableCell, TextKind, TextStyle};
+use super::fonts::{FontSet, FontStyle};
+use super::images::{load_image, LoadedImage};
+use std::collections::HashMap;
+
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const HEADING_SIZES: [f32; 6] = [24.0, 20.0, 16.0, 14.0, 12.0, 11.0];
+/// Line height as a multiple of the font size.
+const LINE_SPACING: f32 = 1.35;
+const PARAGRAPH_SPACING: f32 = 6.0;
+const ITEM_SPACING: f32 = 2.0;
+const LIST_INDENT: f32 = 18.0;
+const QUOTE_INDENT: f32 = 14.0;
+const CODE_PADDING: f32 = 5.0;
+const CELL_PADDING: f32 = 4.0;
+const BORDER_WIDTH: f32 = 0.5;
+const BAR_WIDTH: f32 = 2.0;
+/// Superscript and subscript size as a fraction of the text size.
+const SCRIPT_SCALE: f32 = 0.7;
+/// Images carry no physical size; they are drawn at 96 pixels per inch.
+const POINTS_PER_PIXEL: f32 = 0.75;
+/// Columns of wider tables are left out.
+const MAX_COLUMNS: usize = 1000;
+
+pub type Color = [f32; 3];
+
+const BLACK: Color = [0.0, 0.0, 0.0];
+const LINK_COLOR: Color = [0.1, 0.3, 0.75];
+const RULE_COLOR: Color = [0.7, 0.7, 0.7];
+const QUOTE_COLOR: Color = [0.8, 0.8, 0.8];
+const CODE_BACKGROUND: Color = [0.94, 0.94, 0.94];
+const HEADER_BACKGROUND: Color = [0.9, 0.9, 0.9];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Something drawn on a page. While a piece is laid out, `y` runs down
+/// from the piece's top edge and a rectangle's `y` is its top; on a page,
+/// they are PDF coordinates, with `y` running up and a rectangle's `y`
+/// its bottom.
+#[derive(Debug, Clone)]
+pub enum Op {
+    Text {
+        face: usize,
+        size: f32,
+        x: f32,
+        y: f32,
+        glyphs: Vec<u16>,
+        color: Color,
+    },
+    Fill {
+        rect: Rect,
+        color: Color,
+    },
+    Image {
+        image: usize,
+        rect: Rect,
+    },
+}
+
+impl Op {
+    fn shifted(mut self, dx: f32, dy: f32) -> Op {
+        match &mut self {
+            Op::Text { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            Op::Fill { rect, .. } | Op::Image { rect, .. } => *rect = rect.shifted(dx, dy),
+        }
+        self
+    }
+
+    /// Moves the op from piece to page coordinates.
+    fn placed(self, left: f32, top: f32) -> Op {
+        match self {
+            Op::Text {
+                face,
+                size,
+                x,
+                y,
+                glyphs,
+                color,
+            } => Op::Text {
+                face,
+                size,
+                x: left + x,
+                y: top - y,
+                glyphs,
+                color,
+            },
+            Op::Fill { rect, color } => Op::Fill {
+                rect: rect.placed(left, top),
+                color,
+            },
+            Op::Image { image, rect } => Op::Image {
+                image,
+                rect: rect.placed(left, top),
+            },
+        }
+    }
+}
+
+impl Rect {
+    fn shifted(self, dx: f32, dy: f32) -> Rect {
+        Rect {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..self
+        }
+    }
+
+    fn placed(self, left: f32, top: f32) -> Rect {
+        Rect {
+            x: left + self.x,
+            y: top - self.y - self.height,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PageGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl PageGeometry {
+    pub fn content_width(&self) -> f32 {
+        self.width - self.left - self.right
+    }
+
+    pub fn content_height(&self) -> f32 {
+        self.height - self.top - self.bottom
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Page {
+    pub ops: Vec<Op>,
+    pub links: Vec<(Rect, String)>,
+    /// Areas covered by comment marks, with their thread ids.
+    pub marks: Vec<(Rect, String)>,
+}
+
+/// A heading, for the document outline.
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub level: u8,
+    pub title: String,
+    pub page: usize,
+    pub top: f32,
+}
+
+pub struct Layout {
+    pub pages: Vec<Page>,
+    pub bookmarks: Vec<Bookmark>,
+    pub images: Vec<LoadedImage>,
+    /// Images left out because they could not be loaded.
+    pub skipped_images: usize,
+}
+
+pub fn layout_document(blocks: &[Block], fonts: &mut FontSet, geometry: &PageGeometry) -> Layout {
+    let mut layouter = Layouter {
+        fonts,
+        images: Vec::new(),
+        loaded: HashMap::new(),
+        skipped_images: 0,
+        content_height: geometry.content_height(),
+    };
+    let mut pieces = Vec::new();
+    layouter.blocks(blocks, 0.0, geometry.content_width(), &mut pieces);
+    let (pages, bookmarks) = paginate(pieces, geometry);
+
+    Layout {
+        pages,
+        bookmarks,
+        images: layouter.images,
+        skipped_images: layouter.skipped_images,
+    }
+}
+
+/// A part of a block that is kept on one page.
+#[derive(Debug, Default)]
+struct Piece {
+    height: f32,
+    space_before: f32,
+    /// Whether the piece moves to the next page with the piece after it.
+    keep_with_next: bool,
+    /// Whether the piece starts a new page.
+    page_break: bool,
+    /// The first line's baseline, to align list markers with.
+    baseline: f32,
+    ops: Vec<Op>,
+    links: Vec<(Rect, String)>,
+    marks: Vec<(Rect, String)>,
+    /// Left edges of the quote bars beside the piece.
+    bars: Vec<f32>,
+    bookmark: Option<(u8, String)>,
+    /// Where the piece may be cut, between the lines of stacked pieces.
+    breaks: Vec<f32>,
+}
+
+impl Piece {
+    fn shift(&mut self, dx: f32, dy: f32) {
+        self.ops = std::mem::take(&mut self.ops)
+            .into_iter()
+            .map(|op| op.shifted(dx, dy))
+            .collect();
+        for (rect, _) in self.links.iter_mut().chain(self.marks.iter_mut()) {
+            *rect = rect.shifted(dx, dy);
+        }
+        for bar in &mut self.bars {
+            *bar += dx;
+        }
+        for cut in &mut self.breaks {
+            *cut += dy;
+        }
+        self.baseline += dy;
+    }
+
+    /// Where a part of the piece starting at `start` and at most `max`
+    /// tall ends: at the last break that fits, or at `start + max` when
+    /// none does.
+    fn part_end(&self, start: f32, max: f32) -> f32 {
+        if start + max >= self.height {
+            return self.height;
+        }
+        // Breaks are in order, top to bottom.
+        self.breaks
+            .iter()
+            .rev()
+            .copied()
+            .find(|cut| *cut > start && *cut <= start + max)
+            .unwrap_or(start + max)
+    }
+
+    /// The part of the piece from `start` to `end`, moved up to the top.
+    /// Text and images go with the part their top edge is in; fills are
+    /// clipped to it.
+    fn part(&self, start: f32, end: f32) -> Piece {
+        // The first and last parts take whatever sticks out of the piece.
+        let low = if start <= 0.0 {
+            f32::NEG_INFINITY
+        } else {
+            start
+        };
+        let high = if end >= self.height {
+            f32::INFINITY
+        } else {
+            end
+        };
+        let inside = |y: f32| y >= low && y < high;
+
+        let ops = self
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Text { y, .. } => inside(*y).then(|| op.clone()),
+                Op::Image { rect, .. } => inside(rect.y).then(|| op.clone()),
+                Op::Fill { rect, color } => {
+                    let top = rect.y.max(low);
+                    let bottom = (rect.y + rect.height).min(high);
+                    (bottom > top).then_some(Op::Fill {
+                        rect: Rect {
+                            y: top,
+                            height: bottom - top,
+                            ..*rect
+                        },
+                        color: *color,
+                    })
+                }
+            })
+            .collect();
+        let rects = |rects: &[(Rect, String)]| {
+            rects
+                .iter()
+                .filter(|(rect, _)| inside(rect.y))
+                .cloned()
+                .collect()
+        };
+
+        let mut part = Piece {
+            height: end - start,
+            ops,
+            links: rects(&self.links),
+            marks: rects(&self.marks),
+            breaks: self
+                .breaks
+                .iter()
+                .copied()
+                .filter(|cut| *cut > start && *cut < end)
+                .collect(),
+            ..Piece::default()
+        };
+        part.shift(0.0, -start);
+        part
+    }
+}
+
+/// Stacks pieces into one, as the content of a table cell.
+fn stack(pieces: Vec<Piece>) -> Piece {
+    let mut stacked = Piece::default();
+    let mut previous_bars: Vec<f32> = Vec::new();
+    for (index, mut piece) in pieces.into_iter().enumerate() {
+        let gap = if index == 0 { 0.0 } else { piece.space_before };
+        let top = stacked.height + gap;
+        if index == 0 {
+            stacked.baseline = piece.baseline;
+        } else {
+            stacked.breaks.push(stacked.height);
+        }
+        for bar in &piece.bars {
+            let joined = previous_bars.contains(bar);
+            let bar_top = if joined { top - gap } else { top };
+            stacked.ops.push(Op::Fill {
+                rect: Rect {
+                    x: *bar,
+                    y: bar_top,
+                    width: BAR_WIDTH,
+                    height: top + piece.height - bar_top,
+                },
+                color: QUOTE_COLOR,
+            });
+        }
+        previous_bars = std::mem::take(&mut piece.bars);
+        piece.shift(0.0, top);
+        stacked.ops.append(&mut piece.ops);
+        stacked.links.append(&mut piece.links);
+        stacked.marks.append(&mut piece.marks);
+        stacked.breaks.append(&mut piece.breaks);
+        stacked.height = top + piece.height;
+    }
+    stacked
+}
+
+/// Adds a rectangle, merging it into the previous one when it continues
+/// that one on the same line.
+fn push_rect<T: PartialEq>(rects: &mut Vec<(Rect, T)>, rect: Rect, value: T) {
+    if let Some((last, last_value)) = rects.last_mut() {
+        let continues = (last.x + last.width - rect.x).abs() < 0.01
+            && (last.y - rect.y).abs() < 0.01
+            && (last.height - rect.height).abs() < 0.01;
+        if continues && *last_value == value {
+            last.width += rect.width;
+            return;
+        }
+    }
+    rects.push((rect, value));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    Word,
+    Space,
+    Break,
+}
+
+/// A word, a space or a line break, measured in the font it is set in.
+#[derive(Debug, Clone)]
+struct Item<'a> {
+    kind: ItemKind,
+    text: String,
+    span: &'a Span,
+    font: FontStyle,
+    size: f32,
+    /// How far the baseline is raised, for superscripts and subscripts.
+    rise: f32,
+    width: f32,
+}
+
+struct Line<'a> {
+    items: Vec<Item<'a>>,
+    /// Whether the line ends its paragraph or a forced break, so is not
+    /// justified.
+    last: bool,
+}
+
+struct Layouter<'a> {
+    fonts: &'a mut FontSet,
+    images: Vec<LoadedImage>,
+    /// Indexes into `images` by source; `None` for images that failed.
+    loaded: HashMap<String, Option<usize>>,
+    skipped_images: usize,
+    /// The height of a page's text area, which nothing may be taller than.
+    content_height: f32,
+}
+
+impl Layouter<'_> {
+    fn blocks(&mut self, blocks: &[Block], x: f32, width: f32, out: &mut Vec<Piece>) {
+        for block in blocks {
+            match block {
+                Block::Text { kind, align, spans } => {
+                    self.text(*kind, *align, spans, x, width, out)
+                }
+                Block::List {
+                    ordered,
+                    start,
+                    items,
+                } => self.list(*ordered, *start, items, x, width, out),
+                Block::Quote(blocks) => {
+                    let first = out.len();
+                    self.blocks(blocks, x + QUOTE_INDENT, width - QUOTE_INDENT, out);
+                    for piece in &mut out[first..] {
+                        piece.bars.push(x + 2.0);
+                    }
+                }
+                Block::Table(rows) => self.table(rows, x, width, out),
+                Block::Image { src, alt } => self.image(src, alt.as_deref(), x, width, out),
+                Block::Rule => out.push(Piece {
+                    height: 12.0,
+                    space_before: PARAGRAPH_SPACING,
+                    ops: vec![Op::Fill {
+                        rect: Rect {
+                            x,
+                            y: 6.0,
+                            width,
+                            height: BORDER_WIDTH,
+                        },
+                        color: RULE_COLOR,
+                    }],
+                    ..Piece::default()
+                }),
+                Block::PageBreak => out.push(Piece {
+                    page_break: true,
+                    ..Piece::default()
+                }),
+            }
+        }
+    }
+
+    fn text(
+        &mut self,
+        kind: TextKind,
+        align: Align,
+        spans: &[Span],
+        x: f32,
+        width: f32,
+        out: &mut Vec<Piece>,
+    ) {
+        let (size, space_before) = match kind {
+            TextKind::Paragraph => (BODY_SIZE, PARAGRAPH_SPACING),
+            TextKind::Heading(level) => {
+                let size = HEADING_SIZES[usize::from(level.clamp(1, 6)) - 1];
+                (size, size * 0.75)
+            }
+            TextKind::Code => (CODE_SIZE, PARAGRAPH_SPACING),
+        };
+        let code = kind == TextKind::Code;
+        let padding = if code { CODE_PADDING } else { 0.0 };
+        let heading = matches!(kind, TextKind::Heading(_));
+
+        let items = self.items(spans, size, heading, code, width - 2.0 * padding);
+        let lines = break_lines(items, width - 2.0 * padding, code);
+        let line_count = lines.len();
+        for (index, line) in lines.into_iter().enumerate() {
+            let mut piece = self.line(line, size, align, x + padding, width - 2.0 * padding);
+            piece.keep_with_next = heading;
+            if index == 0 {
+                piece.space_before = space_before;
+                if let TextKind::Heading(level) = kind {
+                    let title: String = spans.iter().map(|span| span.text.as_str()).collect();
+                    piece.bookmark = Some((level, title.trim().to_string()));
+                }
+            }
+            if code {
+                let pad_top = if index == 0 { CODE_PADDING } else { 0.0 };
+                let pad_bottom = if index + 1 == line_count {
+                    CODE_PADDING
+                } else {
+                    0.0
+                };
+                piece.shift(0.0, pad_top);
+                piece.height += pad_top + pad_bottom;
+                piece.ops.insert(
+                    0,
+                    Op::Fill {
+                        rect: Rect {
+                            x,
+                            y: 0.0,
+                            width,
+                            height: piece.height,
+                        },
+                        color: CODE_BACKGROUND,
+                    },
+                );
+            }
+            out.push(piece);
+        }
+    }
+
+    /// Splits spans into words, spaces and breaks. Outside preformatted
+    /// text, runs of whitespace count as one space. Words wider than the
+    /// line are split between characters.
+    fn items<'s>(
+        &mut self,
+        spans: &'s [Span],
+        size: f32,
+        bold: bool,
+        preformatted: bool,
+        max_width: f32,
+    ) -> Vec<Item<'s>> {
+        let mut items: Vec<Item> = Vec::new();
+        for span in spans {
+            let font = FontStyle {
+                bold: bold || span.style.bold,
+                italic: span.style.italic,
+                mono: preformatted || span.style.code,
+            };
+            let (item_size, rise) = match span.style.script {
+                Script::Normal => (size, 0.0),
+                Script::Super => (size * SCRIPT_SCALE, size * 0.35),
+                Script::Sub => (size * SCRIPT_SCALE, -size * 0.15),
+            };
+            let mut push = |kind: ItemKind, text: String, width: f32| {
+                items.push(Item {
+                    kind,
+                    text,
+                    span,
+                    font,
+                    size: item_size,
+                    rise,
+                    width,
+                })
+            };
+
+            let text = span.text.replace('\t', "    ");
+            for (line_index, line) in text.split('\n').enumerate() {
+                if line_index > 0 {
+                    push(ItemKind::Break, String::new(), 0.0);
+                }
+                for (is_space, run) in whitespace_runs(line) {
+                    if is_space {
+                        let run = if preformatted { run } else { " " };
+                        let width = self.fonts.measure(run, font, item_size);
+                        push(ItemKind::Space, run.to_string(), width);
+                        continue;
+                    }
+                    let width = self.fonts.measure(run, font, item_size);
+                    if width <= max_width {
+                        push(ItemKind::Word, run.to_string(), width);
+                        continue;
+                    }
+                    for (index, part) in self
+                        .split_word(run, font, item_size, max_width)
+                        .into_iter()
+                        .enumerate()
+                    {
+                        if index > 0 {
+                            // Breaks the line without taking up room.
+                            push(ItemKind::Space, String::new(), 0.0);
+                        }
+                        let width = self.fonts.measure(&part, font, item_size);
+                        push(ItemKind::Word, part, width);
+                    }
+                }
+            }
+        }
+
+        if !preformatted {
+            items.dedup_by(|next, previous| {
+                next.kind == ItemKind::Space
+                    && previous.kind == ItemKind::Space
+                    && !previous.text.is_empty()
+            });
+        }
+        items
+    }
+
+    fn split_word(
+        &mut self,
+        word: &str,
+        font: FontStyle,
+        size: f32,
+        max_width: f32,
+    ) -> Vec<String> {
+        let mut parts = vec![String::new()];
+        let mut used = 0.0;
+        for c in word.chars() {
+            let width = self.fonts.measure(c.encode_utf8(&mut [0; 4]), font, size);
+            let current = parts.last_mut().expect("parts is never empty");
+            if used + width > max_width && !current.is_empty() {
+                parts.push(String::new());
+                used = 0.0;
+            }
+            parts.last_mut().expect("parts is never empty").push(c);
+            used += width;
+        }
+        parts
+    }
+
+    /// Sets one line as a piece.
+    fn line(&mut self, line: Line, size: f32, align: Align, x: f32, width: f32) -> Piece {
+        let mut items = line.items;
+        while items.last().is_some_and(|item| item.kind != ItemKind::Word) {
+            items.pop();
+        }
+        let height = size * LINE_SPACING;
+        let baseline = size * (LINE_SPACING - 1.0) / 2.0 + size * 0.8;
+
+        let natural: f32 = items.iter().map(|item| item.width).sum();
+        let stretchable = items
+            .iter()
+            .filter(|item| item.kind == ItemKind::Space && item.width > 0.0)
+            .count();
+        let slack = (width - natural).max(0.0);
+        let (start, extra) = match align {
+            Align::Left => (0.0, 0.0),
+            Align::Center => (slack / 2.0, 0.0),
+            Align::Right => (slack, 0.0),
+            Align::Justify if !line.last && stretchable > 0 => (0.0, slack / stretchable as f32),
+            Align::Justify => (0.0, 0.0),
+        };
+
+        let mut backgrounds: Vec<(Rect, Color)> = Vec::new();
+        let mut rules: Vec<(Rect, Color)> = Vec::new();
+        let mut piece = Piece {
+            height,
+            baseline,
+            ..Piece::default()
+        };
+        let mut text_ops = Vec::new();
+        let mut pen = x + start;
+        for item in &items {
+            let style = &item.span.style;
+            let advance = match item.kind {
+                ItemKind::Space if item.width > 0.0 => item.width + extra,
+                _ => item.width,
+            };
+            let color = if style.link.is_some() {
+                LINK_COLOR
+            } else {
+                BLACK
+            };
+
+            if item.kind == ItemKind::Word {
+                let mut run_x = pen;
+                for run in self.fonts.encode(&item.text, item.font) {
+                    text_ops.push(Op::Text {
+                        face: run.face,
+                        size: item.size,
+                        x: run_x,
+                        y: baseline - item.rise,
+                        glyphs: run.glyphs,
+                        color,
+                    });
+                    run_x += run.width * item.size / 1000.0;
+                }
+            }
+
+            let text_box = Rect {
+                x: pen,
+                y: baseline - size * 0.85,
+                width: advance,
+                height: size * 1.1,
+            };
+            if let Some(highlight) = style.highlight {
+                push_rect(&mut backgrounds, text_box, highlight);
+            } else if style.code {
+                push_rect(&mut backgrounds, text_box, CODE_BACKGROUND);
+            }
+            let thickness = size * 0.06;
+            if style.underline || style.link.is_some() {
+                let rect = Rect {
+                    x: pen,
+                    y: baseline + size * 0.12,
+                    width: advance,
+                    height: thickness,
+                };
+                push_rect(&mut rules, rect, color);
+            }
+            if style.strike {
+                let rect = Rect {
+                    x: pen,
+                    y: baseline - size * 0.3,
+                    width: advance,
+                    height: thickness,
+                };
+                push_rect(&mut rules, rect, color);
+            }
+            if let Some(link) = &style.link {
+                push_rect(&mut piece.links, text_box, link.clone());
+            }
+            for thread in &style.threads {
+                push_rect(&mut piece.marks, text_box, thread.clone());
+            }
+            pen += advance;
+        }
+
+        piece.ops = backgrounds
+            .into_iter()
+            .map(|(rect, color)| Op::Fill { rect, color })
+            .chain(text_ops)
+            .chain(
+                rules
+                    .into_iter()
+                    .map(|(rect, color)| Op::Fill { rect, color }),
+            )
+            .collect();
+        piece
+    }
+
+    fn list(
+        &mut self,
+        ordered: bool,
+        start: u32,
+        items: &[ListItem],
+        x: f32,
+        width: f32,
+        out: &mut Vec<Piece>,
+    ) {
+        let plain = FontStyle::default();
+        for (index, item) in items.iter().enumerate() {
+            let marker = match item.checked {
+                Some(checked) => {
+                    let (symbol, ascii) = if checked {
+                        ("☑", "[x]")
+                    } else {
+                        ("☐", "[ ]")
+                    };
+                    if self.fonts.covers(symbol, plain) {
+                        symbol.to_string()
+                    } else {
+                        ascii.to_string()
+                    }
+                }
+                None if ordered => format!("{}.", start as usize + index),
+                None => "•".to_string(),
+            };
+
+            let first = out.len();
+            self.blocks(&item.blocks, x + LIST_INDENT, width - LIST_INDENT, out);
+            if out.len() == first {
+                out.push(Piece {
+                    height: BODY_SIZE * LINE_SPACING,
+                    baseline: BODY_SIZE * (LINE_SPACING - 1.0) / 2.0 + BODY_SIZE * 0.8,
+                    ..Piece::default()
+                });
+            }
+
+            let marker_width = self.fonts.measure(&marker, plain, BODY_SIZE);
+            let mut marker_x = x + LIST_INDENT - 5.0 - marker_width;
+            let piece = &mut out[first];
+            piece.space_before = if index == 0 {
+                PARAGRAPH_SPACING
+            } else {
+                ITEM_SPACING
+            };
+            let baseline = if piece.baseline > 0.0 {
+                piece.baseline
+            } else {
+                BODY_SIZE
+            };
+            for run in self.fonts.encode(&marker, plain) {
+                piece.ops.push(Op::Text {
+                    face: run.face,
+                    size: BODY_SIZE,
+                    x: marker_x,
+                    y: baseline,
+                    glyphs: run.glyphs,
+                    color: BLACK,
+                });
+                marker_x += run.width * BODY_SIZE / 1000.0;
+            }
+        }
+    }
+
+    fn table(&mut self, rows: &[Vec<TableCell>], x: f32, width: f32, out: &mut Vec<Piece>) {
+        let columns = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .fold(0usize, |sum, cell| sum.saturating_add(cell.colspan))
+            })
+            .max()
+            .unwrap_or(0)
+            .min(MAX_COLUMNS);
+        if columns == 0 {
+            return;
+        }
+
+        // Columns share the width in proportion to their widest text, with
+        // none narrower than half an even share.
+        let mut wanted = vec![width / columns as f32 / 2.0; columns];
+        for row in rows {
+            let mut column = 0;
+            for cell in row {
+                if column >= columns {
+                    break;
+                }
+                if cell.colspan == 1 {
+                    let natural = self.natural_width(&cell.blocks) + 2.0 * CELL_PADDING;
+                    wanted[column] = wanted[column].max(natural.min(width));
+                }
+                column += cell.colspan;
+            }
+        }
+        let total: f32 = wanted.iter().sum();
+        let widths: Vec<f32> = wanted.iter().map(|w| w / total * width).collect();
+
+        for (row_index, row) in rows.iter().enumerate() {
+            let mut cells = Vec::new();
+            let mut column = 0;
+            for cell in row {
+                if column >= columns {
+                    break;
+                }
+                let end = column.saturating_add(cell.colspan).min(columns);
+                let cell_x = x + widths[..column].iter().sum::<f32>();
+                let cell_width: f32 = widths[column..end].iter().sum();
+                column = end;
+
+                let blocks = if cell.header {
+                    emboldened(&cell.blocks)
+                } else {
+                    cell.blocks.clone()
+                };
+                let mut pieces = Vec::new();
+                let inner = cell_width - 2.0 * CELL_PADDING;
+                self.blocks(&blocks, cell_x + CELL_PADDING, inner, &mut pieces);
+                cells.push((cell_x, cell_width, cell.header, stack(pieces)));
+            }
+
+            // A row taller than a page is cut into parts that fit, each
+            // cell between two of its lines.
+            let max_content = self.content_height - 2.0 * CELL_PADDING;
+            let mut starts = vec![0.0; cells.len()];
+            let mut first_part = true;
+            loop {
+                let ends: Vec<f32> = cells
+                    .iter()
+                    .zip(&starts)
+                    .map(|((_, _, _, content), start)| content.part_end(*start, max_content))
+                    .collect();
+                let height = starts
+                    .iter()
+                    .zip(&ends)
+                    .map(|(start, end)| end - start)
+                    .fold(0.0, f32::max)
+                    + 2.0 * CELL_PADDING;
+                let mut piece = Piece {
+                    height,
+                    space_before: if row_index == 0 && first_part {
+                        PARAGRAPH_SPACING
+                    } else {
+                        0.0
+                    },
+                    ..Piece::default()
+                };
+                let mut borders = Vec::new();
+                for ((cell_x, cell_width, header, content), (start, end)) in
+                    cells.iter().zip(starts.iter().zip(&ends))
+                {
+                    if *header {
+                        piece.ops.push(Op::Fill {
+                            rect: Rect {
+                                x: *cell_x,
+                                y: 0.0,
+                                width: *cell_width,
+                                height,
+                            },
+                            color: HEADER_BACKGROUND,
+                        });
+                    }
+                    let mut part = content.part(*start, *end);
+                    part.shift(0.0, CELL_PADDING);
+                    piece.ops.append(&mut part.ops);
+                    piece.links.append(&mut part.links);
+                    piece.marks.append(&mut part.marks);
+                    borders.extend(cell_borders(*cell_x, *cell_width, height));
+                }
+                piece.ops.extend(borders);
+                out.push(piece);
+
+                let done = cells
+                    .iter()
+                    .zip(&ends)
+                    .all(|((_, _, _, content), end)| *end >= content.height);
+                if done {
+                    break;
+                }
+                starts = ends;
+                first_part = false;
+            }
+        }
+    }
+
+    /// The width a cell's widest paragraph would take on one line.
+    fn natural_width(&mut self, blocks: &[Block]) -> f32 {
+        let mut widest: f32 = 0.0;
+        for block in blocks {
+            if let Block::Text { spans, .. } = block {
+                let width = spans
+                    .iter()
+                    .map(|span| {
+                        let font = FontStyle {
+                            bold: span.style.bold,
+                            italic: span.style.italic,
+                            mono: span.style.code,
+                        };
+                        self.fonts.measure(&span.text, font, BODY_SIZE)
+                    })
+                    .sum();
+                widest = widest.max(width);
+            }
+        }
+        widest
+    }
+
+    fn image(&mut self, src: &str, alt: Option<&str>, x: f32, width: f32, out: &mut Vec<Piece>) {
+        let index = match self.loaded.get(src) {
+            Some(index) => *index,
+            None => {
+                let index = load_image(src).ok().map(|image| {
+                    self.images.push(image);
+                    self.images.len() - 1
+                });
+                self.loaded.insert(src.to_string(), index);
+                index
+            }
+        };
+
+        let Some(index) = index else {
+            self.skipped_images += 1;
+            // The description stands in for the picture.
+            if let Some(alt) = alt.filter(|alt| !alt.trim().is_empty()) {
+                let span = Span {
+                    text: format!("[{}]", alt.trim()),
+                    style: TextStyle {
+                        italic: true,
+                        ..Default::default()
+                    },
+                };
+                self.text(TextKind::Paragraph, Align::Left, &[span], x, width, out);
+            }
+            return;
+        };
+
+        let image = &self.images[index];
+        let natural_width = image.width as f32 * POINTS_PER_PIXEL;
+        let natural_height = image.height as f32 * POINTS_PER_PIXEL;
+        let scale = 1.0_f32
+            .min(width / natural_width)
+            .min(self.content_height / natural_height);
+        let (image_width, image_height) = (natural_width * scale, natural_height * scale);
+        out.push(Piece {
+            height: image_height,
+            space_before: PARAGRAPH_SPACING,
+            ops: vec![Op::Image {
+                image: index,
+                rect: Rect {
+                    x,
+                    y: 0.0,
+                    width: image_width,
+                    height: image_height,
+                },
+            }],
+            ..Piece::default()
+        });
+    }
+}
+
+/// A header cell's blocks, with their text in bold.
+fn emboldened(blocks: &[Block]) -> Vec<Block> {
+    let mut blocks = blocks.to_vec();
+    for block in &mut blocks {
+        if let Block::Text { spans, .. } = block {
+            for span in spans {
+                span.style.bold = true;
+            }
+        }
+    }
+    blocks
+}
+
+fn cell_borders(x: f32, width: f32, height: f32) -> [Op; 4] {
+    let edge = |x: f32, y: f32, width: f32, height: f32| Op::Fill {
+        rect: Rect {
+            x,
+            y,
+            width,
+            height,
+        },
+        color: RULE_COLOR,
+    };
+    let half = BORDER_WIDTH / 2.0;
+    [
+        edge(x - half, -half, width + BORDER_WIDTH, BORDER_WIDTH),
+        edge(x - half, height - half, width + BORDER_WIDTH, BORDER_WIDTH),
+        edge(x - half, -half, BORDER_WIDTH, height + BORDER_WIDTH),
+        edge(x + width - half, -half, BORDER_WIDTH, height + BORDER_WIDTH),
+    ]
+}
+
+/// Splits text into runs of whitespace and non-whitespace, flagged by
+/// whether they are whitespace.
+fn whitespace_runs(text: &str) -> Vec<(bool, &str)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (index, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|in_space| in_space != space) {
+            runs.push((!space, &text[start..index]));
+            start = index;
+        }
+        in_space = Some(space);
+    }
+    if let Some(space) = in_space {
+        runs.push((space, &text[start..]));
+    }
+    runs
+}
+
+/// Breaks items into lines no wider than `width`, at spaces. Outside
+/// preformatted text, spaces at the start of a line are dropped.
+fn break_lines(items: Vec<Item>, width: f32, preformatted: bool) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut current: Vec<Item> = Vec::new();
+    let mut used = 0.0;
+    let mut last_space: Option<usize> = None;
+    for item in items {
+        match item.kind {
+            ItemKind::Break => {
+                lines.push(Line {
+                    items: std::mem::take(&mut current),
+                    last: true,
+                });
+                used = 0.0;
+                last_space = None;
+            }
+            ItemKind::Space => {
+                if current.is_empty() && !preformatted {
+                    continue;
+                }
+                last_space = Some(current.len());
+                used += item.width;
+                current.push(item);
+            }
+            ItemKind::Word => {
+                if let Some(index) = last_space.filter(|index| *index > 0) {
+                    if used + item.width > width + 0.01 {
+                        let rest = current.split_off(index + 1);
+                        current.truncate(index);
+                        lines.push(Line {
+                            items: std::mem::replace(&mut current, rest),
+                            last: false,
+                        });
+                        used = current.iter().map(|item| item.width).sum();
+                        last_space = None;
+                    }
+                }
+                used += item.width;
+                current.push(item);
+            }
+        }
+    }
+    // A trailing line break does not start another line.
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(Line {
+            items: current,
+            last: true,
+        });
+    }
+    lines
+}
+
+/// Places pieces on pages, top to bottom.
+fn paginate(pieces: Vec<Piece>, geometry: &PageGeometry) -> (Vec<Page>, Vec<Bookmark>) {
+    let top = geometry.height - geometry.top;
+    let mut pages = vec![Page::default()];
+    let mut bookmarks = Vec::new();
+    let mut cursor = top;
+    let mut previous_bars: Vec<f32> = Vec::new();
+
+    for index in 0..pieces.len() {
+        let piece = &pieces[index];
+        let mut at_top = cursor >= top;
+        if piece.page_break {
+            if !at_top {
+                pages.push(Page::default());
+                cursor = top;
+            }
+            continue;
+        }
+
+        let mut needed = piece.space_before + piece.height;
+        if piece.keep_with_next {
+            if let Some(next) = pieces.get(index + 1).filter(|next| !next.page_break) {
+                needed += next.space_before + next.height;
+            }
+        }
+        if !at_top && cursor - needed < geometry.bottom {
+            pages.push(Page::default());
+            cursor = top;
+            at_top = true;
+        }
+
+        let gap = if at_top { 0.0 } else { piece.space_before };
+        let piece_top = cursor - gap;
+        let page = pages.last_mut().expect("there is always a page");
+        for bar in &piece.bars {
+            let joined = !at_top && previous_bars.contains(bar);
+            let bar_top = if joined { cursor } else { piece_top };
+            page.ops.push(Op::Fill {
+                rect: Rect {
+                    x: geometry.left + bar,
+                    y: piece_top - piece.height,
+                    width: BAR_WIDTH,
+                    height: bar_top - (piece_top - piece.height),
+                },
+                color: QUOTE_COLOR,
+            });
+        }
+        page.ops.extend(
+            piece
+                .ops
+                .iter()
+                .cloned()
+                .map(|op| op.placed(geometry.left, piece_top)),
+        );
+        for (rect, link) in &piece.links {
+            page.links
+                .push((rect.placed(geometry.left, piece_top), link.clone()));
+        }
+        for (rect, thread) in &piece.marks {
+            page.marks
+                .push((rect.placed(geometry.left, piece_top), thread.clone()));
+        }
+        if let Some((level, title)) = &piece.bookmark {
+            bookmarks.push(Bookmark {
+                level: *level,
+                title: title.clone(),
+                page: pages.len() - 1,
+                top: piece_top,
+            });
+        }
+
+        previous_bars = piece.bars.clone();
+        cursor = piece_top - piece.height;
+    }
+    (pages, bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEOMETRY: PageGeometry = PageGeometry {
+        width: 300.0,
+        height: 300.0,
+        top: 50.0,
+        right: 50.0,
+        bottom: 50.0,
+        left: 50.0,
+    };
+
+    fn item<'a>(kind: ItemKind, text: &str, span: &'a Span) -> Item<'a> {
+        Item {
+            kind,
+            text: text.to_string(),
+            span,
+            font: FontStyle::default(),
+            size: BODY_SIZE,
+            rise: 0.0,
+            width: if kind == ItemKind::Break { 0.0 } else { 10.0 },
+        }
+    }
+
+    fn words(line: &Line) -> Vec<String> {
+        line.items
+            .iter()
+            .filter(|item| item.kind == ItemKind::Word)
+            .map(|item| item.text.clone())
+            .collect()
+    }
+
+    fn text_op(y: f32) -> Op {
+        Op::Text {
+            face: 0,
+            size: BODY_SIZE,
+            x: 0.0,
+            y,
+            glyphs: vec![1],
+            color: BLACK,
+        }
+    }
+
+    fn line_piece(height: f32) -> Piece {
+        Piece {
+            height,
+            baseline: height * 0.7,
+            ops: vec![text_op(height * 0.7)],
+            ..Piece::default()
+        }
+    }
+
+    #[test]
+    fn breaks_lines_at_spaces() {
+        let span = Span {
+            text: String::new(),
+            style: TextStyle::default(),
+        };
+        let items = vec![
+            item(ItemKind::Space, " ", &span),
+            item(ItemKind::Word, "a", &span),
+            item(ItemKind::Space, " ", &span),
+            item(ItemKind::Word, "b", &span),
+            item(ItemKind::Space, " ", &span),
+            item(ItemKind::Word, "c", &span),
+            item(ItemKind::Break, "", &span),
+            item(ItemKind::Word, "d", &span),
+        ];
+        let lines = break_lines(items, 30.0, false);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(words(&lines[0]), ["a", "b"]);
+        assert!(!lines[0].last);
+        assert_eq!(lines[0].items[0].kind, ItemKind::Word);
+        assert_eq!(words(&lines[1]), ["c"]);
+        assert!(lines[1].last);
+        assert_eq!(words(&lines[2]), ["d"]);
+    }
+
+    #[test]
+    fn keeps_leading_spaces_in_preformatted_text() {
+        let span = Span {
+            text: String::new(),
+            style: TextStyle::default(),
+        };
+        let items = vec![
+            item(ItemKind::Space, "  ", &span),
+            item(ItemKind::Word, "x", &span),
+            item(ItemKind::Break, "", &span),
+        ];
+        let lines = break_lines(items, 100.0, true);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].items[0].kind, ItemKind::Space);
+    }
+
+    #[test]
+    fn cuts_stacked_pieces_between_lines() {
+        let stacked = stack((0..10).map(|_| line_piece(20.0)).collect());
+        assert_eq!(stacked.height, 200.0);
+        assert_eq!(stacked.part_end(0.0, 75.0), 60.0);
+        assert_eq!(stacked.part_end(60.0, 75.0), 120.0);
+        assert_eq!(stacked.part_end(180.0, 75.0), 200.0);
+
+        let part = stacked.part(60.0, 120.0);
+        assert_eq!(part.height, 60.0);
+        assert_eq!(part.ops.len(), 3);
+        assert!(part.ops.iter().all(|op| match op {
+            Op::Text { y, .. } => *y >= 0.0 && *y < 60.0,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn cuts_pieces_without_breaks_at_the_limit() {
+        let tall = line_piece(500.0);
+        assert_eq!(tall.part_end(0.0, 150.0), 150.0);
+    }
+
+    #[test]
+    fn starts_new_pages_when_pieces_do_not_fit() {
+        let pieces: Vec<Piece> = (0..25).map(|_| line_piece(20.0)).collect();
+        let (pages, _) = paginate(pieces, &GEOMETRY);
+        // 200 points of text area hold ten lines.
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0].ops.len(), 10);
+        assert_eq!(pages[2].ops.len(), 5);
+        for page in &pages {
+            for op in &page.ops {
+                if let Op::Text { y, .. } = op {
+                    assert!(*y >= GEOMETRY.bottom && *y <= GEOMETRY.height - GEOMETRY.top);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn page_breaks_start_new_pages_once() {
+        let pieces = vec![
+            line_piece(20.0),
+            Piece {
+                page_break: true,
+                ..Piece::default()
+            },
+            Piece {
+                page_break: true,
+                ..Piece::default()
+            },
+            line_piece(20.0),
+        ];
+        let (pages, _) = paginate(pieces, &GEOMETRY);
+        assert_eq!(pages.len(), 2);
+    }
+}
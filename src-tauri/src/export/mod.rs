@@ -0,0 +1,185 @@
+// src-tauri/src/export/mod.rs
+//! Exports editor documents as PDF.
+pub mod document;
+pub mod endnotes;
+pub mod fonts;
+pub mod images;
+pub mod layout;
+pub mod subset;
+pub mod writer;
+
+use crate::comments::models::CommentThread;
+use crate::errors::AppError;
+use document::parse_document;
+use fonts::FontSet;
+use layout::{layout_document, PageGeometry};
+use serde::{Deserialize, Serialize};
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+/// The narrowest text column the margins may leave, in points.
+const MIN_CONTENT_SIZE: f32 = 72.0;
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Paper {
+    A3,
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl Paper {
+    /// Portrait width and height in millimetres.
+    fn size_mm(self) -> (f32, f32) {
+        match self {
+            Paper::A3 => (297.0, 420.0),
+            Paper::A4 => (210.0, 297.0),
+            Paper::A5 => (148.0, 210.0),
+            Paper::Letter => (215.9, 279.4),
+            Paper::Legal => (215.9, 355.6),
+        }
+    }
+}
+
+/// How comment threads appear in the PDF.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentExport {
+    /// Left out.
+    #[default]
+    None,
+    /// As highlight annotations with replies, which PDF viewers show in
+    /// their comment panes.
+    Annotations,
+    /// As numbered notes in a section at the end.
+    Endnotes,
+}
+
+/// Page margins in millimetres.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Margins {
+            top: 20.0,
+            right: 20.0,
+            bottom: 20.0,
+            left: 20.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    pub paper: Paper,
+    /// A custom page size in millimetres, overriding `paper`.
+    pub page_width_mm: Option<f32>,
+    pub page_height_mm: Option<f32>,
+    pub landscape: bool,
+    pub margins: Margins,
+    pub comments: CommentExport,
+    /// A TrueType or OpenType font for the body text, instead of the
+    /// system fonts.
+    pub font_path: Option<String>,
+    /// Defaults to the first heading.
+    pub title: Option<String>,
+}
+
+impl ExportOptions {
+    fn geometry(&self) -> Result<PageGeometry, AppError> {
+        let (paper_width, paper_height) = self.paper.size_mm();
+        let width = self.page_width_mm.unwrap_or(paper_width);
+        let height = self.page_height_mm.unwrap_or(paper_height);
+        let (width, height) = if self.landscape {
+            (width.max(height), width.min(height))
+        } else {
+            (width, height)
+        };
+
+        let margins = [
+            self.margins.top,
+            self.margins.right,
+            self.margins.bottom,
+            self.margins.left,
+        ];
+        if !(width.is_finite() && height.is_finite())
+            || margins.iter().any(|m| !m.is_finite() || *m < 0.0)
+        {
+            return Err(AppError::ExportError(
+                "Page size and margins must be positive numbers".to_string(),
+            ));
+        }
+        let geometry = PageGeometry {
+            width: width * POINTS_PER_MM,
+            height: height * POINTS_PER_MM,
+            top: self.margins.top * POINTS_PER_MM,
+            right: self.margins.right * POINTS_PER_MM,
+            bottom: self.margins.bottom * POINTS_PER_MM,
+            left: self.margins.left * POINTS_PER_MM,
+        };
+        if geometry.content_width() < MIN_CONTENT_SIZE
+            || geometry.content_height() < MIN_CONTENT_SIZE
+        {
+            return Err(AppError::ExportError(
+                "The margins leave too little room for the text".to_string(),
+            ));
+        }
+        Ok(geometry)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub page_count: usize,
+    /// Images left out because they could not be loaded.
+    pub skipped_images: usize,
+    /// Characters none of the fonts could show.
+    pub missing_characters: usize,
+}
+
+/// Lays out the document HTML and writes it as a PDF to `output_path`.
+pub fn export_pdf(
+    html: &str,
+    threads: &[CommentThread],
+    options: &ExportOptions,
+    output_path: &str,
+) -> Result<ExportSummary, AppError> {
+    let geometry = options.geometry()?;
+    let mut fonts = FontSet::load(options.font_path.as_deref())?;
+
+    let mut blocks = parse_document(html);
+    if options.comments == CommentExport::Endnotes {
+        endnotes::add_endnotes(&mut blocks, html, threads);
+    }
+    let layout = layout_document(&blocks, &mut fonts, &geometry);
+
+    let annotated = match options.comments {
+        CommentExport::Annotations => threads,
+        CommentExport::None | CommentExport::Endnotes => &[],
+    };
+    writer::write_pdf(
+        &layout,
+        &fonts,
+        annotated,
+        options.title.as_deref(),
+        &geometry,
+        output_path,
+    )?;
+
+    Ok(ExportSummary {
+        page_count: layout.pages.len(),
+        skipped_images: layout.skipped_images,
+        missing_characters: fonts.missing,
+    })
+}
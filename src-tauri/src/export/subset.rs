@@ -0,0 +1,330 @@
+// src-tauri/src/export/subset.rs
+//! Cuts TrueType fonts down to the glyphs a document uses before they are
+//! embedded. Glyph ids are kept, so the content streams, the `W` array and
+//! the identity CID-to-glyph mapping stay valid; the outlines of unused
+//! glyphs are emptied and the tables a PDF viewer does not read (cmap,
+//! layout and naming tables) are left out.
+use std::collections::BTreeSet;
+
+/// The tables PDF viewers need to render an embedded TrueType font.
+const KEPT_TABLES: [&[u8; 4]; 9] = [
+    b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep",
+];
+
+/// Components of composite glyphs nest; deeper ones are not followed.
+const MAX_COMPOSITE_DEPTH: usize = 16;
+
+// Composite glyph flags.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// The font with only `glyphs`, the glyphs their composites are built
+/// from and the missing glyph box. Returns `None` when the font is not
+/// a well-formed TrueType font, which is then embedded whole.
+pub fn subset_truetype(data: &[u8], glyphs: &BTreeSet<u16>) -> Option<Vec<u8>> {
+    let tables = table_directory(data)?;
+    let table = |tag: &[u8; 4]| {
+        tables
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, bytes)| *bytes)
+    };
+    let head = table(b"head")?;
+    let glyf = table(b"glyf")?;
+    let loca = table(b"loca")?;
+    let glyph_count = usize::from(read_u16(table(b"maxp")?, 4)?);
+    let long_offsets = read_u16(head, 50)? == 1;
+
+    let glyph_range = |id: u16| -> Option<(usize, usize)> {
+        let index = usize::from(id);
+        if index >= glyph_count {
+            return None;
+        }
+        let (start, end) = if long_offsets {
+            (
+                read_u32(loca, index * 4)? as usize,
+                read_u32(loca, index * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                usize::from(read_u16(loca, index * 2)?) * 2,
+                usize::from(read_u16(loca, index * 2 + 2)?) * 2,
+            )
+        };
+        (start <= end && end <= glyf.len()).then_some((start, end))
+    };
+
+    // The requested glyphs and every glyph their composites use.
+    let mut kept = BTreeSet::from([0u16]);
+    let mut pending: Vec<(u16, usize)> = glyphs.iter().map(|id| (*id, 0)).collect();
+    pending.push((0, 0));
+    while let Some((id, depth)) = pending.pop() {
+        kept.insert(id);
+        if depth >= MAX_COMPOSITE_DEPTH {
+            continue;
+        }
+        let Some((start, end)) = glyph_range(id) else {
+            continue;
+        };
+        for component in components(&glyf[start..end]) {
+            if !kept.contains(&component) {
+                pending.push((component, depth + 1));
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((glyph_count + 1) * 4);
+    for id in 0..glyph_count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        let range = u16::try_from(id)
+            .ok()
+            .filter(|id| kept.contains(id))
+            .and_then(glyph_range);
+        if let Some((start, end)) = range {
+            new_glyf.extend_from_slice(&glyf[start..end]);
+            while new_glyf.len() % 4 != 0 {
+                new_glyf.push(0);
+            }
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    // Long offsets, and a checksum adjustment worked out once the font is
+    // written.
+    new_head
+        .get_mut(50..52)?
+        .copy_from_slice(&1u16.to_be_bytes());
+    new_head.get_mut(8..12)?.copy_from_slice(&[0; 4]);
+
+    let subset: Vec<([u8; 4], Vec<u8>)> = KEPT_TABLES
+        .iter()
+        .filter_map(|tag| {
+            let bytes = match *tag {
+                b"glyf" => std::mem::take(&mut new_glyf),
+                b"loca" => std::mem::take(&mut new_loca),
+                b"head" => std::mem::take(&mut new_head),
+                _ => table(tag)?.to_vec(),
+            };
+            Some((**tag, bytes))
+        })
+        .collect();
+    Some(write_font(subset))
+}
+
+/// The tables of a TrueType font, by tag.
+fn table_directory(data: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let version = read_u32(data, 0)?;
+    if version != 0x0001_0000 && version != u32::from_be_bytes(*b"true") {
+        return None;
+    }
+    let count = usize::from(read_u16(data, 4)?);
+    (0..count)
+        .map(|index| {
+            let record = 12 + index * 16;
+            let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(data, record + 8)? as usize;
+            let length = read_u32(data, record + 12)? as usize;
+            Some((tag, data.get(offset..offset.checked_add(length)?)?))
+        })
+        .collect()
+}
+
+/// The glyphs a composite glyph is built from; none for simple glyphs.
+fn components(glyph: &[u8]) -> Vec<u16> {
+    let mut found = Vec::new();
+    let Some(contours) = read_u16(glyph, 0) else {
+        return found;
+    };
+    // A negative contour count marks a composite glyph.
+    if contours & 0x8000 == 0 {
+        return found;
+    }
+    let mut at = 10;
+    while let (Some(flags), Some(component)) = (read_u16(glyph, at), read_u16(glyph, at + 2)) {
+        found.push(component);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            at += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            at += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            at += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    found
+}
+
+/// Writes tables as a font file, with the directory, checksums and
+/// padding the format asks for.
+fn write_font(mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, bytes) in &tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(bytes).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        offset += bytes.len().next_multiple_of(4);
+    }
+    let mut head_at = None;
+    for (tag, bytes) in &tables {
+        if tag == b"head" {
+            head_at = Some(font.len());
+        }
+        font.extend_from_slice(bytes);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head_at) = head_at {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head_at + 8..head_at + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ttf_parser::{Face, GlyphId};
+
+    /// A triangle, as a simple glyph with one contour.
+    fn triangle(size: i16) -> Vec<u8> {
+        let mut glyph = Vec::new();
+        for value in [1, 0, 0, size, size, 2] {
+            glyph.extend_from_slice(&value.to_be_bytes());
+        }
+        glyph.extend_from_slice(&0u16.to_be_bytes());
+        glyph.extend_from_slice(&[0x01; 3]);
+        for value in [0, size, -size / 2, 0, 0, size] {
+            glyph.extend_from_slice(&value.to_be_bytes());
+        }
+        glyph
+    }
+
+    /// A composite glyph drawing glyph `component`.
+    fn composite(component: u16) -> Vec<u8> {
+        let mut glyph = Vec::new();
+        for value in [-1i16, 0, 0, 500, 500] {
+            glyph.extend_from_slice(&value.to_be_bytes());
+        }
+        for value in [ARG_1_AND_2_ARE_WORDS | 0x0002, component, 0, 0] {
+            glyph.extend_from_slice(&value.to_be_bytes());
+        }
+        glyph
+    }
+
+    /// A font with the missing glyph box, two triangles and a composite of
+    /// the first triangle.
+    fn test_font() -> Vec<u8> {
+        let glyphs = [triangle(300), triangle(500), triangle(700), composite(1)];
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for glyph in &glyphs {
+            loca.extend_from_slice(&((glyf.len() / 2) as u16).to_be_bytes());
+            glyf.extend_from_slice(glyph);
+            glyf.resize(glyf.len().next_multiple_of(2), 0);
+        }
+        loca.extend_from_slice(&((glyf.len() / 2) as u16).to_be_bytes());
+
+        let mut head = vec![0; 54];
+        head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[40..44].copy_from_slice(&[0x02, 0xBC, 0x02, 0xBC]);
+        let mut hhea = vec![0; 36];
+        hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&4u16.to_be_bytes());
+        let hmtx: Vec<u8> = (0..4).flat_map(|_| [0x02, 0x58, 0, 0]).collect();
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend_from_slice(&4u16.to_be_bytes());
+        let mut name = vec![0; 6];
+        name[4..6].copy_from_slice(&6u16.to_be_bytes());
+
+        write_font(vec![
+            (*b"glyf", glyf),
+            (*b"head", head),
+            (*b"hhea", hhea),
+            (*b"hmtx", hmtx),
+            (*b"loca", loca),
+            (*b"maxp", maxp),
+            (*b"name", name),
+        ])
+    }
+
+    #[test]
+    fn keeps_used_glyphs_and_their_components() {
+        let font = test_font();
+        let subset = subset_truetype(&font, &BTreeSet::from([3])).unwrap();
+
+        let face = Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), 4);
+        assert!(face.glyph_bounding_box(GlyphId(0)).is_some());
+        assert!(face.glyph_bounding_box(GlyphId(1)).is_some());
+        assert!(face.glyph_bounding_box(GlyphId(2)).is_none());
+        assert!(face.glyph_bounding_box(GlyphId(3)).is_some());
+        assert_eq!(face.glyph_hor_advance(GlyphId(2)), Some(600));
+        assert!(face.tables().name.is_none());
+    }
+
+    #[test]
+    fn checksums_the_whole_font() {
+        let subset = subset_truetype(&test_font(), &BTreeSet::from([1])).unwrap();
+        assert_eq!(subset.len() % 4, 0);
+        assert_eq!(checksum(&subset), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn leaves_other_fonts_alone() {
+        assert!(subset_truetype(b"OTTO\0\0", &BTreeSet::new()).is_none());
+        assert!(subset_truetype(&[], &BTreeSet::new()).is_none());
+        let mut truncated = test_font();
+        truncated.truncate(40);
+        assert!(subset_truetype(&truncated, &BTreeSet::new()).is_none());
+    }
+}
@@ -0,0 +1,651 @@
+// src-tauri/src/export/writer.rs
+//! Writes a laid-out document as a PDF: pages with their text, fills and
+//! images, embedded fonts, link annotations, an outline from the headings
+//! and, optionally, comment threads as highlight annotations with replies.
+use super::fonts::{FontFace, FontSet};
+use super::images::{ImageData, LoadedImage};
+use super::layout::{Bookmark, Layout, Op, PageGeometry, Rect};
+use super::subset::subset_truetype;
+use crate::comments::models::{Comment, CommentThread};
+use crate::errors::AppError;
+use crate::html::text::html_to_text;
+use chrono::{DateTime, Utc};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// ToUnicode CMaps list at most this many mappings per section.
+const CMAP_SECTION_SIZE: usize = 100;
+const HIGHLIGHT_COLOR: [f32; 3] = [1.0, 0.85, 0.3];
+/// Size of the note icon for threads whose marks are gone.
+const NOTE_SIZE: f32 = 18.0;
+
+pub fn write_pdf(
+    layout: &Layout,
+    fonts: &FontSet,
+    threads: &[CommentThread],
+    title: Option<&str>,
+    geometry: &PageGeometry,
+    output_path: &str,
+) -> Result<(), AppError> {
+    let mut doc = Document::with_version("1.7");
+    let pages_id = doc.new_object_id();
+
+    let mut font_resources = Dictionary::new();
+    for (index, face) in fonts.faces.iter().enumerate() {
+        if !face.used.is_empty() {
+            let font_id = add_font(&mut doc, face);
+            font_resources.set(format!("F{}", index), font_id);
+        }
+    }
+    let mut image_resources = Dictionary::new();
+    for (index, image) in layout.images.iter().enumerate() {
+        let image_id = add_image(&mut doc, image);
+        image_resources.set(format!("Im{}", index), image_id);
+    }
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => font_resources,
+        "XObject" => image_resources,
+    });
+
+    let mut page_ids = Vec::new();
+    for page in &layout.pages {
+        let mut content = Stream::new(Dictionary::new(), content_stream(&page.ops));
+        let _ = content.compress();
+        let content_id = doc.add_object(content);
+
+        let mut annotations: Vec<Object> = Vec::new();
+        for (rect, uri) in &page.links {
+            // Links within the document have no target once it is a PDF.
+            if uri.starts_with('#') {
+                continue;
+            }
+            annotations.push(
+                doc.add_object(dictionary! {
+                    "Type" => "Annot",
+                    "Subtype" => "Link",
+                    "Rect" => rect_array(rect),
+                    "Border" => vec![0.into(), 0.into(), 0.into()],
+                    "A" => dictionary! {
+                        "S" => "URI",
+                        "URI" => Object::string_literal(uri.as_str()),
+                    },
+                })
+                .into(),
+            );
+        }
+
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), geometry.width.into(), geometry.height.into()],
+            "Contents" => content_id,
+            "Resources" => resources_id,
+            "Annots" => annotations,
+        });
+        page_ids.push(page_id);
+    }
+
+    add_comment_annotations(&mut doc, layout, &page_ids, threads, geometry)?;
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids.iter().map(|id| Object::from(*id)).collect::<Vec<_>>(),
+            "Count" => page_ids.len() as i64,
+        }),
+    );
+
+    let mut catalog = dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    };
+    if let Some(outline_id) = add_outline(&mut doc, &layout.bookmarks, &page_ids) {
+        catalog.set("Outlines", outline_id);
+        catalog.set("PageMode", "UseOutlines");
+    }
+    let catalog_id = doc.add_object(catalog);
+
+    let title = title.map(str::to_string).or_else(|| {
+        layout
+            .bookmarks
+            .first()
+            .map(|bookmark| bookmark.title.clone())
+    });
+    let mut info = dictionary! {
+        "Producer" => Object::string_literal("tiptaptoe"),
+        "CreationDate" => Object::string_literal(pdf_date(Utc::now())),
+    };
+    if let Some(title) = title {
+        info.set("Title", text_string(&title));
+    }
+    let info_id = doc.add_object(info);
+
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.save(output_path)
+        .map_err(|e| AppError::ExportError(format!("Cannot write {}: {}", output_path, e)))?;
+    Ok(())
+}
+
+fn content_stream(ops: &[Op]) -> Vec<u8> {
+    let mut content = String::new();
+    for op in ops {
+        match op {
+            Op::Fill { rect, color } => content.push_str(&format!(
+                "{} rg {} {} {} {} re f\n",
+                color_operands(color),
+                number(rect.x),
+                number(rect.y),
+                number(rect.width),
+                number(rect.height)
+            )),
+            Op::Text {
+                face,
+                size,
+                x,
+                y,
+                glyphs,
+                color,
+            } => {
+                let hex: String = glyphs
+                    .iter()
+                    .map(|glyph| format!("{:04X}", glyph))
+                    .collect();
+                content.push_str(&format!(
+                    "BT /F{} {} Tf {} rg {} {} Td <{}> Tj ET\n",
+                    face,
+                    number(*size),
+                    color_operands(color),
+                    number(*x),
+                    number(*y),
+                    hex
+                ));
+            }
+            Op::Image { image, rect } => content.push_str(&format!(
+                "q {} 0 0 {} {} {} cm /Im{} Do Q\n",
+                number(rect.width),
+                number(rect.height),
+                number(rect.x),
+                number(rect.y),
+                image
+            )),
+        }
+    }
+    content.into_bytes()
+}
+
+fn number(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn color_operands(color: &[f32; 3]) -> String {
+    color.map(number).join(" ")
+}
+
+fn rect_array(rect: &Rect) -> Vec<Object> {
+    [rect.x, rect.y, rect.x + rect.width, rect.y + rect.height]
+        .map(Object::from)
+        .to_vec()
+}
+
+/// Embeds a face as a Type 0 font with Identity-H encoding, so the content
+/// streams address glyphs by id. TrueType faces are cut down to the glyphs
+/// the document uses; CFF faces are embedded whole, so an OpenType/CFF
+/// font chosen for the export adds its full size to the file.
+fn add_font(doc: &mut Document, face: &FontFace) -> ObjectId {
+    let subset = if face.cff {
+        None
+    } else {
+        let glyphs: BTreeSet<u16> = face.used.keys().copied().collect();
+        subset_truetype(&face.data, &glyphs)
+    };
+    // Subset fonts are named with a tag that tells them apart from the
+    // whole font.
+    let name = match &subset {
+        Some(_) => format!("{}+{}", subset_tag(&face.used), face.postscript_name),
+        None => face.postscript_name.clone(),
+    };
+    let data = subset.unwrap_or_else(|| face.data.clone());
+
+    let mut file_dict = Dictionary::new();
+    if face.cff {
+        file_dict.set("Subtype", "OpenType");
+    } else {
+        file_dict.set("Length1", data.len() as i64);
+    }
+    let mut file = Stream::new(file_dict, data);
+    let _ = file.compress();
+    let file_id = doc.add_object(file);
+
+    let mut flags = 32;
+    if face.italic {
+        flags |= 64;
+    }
+    if face.monospaced {
+        flags |= 1;
+    }
+    let descriptor_id = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => Object::Name(name.clone().into_bytes()),
+        "Flags" => flags,
+        "FontBBox" => face.bbox.map(Object::from).to_vec(),
+        "ItalicAngle" => face.italic_angle,
+        "Ascent" => face.ascent,
+        "Descent" => face.descent,
+        "CapHeight" => face.cap_height,
+        "StemV" => 80,
+        if face.cff { "FontFile3" } else { "FontFile2" } => file_id,
+    });
+
+    let mut cid_font = dictionary! {
+        "Type" => "Font",
+        "Subtype" => if face.cff { "CIDFontType0" } else { "CIDFontType2" },
+        "BaseFont" => Object::Name(name.clone().into_bytes()),
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Identity"),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor_id,
+        "W" => glyph_widths(&face.used),
+    };
+    if !face.cff {
+        cid_font.set("CIDToGIDMap", "Identity");
+    }
+    let cid_font_id = doc.add_object(cid_font);
+
+    let mut to_unicode = Stream::new(Dictionary::new(), to_unicode_cmap(&face.used));
+    let _ = to_unicode.compress();
+    let to_unicode_id = doc.add_object(to_unicode);
+
+    doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => Object::Name(name.into_bytes()),
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![Object::from(cid_font_id)],
+        "ToUnicode" => to_unicode_id,
+    })
+}
+
+/// Six capital letters derived from the glyphs in a subset.
+fn subset_tag(used: &BTreeMap<u16, (f32, String)>) -> String {
+    let mut hash = used.keys().fold(0xcbf2_9ce4_8422_2325u64, |hash, glyph| {
+        (hash ^ u64::from(*glyph)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    (0..6)
+        .map(|_| {
+            let letter = char::from(b'A' + (hash % 26) as u8);
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
+
+/// The `W` array, with consecutive glyphs grouped.
+fn glyph_widths(used: &BTreeMap<u16, (f32, String)>) -> Vec<Object> {
+    let mut groups: Vec<(u16, Vec<Object>)> = Vec::new();
+    for (glyph, (width, _)) in used {
+        match groups.last_mut() {
+            Some((first, widths)) if usize::from(*first) + widths.len() == usize::from(*glyph) => {
+                widths.push(Object::from(width.round()))
+            }
+            _ => groups.push((*glyph, vec![Object::from(width.round())])),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(first, widths)| [Object::from(i64::from(first)), Object::Array(widths)])
+        .collect()
+}
+
+fn to_unicode_cmap(used: &BTreeMap<u16, (f32, String)>) -> Vec<u8> {
+    let mappings: Vec<String> = used
+        .iter()
+        .filter(|(glyph, _)| **glyph != 0)
+        .map(|(glyph, (_, text))| {
+            let unicode: String = text
+                .encode_utf16()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            format!("<{:04X}> <{}>", glyph, unicode)
+        })
+        .collect();
+
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    for section in mappings.chunks(CMAP_SECTION_SIZE) {
+        cmap.push_str(&format!("{} beginbfchar\n", section.len()));
+        for mapping in section {
+            cmap.push_str(mapping);
+            cmap.push('\n');
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap.into_bytes()
+}
+
+fn add_image(doc: &mut Document, image: &LoadedImage) -> ObjectId {
+    let color_space = |components: u8| match components {
+        1 => "DeviceGray",
+        4 => "DeviceCMYK",
+        _ => "DeviceRGB",
+    };
+    let mut dict = dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => image.width as i64,
+        "Height" => image.height as i64,
+        "BitsPerComponent" => 8,
+    };
+
+    match &image.data {
+        ImageData::Jpeg {
+            data,
+            components,
+            inverted,
+        } => {
+            dict.set("ColorSpace", color_space(*components));
+            dict.set("Filter", "DCTDecode");
+            if *inverted {
+                dict.set(
+                    "Decode",
+                    [1, 0, 1, 0, 1, 0, 1, 0].map(Object::from).to_vec(),
+                );
+            }
+            doc.add_object(Stream::new(dict, data.clone()))
+        }
+        ImageData::Samples {
+            pixels,
+            components,
+            alpha,
+        } => {
+            dict.set("ColorSpace", color_space(*components));
+            if let Some(alpha) = alpha {
+                let mut mask = Stream::new(
+                    dictionary! {
+                        "Type" => "XObject",
+                        "Subtype" => "Image",
+                        "Width" => image.width as i64,
+                        "Height" => image.height as i64,
+                        "BitsPerComponent" => 8,
+                        "ColorSpace" => "DeviceGray",
+                    },
+                    alpha.clone(),
+                );
+                let _ = mask.compress();
+                dict.set("SMask", doc.add_object(mask));
+            }
+            let mut stream = Stream::new(dict, pixels.clone());
+            let _ = stream.compress();
+            doc.add_object(stream)
+        }
+    }
+}
+
+/// Adds each thread as a highlight over its marked text, carrying the
+/// first comment, with the other comments as replies. A resolved thread
+/// gets an "Accepted" review state, as PDF viewers record resolution.
+fn add_comment_annotations(
+    doc: &mut Document,
+    layout: &Layout,
+    page_ids: &[ObjectId],
+    threads: &[CommentThread],
+    geometry: &PageGeometry,
+) -> Result<(), AppError> {
+    for thread in threads {
+        let mut comments = thread.visible_comments();
+        let Some(first) = comments.next() else {
+            continue;
+        };
+
+        // The thread's marks on each page.
+        let marks: Vec<(usize, Vec<Rect>)> = layout
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(page, content)| {
+                let rects = content
+                    .marks
+                    .iter()
+                    .filter(|(_, id)| *id == thread.id)
+                    .map(|(rect, _)| *rect)
+                    .collect::<Vec<_>>();
+                (page, rects)
+            })
+            .filter(|(_, rects)| !rects.is_empty())
+            .collect();
+
+        let (page, anchor, parent) = match marks.split_first() {
+            Some(((page, rects), rest)) => {
+                let mut highlight = comment_annotation("Highlight", first, thread, bounds(rects));
+                highlight.set("QuadPoints", quad_points(rects));
+                let parent = add_annotation(doc, page_ids[*page], highlight)?;
+                // Marks running onto later pages are grouped with the first.
+                for (page, rects) in rest {
+                    let mut part = comment_annotation("Highlight", first, thread, bounds(rects));
+                    part.set("QuadPoints", quad_points(rects));
+                    part.set("IRT", parent);
+                    part.set("RT", "Group");
+                    add_annotation(doc, page_ids[*page], part)?;
+                }
+                (*page, bounds(rects), parent)
+            }
+            None => {
+                let anchor = Rect {
+                    x: geometry.width - geometry.right + 4.0,
+                    y: geometry.height - geometry.top - NOTE_SIZE,
+                    width: NOTE_SIZE,
+                    height: NOTE_SIZE,
+                };
+                let mut note = comment_annotation("Text", first, thread, anchor);
+                note.set("Name", "Comment");
+                let parent = add_annotation(doc, page_ids[0], note)?;
+                (0, anchor, parent)
+            }
+        };
+
+        for reply in comments {
+            let mut annotation = comment_annotation("Text", reply, thread, anchor);
+            annotation.set("IRT", parent);
+            annotation.set("Name", "Comment");
+            add_annotation(doc, page_ids[page], annotation)?;
+        }
+        if let Some(resolved_at) = thread.resolved_at {
+            let mut state = dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Text",
+                "Rect" => rect_array(&anchor),
+                "IRT" => parent,
+                "State" => Object::string_literal("Accepted"),
+                "StateModel" => Object::string_literal("Review"),
+                "CreationDate" => Object::string_literal(pdf_date(resolved_at)),
+                "M" => Object::string_literal(pdf_date(resolved_at)),
+                "F" => 32,
+            };
+            if let Some(user) = &thread.resolved_by {
+                state.set("T", text_string(user));
+            }
+            add_annotation(doc, page_ids[page], state)?;
+        }
+    }
+    Ok(())
+}
+
+fn comment_annotation(
+    subtype: &str,
+    comment: &Comment,
+    thread: &CommentThread,
+    rect: Rect,
+) -> Dictionary {
+    let modified = comment.updated_at.unwrap_or(comment.created_at);
+    dictionary! {
+        "Type" => "Annot",
+        "Subtype" => subtype,
+        "Rect" => rect_array(&rect),
+        "Contents" => text_string(html_to_text(&comment.content).trim()),
+        "T" => text_string(&comment.user_id),
+        "NM" => text_string(&format!("{}/{}", thread.id, comment.id)),
+        "CreationDate" => Object::string_literal(pdf_date(comment.created_at)),
+        "M" => Object::string_literal(pdf_date(modified)),
+        "C" => HIGHLIGHT_COLOR.map(Object::from).to_vec(),
+        "F" => 4,
+    }
+}
+
+fn add_annotation(
+    doc: &mut Document,
+    page_id: ObjectId,
+    annotation: Dictionary,
+) -> Result<ObjectId, AppError> {
+    let annotation_id = doc.add_object(annotation);
+    doc.get_object_mut(page_id)
+        .and_then(Object::as_dict_mut)
+        .and_then(|page| page.get_mut(b"Annots"))
+        .and_then(Object::as_array_mut)
+        .map_err(|e| AppError::ExportError(format!("Cannot annotate page: {}", e)))?
+        .push(annotation_id.into());
+    Ok(annotation_id)
+}
+
+fn bounds(rects: &[Rect]) -> Rect {
+    let left = rects.iter().map(|r| r.x).fold(f32::MAX, f32::min);
+    let bottom = rects.iter().map(|r| r.y).fold(f32::MAX, f32::min);
+    let right = rects.iter().map(|r| r.x + r.width).fold(f32::MIN, f32::max);
+    let top = rects
+        .iter()
+        .map(|r| r.y + r.height)
+        .fold(f32::MIN, f32::max);
+    Rect {
+        x: left,
+        y: bottom,
+        width: right - left,
+        height: top - bottom,
+    }
+}
+
+/// QuadPoints in the order viewers expect: top left, top right, bottom
+/// left, bottom right.
+fn quad_points(rects: &[Rect]) -> Vec<Object> {
+    rects
+        .iter()
+        .flat_map(|r| {
+            let (right, top) = (r.x + r.width, r.y + r.height);
+            [r.x, top, right, top, r.x, r.y, right, r.y]
+        })
+        .map(Object::from)
+        .collect()
+}
+
+/// Builds the outline from the headings, nesting each under the nearest
+/// heading of a higher level before it.
+fn add_outline(
+    doc: &mut Document,
+    bookmarks: &[Bookmark],
+    page_ids: &[ObjectId],
+) -> Option<ObjectId> {
+    let bookmarks: Vec<&Bookmark> = bookmarks.iter().filter(|b| !b.title.is_empty()).collect();
+    if bookmarks.is_empty() {
+        return None;
+    }
+
+    let mut parents: Vec<Option<usize>> = Vec::new();
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    for (index, bookmark) in bookmarks.iter().enumerate() {
+        while open
+            .last()
+            .is_some_and(|(level, _)| *level >= bookmark.level)
+        {
+            open.pop();
+        }
+        parents.push(open.last().map(|(_, parent)| *parent));
+        open.push((bookmark.level, index));
+    }
+    let children = |parent: Option<usize>| -> Vec<usize> {
+        (0..bookmarks.len())
+            .filter(|i| parents[*i] == parent)
+            .collect()
+    };
+    let descendants = |index: usize| -> i64 {
+        let mut count = 0;
+        let mut ancestor = index + 1;
+        while ancestor < bookmarks.len() && bookmarks[ancestor].level > bookmarks[index].level {
+            count += 1;
+            ancestor += 1;
+        }
+        count
+    };
+
+    let outline_id = doc.new_object_id();
+    let ids: Vec<ObjectId> = bookmarks.iter().map(|_| doc.new_object_id()).collect();
+    for (index, bookmark) in bookmarks.iter().enumerate() {
+        let parent = parents[index].map_or(outline_id, |parent| ids[parent]);
+        let siblings = children(parents[index]);
+        let position = siblings.iter().position(|i| *i == index).unwrap_or(0);
+        let mut item = dictionary! {
+            "Title" => text_string(&bookmark.title),
+            "Parent" => parent,
+            "Dest" => vec![
+                page_ids[bookmark.page].into(),
+                "XYZ".into(),
+                Object::Null,
+                bookmark.top.into(),
+                Object::Null,
+            ],
+        };
+        if position > 0 {
+            item.set("Prev", ids[siblings[position - 1]]);
+        }
+        if let Some(next) = siblings.get(position + 1) {
+            item.set("Next", ids[*next]);
+        }
+        let own = children(Some(index));
+        if let (Some(first), Some(last)) = (own.first(), own.last()) {
+            item.set("First", ids[*first]);
+            item.set("Last", ids[*last]);
+            item.set("Count", descendants(index));
+        }
+        doc.objects.insert(ids[index], Object::Dictionary(item));
+    }
+
+    let top = children(None);
+    doc.objects.insert(
+        outline_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Outlines",
+            "First" => ids[top[0]],
+            "Last" => ids[top[top.len() - 1]],
+            "Count" => bookmarks.len() as i64,
+        }),
+    );
+    Some(outline_id)
+}
+
+fn pdf_date(date: DateTime<Utc>) -> String {
+    date.format("D:%Y%m%d%H%M%SZ").to_string()
+}
+
+/// A PDF text string: literal when ASCII, UTF-16 with a byte order mark
+/// otherwise.
+fn text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        return Object::string_literal(text);
+    }
+    let bytes = [0xFE, 0xFF]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+        .collect();
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
@@ -0,0 +1,91 @@
+// src-tauri/src/html/mod.rs
+pub mod text;
+pub mod tokenizer;
+
+/// Elements that start a new block of text in TipTap output.
+pub fn is_block_element(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "li"
+            | "ul"
+            | "ol"
+            | "blockquote"
+            | "pre"
+            | "div"
+            | "table"
+            | "tr"
+            | "td"
+            | "th"
+            | "hr"
+    )
+}
+
+pub fn is_void_element(name: &str) -> bool {
+    matches!(
+        name,
+        "br" | "hr" | "img" | "input" | "meta" | "link" | "col" | "area" | "base" | "wbr"
+    )
+}
+
+pub fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
+/// Serializes tokens back into HTML.
+pub fn render(tokens: &[tokenizer::Token]) -> String {
+    tokens.iter().map(tokenizer::Token::to_html).collect()
+}
+
+/// Inserts `block` after the first `position` top-level elements of the
+/// document, or at the end when `position` is `None` or past the last one.
+pub fn insert_block(html: &str, block: &str, position: Option<usize>) -> String {
+    let Some(position) = position else {
+        return format!("{}{}", html, block);
+    };
+
+    let tokens = tokenizer::tokenize(html);
+    let mut depth = 0usize;
+    let mut seen = 0;
+    let mut split = tokens.len();
+    for (index, token) in tokens.iter().enumerate() {
+        if seen == position && depth == 0 {
+            split = index;
+            break;
+        }
+        match token {
+            tokenizer::Token::StartTag {
+                name, self_closing, ..
+            } => {
+                if *self_closing || is_void_element(name) {
+                    seen += usize::from(depth == 0);
+                } else {
+                    depth += 1;
+                }
+            }
+            tokenizer::Token::EndTag { .. } => {
+                depth = depth.saturating_sub(1);
+                seen += usize::from(depth == 0);
+            }
+            _ => {}
+        }
+    }
+
+    format!(
+        "{}{}{}",
+        render(&tokens[..split]),
+        block,
+        render(&tokens[split..])
+    )
+}
@@ -0,0 +1,271 @@
+// src-tauri/src/html/text.rs
+//! Converts editor HTML into readable plain text for prompts: headings
+//! become `#` lines, lists keep their markers and tables become pipe rows.
+use super::tokenizer::{tokenize, Token};
+
+/// A run of document text under one heading.
+#[derive(Debug, Clone)]
+pub struct TextSection {
+    pub number: u32,
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+pub fn html_to_text(html: &str) -> String {
+    let mut writer = TextWriter::default();
+    for token in tokenize(html) {
+        writer.push(token);
+    }
+    writer.finish()
+}
+
+/// Splits text produced by [`html_to_text`] at its headings. Text before
+/// the first heading forms an untitled first section.
+pub fn split_sections(text: &str) -> Vec<TextSection> {
+    let mut sections: Vec<TextSection> = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut body = String::new();
+    let mut in_code = false;
+
+    let mut close = |heading: Option<String>, body: &mut String| {
+        if heading.is_some() || !body.trim().is_empty() {
+            sections.push(TextSection {
+                number: sections.len() as u32 + 1,
+                heading,
+                text: body.trim().to_string(),
+            });
+        }
+        body.clear();
+    };
+
+    for line in text.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+        }
+        match heading_title(line).filter(|_| !in_code) {
+            Some(title) => close(heading.replace(title.to_string()), &mut body),
+            None => {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+    }
+    close(heading, &mut body);
+
+    sections
+}
+
+fn heading_title(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(str::trim)
+}
+
+#[derive(Default)]
+struct TextWriter {
+    out: String,
+    inline: String,
+    marker: String,
+    /// `Some(next number)` for ordered lists, `None` for bullet lists.
+    lists: Vec<Option<u32>>,
+    quote_depth: usize,
+    pre_depth: usize,
+    row: Option<Vec<String>>,
+    cell: Option<String>,
+    header_row: bool,
+    table_rows: usize,
+    last_was_item: bool,
+}
+
+impl TextWriter {
+    fn push(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => match self.cell.as_mut() {
+                Some(cell) => cell.push_str(&text),
+                None => self.inline.push_str(&text),
+            },
+            Token::StartTag {
+                name, attributes, ..
+            } => {
+                let alt = attributes
+                    .iter()
+                    .find(|(key, _)| key == "alt")
+                    .map(|(_, value)| value.clone());
+                self.open(&name, alt);
+            }
+            Token::EndTag { name } => self.close(&name),
+            Token::Other(_) => {}
+        }
+    }
+
+    fn open(&mut self, name: &str, alt: Option<String>) {
+        if let Some(cell) = self.cell.as_mut() {
+            if name == "br" || super::is_block_element(name) && !is_table_part(name) {
+                cell.push(' ');
+                return;
+            }
+        }
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                let level = name[1..].parse().unwrap_or(1);
+                self.marker = format!("{} ", "#".repeat(level));
+            }
+            "p" | "div" => self.flush(),
+            "blockquote" => {
+                self.flush();
+                self.quote_depth += 1;
+            }
+            "ul" => {
+                self.flush();
+                self.lists.push(None);
+            }
+            "ol" => {
+                self.flush();
+                self.lists.push(Some(1));
+            }
+            "li" => {
+                self.flush();
+                let depth = self.lists.len().saturating_sub(1);
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        *next += 1;
+                        format!("{}.", *next - 1)
+                    }
+                    _ => "-".to_string(),
+                };
+                self.marker = format!("{}{} ", "  ".repeat(depth), bullet);
+            }
+            "pre" => {
+                self.flush();
+                self.pre_depth += 1;
+            }
+            "br" => self.inline.push('\n'),
+            "hr" => {
+                self.flush();
+                self.emit("---".to_string(), false);
+            }
+            "img" => {
+                if let Some(alt) = alt.filter(|a| !a.trim().is_empty()) {
+                    self.inline.push_str(&format!("[image: {}]", alt.trim()));
+                }
+            }
+            "table" => {
+                self.flush();
+                self.table_rows = 0;
+                self.header_row = false;
+            }
+            "tr" => self.row = Some(Vec::new()),
+            "td" | "th" => {
+                self.header_row |= name == "th" && self.table_rows == 0;
+                self.cell = Some(String::new());
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "td" | "th" => {
+                if let (Some(cell), Some(row)) = (self.cell.take(), self.row.as_mut()) {
+                    row.push(collapse(&cell).replace('|', "\\|"));
+                }
+            }
+            "tr" => {
+                if let Some(row) = self.row.take() {
+                    let line = format!("| {} |", row.join(" | "));
+                    let separator = (self.table_rows == 0 && self.header_row)
+                        .then(|| format!("|{}", " --- |".repeat(row.len())));
+                    let first = self.table_rows == 0;
+                    self.emit_raw(line, !first);
+                    if let Some(separator) = separator {
+                        self.emit_raw(separator, true);
+                    }
+                    self.table_rows += 1;
+                }
+            }
+            _ if self.cell.is_some() => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li" => {
+                self.flush();
+                self.marker.clear();
+            }
+            "p" | "div" => self.flush(),
+            "blockquote" => {
+                self.flush();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            "ul" | "ol" => {
+                self.flush();
+                self.lists.pop();
+            }
+            "pre" => {
+                let code = std::mem::take(&mut self.inline);
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+                let code = code.trim_matches('\n');
+                if !code.is_empty() {
+                    self.emit_raw(format!("```\n{}\n```", code), false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pre_depth > 0 {
+            return;
+        }
+        let text = collapse(&std::mem::take(&mut self.inline));
+        if text.is_empty() {
+            // Keep a list marker for the paragraph nested in the item.
+            return;
+        }
+        let marker = std::mem::take(&mut self.marker);
+
+        let is_item = !self.lists.is_empty();
+        let indent = " ".repeat(marker.chars().count());
+        let body = text.replace('\n', &format!("\n{}", indent));
+        self.emit(format!("{}{}", marker, body), is_item);
+    }
+
+    fn emit(&mut self, block: String, is_item: bool) {
+        let tight = is_item && self.last_was_item;
+        self.emit_raw(block, tight);
+        self.last_was_item = is_item;
+    }
+
+    fn emit_raw(&mut self, block: String, tight: bool) {
+        if !self.out.is_empty() {
+            self.out.push_str(if tight { "\n" } else { "\n\n" });
+        }
+        let quote = "> ".repeat(self.quote_depth);
+        if quote.is_empty() {
+            self.out.push_str(&block);
+        } else {
+            let quoted: Vec<String> = block.lines().map(|l| format!("{}{}", quote, l)).collect();
+            self.out.push_str(&quoted.join("\n"));
+        }
+        self.last_was_item = false;
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        self.out
+    }
+}
+
+fn is_table_part(name: &str) -> bool {
+    matches!(name, "table" | "tr" | "td" | "th")
+}
+
+/// Collapses runs of whitespace, keeping the explicit line breaks.
+fn collapse(text: &str) -> String {
+    text.split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
@@ -0,0 +1,238 @@
+// src-tauri/src/html/tokenizer.rs
+//! A small, forgiving tokenizer for the HTML fragments TipTap produces. It
+//! does not build a tree; callers track nesting themselves.
+
+use super::{escape_attribute, escape_text};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    StartTag {
+        name: String,
+        attributes: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: String,
+    },
+    /// Text with entities decoded.
+    Text(String),
+    /// Comments, doctypes and other markup passed through verbatim.
+    Other(String),
+}
+
+impl Token {
+    pub fn to_html(&self) -> String {
+        match self {
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                let mut html = format!("<{}", name);
+                for (key, value) in attributes {
+                    html.push_str(&format!(" {}=\"{}\"", key, escape_attribute(value)));
+                }
+                html.push_str(if *self_closing { " />" } else { ">" });
+                html
+            }
+            Token::EndTag { name } => format!("</{}>", name),
+            Token::Text(text) => escape_text(text),
+            Token::Other(raw) => raw.clone(),
+        }
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Token::StartTag { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+pub fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix("<!--") {
+            let end = stripped.find("-->").map(|i| i + 7).unwrap_or(rest.len());
+            tokens.push(Token::Other(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+            tokens.push(Token::Other(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if let Some((token, consumed)) = parse_tag(rest) {
+            tokens.push(token);
+            rest = &rest[consumed..];
+        } else {
+            // A '<' that does not open a tag is literal text.
+            let skip = if rest.starts_with('<') { 1 } else { 0 };
+            let end = rest[skip..]
+                .find('<')
+                .map(|i| i + skip)
+                .unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end]);
+            match tokens.last_mut() {
+                Some(Token::Text(previous)) => previous.push_str(&text),
+                _ => tokens.push(Token::Text(text)),
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+fn parse_tag(input: &str) -> Option<(Token, usize)> {
+    let bytes = input.as_bytes();
+    if bytes.first() != Some(&b'<') {
+        return None;
+    }
+
+    let closing = bytes.get(1) == Some(&b'/');
+    let name_start = if closing { 2 } else { 1 };
+    if !bytes.get(name_start)?.is_ascii_alphabetic() {
+        return None;
+    }
+
+    let name_end = input[name_start..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .map(|i| i + name_start)?;
+    let name = input[name_start..name_end].to_ascii_lowercase();
+
+    if closing {
+        let end = input[name_end..].find('>')? + name_end + 1;
+        return Some((Token::EndTag { name }, end));
+    }
+
+    let mut attributes = Vec::new();
+    let mut pos = name_end;
+    loop {
+        pos += whitespace_len(&input[pos..]);
+        let rest = &input[pos..];
+        if rest.is_empty() {
+            return None;
+        }
+        if rest.starts_with("/>") {
+            return Some((
+                Token::StartTag {
+                    name,
+                    attributes,
+                    self_closing: true,
+                },
+                pos + 2,
+            ));
+        }
+        if rest.starts_with('>') {
+            return Some((
+                Token::StartTag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                },
+                pos + 1,
+            ));
+        }
+        if rest.starts_with('/') {
+            pos += 1;
+            continue;
+        }
+
+        let key_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let key = rest[..key_len].to_ascii_lowercase();
+        pos += key_len;
+        pos += whitespace_len(&input[pos..]);
+
+        let mut value = String::new();
+        if input[pos..].starts_with('=') {
+            pos += 1;
+            pos += whitespace_len(&input[pos..]);
+            let rest = &input[pos..];
+            match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = rest[1..].find(quote)? + 1;
+                    value = decode_entities(&rest[1..end]);
+                    pos += end + 1;
+                }
+                Some(_) => {
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(rest.len());
+                    value = decode_entities(&rest[..end]);
+                    pos += end;
+                }
+                None => return None,
+            }
+        }
+        attributes.push((key, value));
+    }
+}
+
+fn whitespace_len(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .and_then(|end| decode_entity(&rest[1..=end]).map(|c| (c, end + 2)));
+
+        match entity {
+            Some((c, consumed)) => {
+                decoded.push(c);
+                rest = &rest[consumed..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    let c = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '\u{2013}',
+        "mdash" => '\u{2014}',
+        "hellip" => '\u{2026}',
+        "lsquo" => '\u{2018}',
+        "rsquo" => '\u{2019}',
+        "ldquo" => '\u{201c}',
+        "rdquo" => '\u{201d}',
+        _ => return None,
+    };
+    Some(c)
+}
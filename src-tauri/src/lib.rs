@@ -2,14 +2,55 @@
 use std::fs;
 use std::path::Path;
 
+use editing::autocomplete::AutocompleteState;
+use retrieval::cache::IndexCache;
+use secrets::state::VaultState;
+
+mod app_dirs;
+mod chat_history;
+mod comments;
 mod errors;
+mod export;
 mod pdf;
 mod pdf_processor;
 pub mod llm;
 mod commands;
+mod editing;
+mod html;
+mod prompts;
+mod retrieval;
+mod secrets;
+mod settings;
 
-use commands::ai_commands::{process_ai_chat, test_ai_connection};
-use commands::pdf_commands::{analyze_pdf, process_pdf_summarization, extract_pdf_text};
+use commands::ai_commands::{compute_embeddings, process_ai_chat, test_ai_connection};
+use commands::chat_commands::{
+    create_chat_session, delete_chat_session, list_chat_sessions, load_chat_session,
+    rename_chat_session, search_chat_sessions,
+};
+use commands::comment_commands::{assist_comment_thread, triage_comment_threads};
+use commands::document_commands::{
+    ask_document, export_pdf, outline_document, summarize_document, translate_document,
+};
+use commands::editing_commands::{autocomplete, check_grammar, transform_text};
+use commands::pdf_commands::{
+    analyze_pdf, ask_pdf, check_pdf_health, convert_pdf_to_document, extract_pdf_images,
+    extract_pdf_outline, extract_pdf_pages, extract_pdf_tables, extract_pdf_text,
+    import_pdf_annotations, insert_pdf_table, process_pdf_summarization, stream_pdf_pages,
+    summarize_pdf_sections,
+};
+use commands::settings_commands::{
+    delete_ai_profile, get_ai_settings, save_ai_profile, set_active_ai_profile,
+    set_max_pdf_open_size, set_max_pdf_size, set_summary_prompt,
+};
+use commands::vault_commands::{
+    get_vault_status, list_api_key_names, lock_vault, remove_api_key, set_api_key,
+    unlock_vault,
+};
+use commands::prompt_commands::{
+    create_prompt_template, delete_prompt_template, export_prompt_templates,
+    import_prompt_templates, list_prompt_templates, render_prompt_template,
+    update_prompt_template,
+};
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -45,6 +86,9 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(VaultState::default())
+        .manage(AutocompleteState::default())
+        .manage(IndexCache::default())
         .invoke_handler(tauri::generate_handler![
             greet, 
             save_document, 
@@ -52,8 +96,56 @@ pub fn run() {
             process_pdf_summarization,
             analyze_pdf,
             extract_pdf_text,
+            ask_pdf,
             test_ai_connection,
-            process_ai_chat
+            process_ai_chat,
+            compute_embeddings,
+            list_prompt_templates,
+            create_prompt_template,
+            update_prompt_template,
+            delete_prompt_template,
+            render_prompt_template,
+            import_prompt_templates,
+            export_prompt_templates,
+            create_chat_session,
+            list_chat_sessions,
+            load_chat_session,
+            rename_chat_session,
+            delete_chat_session,
+            search_chat_sessions,
+            get_vault_status,
+            unlock_vault,
+            lock_vault,
+            set_api_key,
+            remove_api_key,
+            list_api_key_names,
+            get_ai_settings,
+            save_ai_profile,
+            delete_ai_profile,
+            set_active_ai_profile,
+            set_summary_prompt,
+            transform_text,
+            check_grammar,
+            assist_comment_thread,
+            triage_comment_threads,
+            autocomplete,
+            translate_document,
+            summarize_document,
+            outline_document,
+            ask_document,
+            extract_pdf_pages,
+            extract_pdf_outline,
+            summarize_pdf_sections,
+            check_pdf_health,
+            set_max_pdf_size,
+            stream_pdf_pages,
+            set_max_pdf_open_size,
+            extract_pdf_tables,
+            insert_pdf_table,
+            extract_pdf_images,
+            import_pdf_annotations,
+            convert_pdf_to_document,
+            export_pdf
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
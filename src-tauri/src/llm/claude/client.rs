@@ -1,8 +1,16 @@
 // src-tauri/src/llm/claude/client.rs
-use super::models::{ClaudeMessage, ClaudeRequest};
+use super::models::{ClaudeMessage, ClaudeRequest, ClaudeTool, ClaudeToolChoice};
 use super::services::post_chat_completion;
 use crate::errors::AppError;
-use crate::llm::{reasoning::extract_reasoning_and_output, LLMClient, ReasoningResponse};
+use crate::llm::completion::{
+    build_completion_prompt, clean_completion, CompletionRequest, COMPLETION_SYSTEM_PROMPT,
+    MAX_COMPLETION_TEMPERATURE,
+};
+use crate::llm::structured::JsonSchema;
+use crate::llm::{
+    reasoning::extract_reasoning_and_output, truncate_text, EmbeddingOptions, Embeddings,
+    LLMClient, ReasoningResponse,
+};
 use async_trait::async_trait;
 use reqwest::Client;
 use std::time::Duration;
@@ -41,6 +49,10 @@ impl ClaudeClient {
         }
     }
 
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
     fn estimate_max_chars(&self) -> usize {
         let available_tokens = if self.max_tokens > 200 {
             self.max_tokens - 200
@@ -67,6 +79,9 @@ impl LLMClient for ClaudeClient {
             max_tokens: self.max_tokens,
             temperature: self.temperature,
             system: None,
+            stop_sequences: None,
+            tools: Vec::new(),
+            tool_choice: None,
         };
 
         let api_response =
@@ -84,6 +99,63 @@ impl LLMClient for ClaudeClient {
         }
     }
 
+    /// Claude has no JSON mode, so the reply is requested as the input of
+    /// a tool it is made to call, which the API checks against the schema.
+    async fn chat_structured(
+        &self,
+        prompt: &str,
+        schema: &JsonSchema,
+    ) -> Result<ReasoningResponse, AppError> {
+        if prompt.trim().is_empty() {
+            return Err(AppError::AiError("No prompt provided for chat".to_string()));
+        }
+
+        let request = ClaudeRequest {
+            model: self.model.clone(),
+            messages: vec![ClaudeMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            system: None,
+            stop_sequences: None,
+            tools: vec![ClaudeTool {
+                name: schema.name.to_string(),
+                description: schema.description.to_string(),
+                input_schema: schema.schema.clone(),
+            }],
+            tool_choice: Some(ClaudeToolChoice {
+                choice_type: "tool".to_string(),
+                name: schema.name.to_string(),
+            }),
+        };
+
+        let api_response =
+            post_chat_completion(&self.client, &self.base_url, &self.api_key, &request).await?;
+
+        let mut text = None;
+        for content in api_response.content {
+            match (content.content_type.as_str(), content.input) {
+                ("tool_use", Some(input)) => {
+                    return Ok(ReasoningResponse {
+                        reasoning: None,
+                        output: input.to_string(),
+                    });
+                }
+                ("text", _) => text = Some(content.text),
+                _ => {}
+            }
+        }
+        match text {
+            Some(text) => {
+                let (reasoning, output) = extract_reasoning_and_output(&text);
+                Ok(ReasoningResponse { reasoning, output })
+            }
+            None => Err(AppError::AiError("No content in response".to_string())),
+        }
+    }
+
     async fn summarize(&self, text: &str, prompt: &str) -> Result<String, AppError> {
         if text.trim().is_empty() {
             return Err(AppError::AiError(
@@ -95,11 +167,7 @@ impl LLMClient for ClaudeClient {
         }
 
         let max_chars = self.estimate_max_chars();
-        let truncated_text = if text.len() > max_chars {
-            format!("{}[truncated for length]", &text[..max_chars])
-        } else {
-            text.to_string()
-        };
+        let truncated_text = truncate_text(text, max_chars);
 
         let full_prompt = format!("{}\n\nDocument content:\n{}", prompt, truncated_text);
         self.chat(&full_prompt).await.map(|r| r.output)
@@ -109,4 +177,53 @@ impl LLMClient for ClaudeClient {
         let test_prompt = "Say 'Connection test successful' if you can hear me.";
         self.chat(test_prompt).await.map(|r| r.output)
     }
+    fn max_input_chars(&self) -> usize {
+        self.estimate_max_chars()
+    }
+
+    async fn embed(
+        &self,
+        _inputs: &[String],
+        _options: &EmbeddingOptions,
+    ) -> Result<Embeddings, AppError> {
+        Err(AppError::CapabilityError(
+            "Anthropic Claude does not provide an embeddings API".to_string(),
+        ))
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<String, AppError> {
+        // The Messages API rejects whitespace-only stop sequences, so those
+        // are applied when cleaning the output instead.
+        let stop_sequences: Vec<String> = request
+            .stop
+            .iter()
+            .filter(|s| !s.trim().is_empty())
+            .cloned()
+            .collect();
+
+        let claude_request = ClaudeRequest {
+            model: self.model.clone(),
+            messages: vec![ClaudeMessage {
+                role: "user".to_string(),
+                content: build_completion_prompt(request),
+            }],
+            max_tokens: request.max_tokens,
+            temperature: self.temperature.min(MAX_COMPLETION_TEMPERATURE),
+            system: Some(COMPLETION_SYSTEM_PROMPT.to_string()),
+            stop_sequences: (!stop_sequences.is_empty()).then_some(stop_sequences),
+            tools: Vec::new(),
+            tool_choice: None,
+        };
+
+        let api_response =
+            post_chat_completion(&self.client, &self.base_url, &self.api_key, &claude_request)
+                .await?;
+        let text = api_response
+            .content
+            .into_iter()
+            .find(|c| c.content_type == "text")
+            .map(|c| c.text)
+            .unwrap_or_default();
+        Ok(clean_completion(&text, request))
+    }
 }
@@ -10,6 +10,12 @@ pub struct ClaudeRequest {
     pub temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ClaudeTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ClaudeToolChoice>,
 }
 
 #[derive(Serialize, Clone)]
@@ -18,6 +24,20 @@ pub struct ClaudeMessage {
     pub content: String,
 }
 
+#[derive(Serialize)]
+pub struct ClaudeTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct ClaudeToolChoice {
+    #[serde(rename = "type")]
+    pub choice_type: String,
+    pub name: String,
+}
+
 // --- Response Structs ---
 #[derive(Deserialize)]
 pub struct ClaudeResponse {
@@ -29,7 +49,11 @@ pub struct ClaudeResponse {
 pub struct ClaudeContent {
     #[serde(rename = "type")]
     pub content_type: String,
+    #[serde(default)]
     pub text: String,
+    /// The arguments of a `tool_use` block.
+    #[serde(default)]
+    pub input: Option<serde_json::Value>,
 }
 
 // --- Error Structs ---
@@ -0,0 +1,64 @@
+// src-tauri/src/llm/completion.rs
+//! Shared pieces of inline (ghost-text) completion.
+
+#[derive(Debug, Clone)]
+pub struct CompletionRequest {
+    /// Text before the cursor.
+    pub prefix: String,
+    /// Text after the cursor, for fill-in-the-middle.
+    pub suffix: Option<String>,
+    pub max_tokens: u32,
+    pub stop: Vec<String>,
+}
+
+impl CompletionRequest {
+    pub fn suffix(&self) -> Option<&str> {
+        self.suffix.as_deref().filter(|s| !s.trim().is_empty())
+    }
+}
+
+/// Completions should be predictable rather than creative.
+pub const MAX_COMPLETION_TEMPERATURE: f32 = 0.3;
+
+pub const COMPLETION_SYSTEM_PROMPT: &str = "You are an autocomplete engine inside a text editor. \
+Continue the user's text from the cursor with the most likely next few words. \
+Reply with the inserted text only: never repeat the text before the cursor, never add quotes, \
+explanations or formatting.";
+
+/// Prompt for providers without native fill-in-the-middle support.
+pub fn build_completion_prompt(request: &CompletionRequest) -> String {
+    match request.suffix() {
+        Some(suffix) => format!(
+            "Fill in the text at <cursor/> so that it joins the surrounding text naturally.\n\n{}<cursor/>{}",
+            request.prefix, suffix
+        ),
+        None => format!(
+            "Continue this text from where it stops.\n\n{}<cursor/>",
+            request.prefix
+        ),
+    }
+}
+
+/// Cuts the completion at the first stop sequence and removes an echo of
+/// the prefix that chat models sometimes include.
+pub fn clean_completion(output: &str, request: &CompletionRequest) -> String {
+    let mut text = output.trim_end_matches(['\r', '\n']);
+
+    for stop in request.stop.iter().filter(|s| !s.is_empty()) {
+        if let Some(index) = text.find(stop.as_str()) {
+            text = &text[..index];
+        }
+    }
+
+    let text = text.strip_suffix("<cursor/>").unwrap_or(text);
+    let text = text.strip_prefix("<cursor/>").unwrap_or(text);
+    let prefix_tail = request.prefix.trim_start();
+    let text = text.strip_prefix(prefix_tail).unwrap_or(text);
+
+    // Keep a leading space only when the prefix does not already end in one.
+    if request.prefix.ends_with(char::is_whitespace) {
+        text.trim_start().to_string()
+    } else {
+        text.to_string()
+    }
+}
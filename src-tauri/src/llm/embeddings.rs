@@ -0,0 +1,65 @@
+// src-tauri/src/llm/embeddings.rs
+use crate::errors::AppError;
+use crate::llm::{EmbeddingOptions, Embeddings};
+
+pub const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 64;
+const MAX_EMBEDDING_BATCH_SIZE: usize = 2048;
+
+pub fn validate_inputs(inputs: &[String]) -> Result<(), AppError> {
+    if inputs.is_empty() {
+        return Err(AppError::AiError(
+            "No input provided for embeddings".to_string(),
+        ));
+    }
+
+    if let Some(index) = inputs.iter().position(|input| input.trim().is_empty()) {
+        return Err(AppError::AiError(format!(
+            "Embedding input at index {} is empty",
+            index
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn batch_size(options: &EmbeddingOptions) -> usize {
+    options
+        .batch_size
+        .unwrap_or(DEFAULT_EMBEDDING_BATCH_SIZE)
+        .clamp(1, MAX_EMBEDDING_BATCH_SIZE)
+}
+
+/// Checks that every input got a vector and that all vectors share one
+/// dimension before packing them into `Embeddings`.
+pub fn collect_embeddings(
+    model: String,
+    expected: usize,
+    vectors: Vec<Vec<f32>>,
+) -> Result<Embeddings, AppError> {
+    if vectors.len() != expected {
+        return Err(AppError::AiError(format!(
+            "Expected {} embeddings but received {}",
+            expected,
+            vectors.len()
+        )));
+    }
+
+    let dimensions = vectors.first().map(|v| v.len()).unwrap_or(0);
+    if dimensions == 0 {
+        return Err(AppError::AiError(
+            "AI service returned empty embeddings".to_string(),
+        ));
+    }
+
+    if vectors.iter().any(|v| v.len() != dimensions) {
+        return Err(AppError::AiError(
+            "AI service returned embeddings with inconsistent dimensions".to_string(),
+        ));
+    }
+
+    Ok(Embeddings {
+        model,
+        dimensions,
+        vectors,
+    })
+}
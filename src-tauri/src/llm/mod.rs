@@ -1,13 +1,19 @@
 use crate::errors::AppError;
 use crate::llm::claude::client::ClaudeClient;
+use crate::llm::completion::CompletionRequest;
 use crate::llm::openai::client::OpenAIClient;
+use crate::llm::structured::JsonSchema;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 pub mod claude;
+pub mod completion;
+pub mod embeddings;
 pub mod factory;
+pub mod ollama;
 pub mod openai;
 pub mod reasoning;
+pub mod structured;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ReasoningResponse {
@@ -15,11 +21,49 @@ pub struct ReasoningResponse {
     pub output: String,
 }
 
+/// Cuts `text` to at most `max_chars` characters, marking the cut.
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...[truncated for length]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Embeddings {
+    pub model: String,
+    pub dimensions: usize,
+    pub vectors: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct EmbeddingOptions {
+    pub model: Option<String>,
+    pub dimensions: Option<u32>,
+    pub batch_size: Option<usize>,
+}
+
 #[async_trait]
 pub trait LLMClient: Send + Sync {
     async fn chat(&self, prompt: &str) -> Result<ReasoningResponse, AppError>;
+    /// Like `chat`, but asks for a reply matching `schema` where the
+    /// provider's API can enforce one. The prompt must still describe the
+    /// shape for providers that cannot.
+    async fn chat_structured(
+        &self,
+        prompt: &str,
+        schema: &JsonSchema,
+    ) -> Result<ReasoningResponse, AppError>;
     async fn summarize(&self, text: &str, prompt: &str) -> Result<String, AppError>;
     async fn test_connection(&self) -> Result<String, AppError>;
+    fn max_input_chars(&self) -> usize;
+    async fn embed(
+        &self,
+        inputs: &[String],
+        options: &EmbeddingOptions,
+    ) -> Result<Embeddings, AppError>;
+    /// Short, low-latency continuation of `request.prefix`.
+    async fn complete(&self, request: &CompletionRequest) -> Result<String, AppError>;
 }
 
 pub enum LlmClient {
@@ -27,6 +71,22 @@ pub enum LlmClient {
     Claude(ClaudeClient),
 }
 
+impl LlmClient {
+    pub fn provider(&self) -> &'static str {
+        match self {
+            LlmClient::OpenAi(client) => client.provider(),
+            LlmClient::Claude(_) => "anthropic",
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        match self {
+            LlmClient::OpenAi(client) => client.model(),
+            LlmClient::Claude(client) => client.model(),
+        }
+    }
+}
+
 #[async_trait]
 impl LLMClient for LlmClient {
     async fn chat(&self, prompt: &str) -> Result<ReasoningResponse, AppError> {
@@ -36,6 +96,17 @@ impl LLMClient for LlmClient {
         }
     }
 
+    async fn chat_structured(
+        &self,
+        prompt: &str,
+        schema: &JsonSchema,
+    ) -> Result<ReasoningResponse, AppError> {
+        match self {
+            LlmClient::OpenAi(client) => client.chat_structured(prompt, schema).await,
+            LlmClient::Claude(client) => client.chat_structured(prompt, schema).await,
+        }
+    }
+
     async fn summarize(&self, text: &str, prompt: &str) -> Result<String, AppError> {
         match self {
             LlmClient::OpenAi(client) => client.summarize(text, prompt).await,
@@ -49,5 +120,28 @@ impl LLMClient for LlmClient {
             LlmClient::Claude(client) => client.test_connection().await,
         }
     }
-}
+    fn max_input_chars(&self) -> usize {
+        match self {
+            LlmClient::OpenAi(client) => client.max_input_chars(),
+            LlmClient::Claude(client) => client.max_input_chars(),
+        }
+    }
 
+    async fn embed(
+        &self,
+        inputs: &[String],
+        options: &EmbeddingOptions,
+    ) -> Result<Embeddings, AppError> {
+        match self {
+            LlmClient::OpenAi(client) => client.embed(inputs, options).await,
+            LlmClient::Claude(client) => client.embed(inputs, options).await,
+        }
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<String, AppError> {
+        match self {
+            LlmClient::OpenAi(client) => client.complete(request).await,
+            LlmClient::Claude(client) => client.complete(request).await,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+// src-tauri/src/llm/ollama/mod.rs
+pub mod models;
+pub mod services;
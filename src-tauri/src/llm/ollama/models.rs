@@ -0,0 +1,46 @@
+// src-tauri/src/llm/ollama/models.rs
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+pub struct OllamaEmbedRequest {
+    pub model: String,
+    pub input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct OllamaEmbedResponse {
+    pub model: Option<String>,
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+pub struct OllamaGenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// Skips the model's prompt template so the prompt is continued as-is.
+    pub raw: bool,
+    pub stream: bool,
+    pub options: OllamaOptions,
+}
+
+#[derive(Serialize)]
+pub struct OllamaOptions {
+    pub num_predict: u32,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OllamaGenerateResponse {
+    pub response: String,
+}
+
+#[derive(Deserialize)]
+pub struct OllamaError {
+    pub error: String,
+}
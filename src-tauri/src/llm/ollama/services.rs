@@ -0,0 +1,70 @@
+// src-tauri/src/llm/ollama/services.rs
+use super::models::{
+    OllamaEmbedRequest, OllamaEmbedResponse, OllamaError, OllamaGenerateRequest,
+    OllamaGenerateResponse,
+};
+use crate::errors::AppError;
+use crate::llm::openai::services::map_request_error;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Ollama serves its OpenAI-compatible API under `/v1`, while the native
+/// endpoints live at the server root.
+pub fn native_base_url(base_url: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    trimmed.strip_suffix("/v1").unwrap_or(trimmed).to_string()
+}
+
+pub async fn post_embed(
+    client: &Client,
+    base_url: &str,
+    request: &OllamaEmbedRequest,
+) -> Result<OllamaEmbedResponse, AppError> {
+    post_native(client, base_url, "embed", request).await
+}
+
+pub async fn post_generate(
+    client: &Client,
+    base_url: &str,
+    request: &OllamaGenerateRequest,
+) -> Result<OllamaGenerateResponse, AppError> {
+    post_native(client, base_url, "generate", request).await
+}
+
+async fn post_native<Req: Serialize, Res: DeserializeOwned>(
+    client: &Client,
+    base_url: &str,
+    endpoint: &str,
+    request: &Req,
+) -> Result<Res, AppError> {
+    let response = client
+        .post(format!("{}/api/{}", native_base_url(base_url), endpoint))
+        .header("Content-Type", "application/json")
+        .json(request)
+        .send()
+        .await
+        .map_err(map_request_error)?;
+
+    let status = response.status();
+    let response_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+
+    if !status.is_success() {
+        if let Ok(error_response) = serde_json::from_str::<OllamaError>(&response_text) {
+            return Err(AppError::AiError(format!(
+                "API Error: {}",
+                error_response.error
+            )));
+        }
+        return Err(AppError::AiError(format!(
+            "API Error ({}): {}",
+            status, response_text
+        )));
+    }
+
+    serde_json::from_str(&response_text)
+        .map_err(|e| AppError::AiError(format!("Failed to parse API response: {}", e)))
+}
@@ -1,10 +1,24 @@
 // src-tauri/src/llm/openai/client.rs
-use super::models::{OpenAIMessage, OpenAIRequest};
-use super::services::post_chat_completion;
+use super::models::{
+    OpenAICompletionRequest, OpenAIEmbeddingRequest, OpenAIMessage, OpenAIRequest,
+};
+use super::services::{post_chat_completion, post_completion, post_embeddings};
 use crate::errors::AppError;
-use crate::llm::{reasoning::extract_reasoning_and_output, LLMClient, ReasoningResponse};
+use crate::llm::completion::{
+    build_completion_prompt, clean_completion, CompletionRequest, COMPLETION_SYSTEM_PROMPT,
+    MAX_COMPLETION_TEMPERATURE,
+};
+use crate::llm::embeddings::{batch_size, collect_embeddings, validate_inputs};
+use crate::llm::ollama::models::{OllamaEmbedRequest, OllamaGenerateRequest, OllamaOptions};
+use crate::llm::ollama::services::{post_embed, post_generate};
+use crate::llm::structured::JsonSchema;
+use crate::llm::{
+    reasoning::extract_reasoning_and_output, truncate_text, EmbeddingOptions, Embeddings,
+    LLMClient, ReasoningResponse,
+};
 use async_trait::async_trait;
 use reqwest::Client;
+use serde_json::{json, Value};
 use std::time::Duration;
 
 pub struct OpenAIClient {
@@ -50,11 +64,73 @@ impl OpenAIClient {
         };
         (available_tokens as usize) * 4
     }
-}
 
-#[async_trait]
-impl LLMClient for OpenAIClient {
-    async fn chat(&self, prompt: &str) -> Result<ReasoningResponse, AppError> {
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn provider(&self) -> &'static str {
+        if self.is_ollama() {
+            "ollama"
+        } else if self.base_url.contains("localhost:1234") {
+            "lmstudio"
+        } else if self.base_url.contains("api.together.xyz") {
+            "together"
+        } else if self.base_url.contains("api.deepseek.com") {
+            "deepseek"
+        } else {
+            "openai"
+        }
+    }
+
+    fn is_ollama(&self) -> bool {
+        self.base_url.contains("localhost:11434")
+    }
+
+    /// Base URL of a `/completions` endpoint that accepts a `suffix` for
+    /// fill-in-the-middle, when this provider and model have one. DeepSeek
+    /// serves it under `/beta` next to the profile's API root.
+    fn fim_base_url(&self) -> Option<String> {
+        match self.provider() {
+            "deepseek" if !self.model.contains("reasoner") => {
+                let root = self.base_url.trim_end_matches('/');
+                let root = root
+                    .strip_suffix("/v1")
+                    .or_else(|| root.strip_suffix("/beta"))
+                    .unwrap_or(root);
+                Some(format!("{}/beta", root))
+            }
+            "openai" if self.model.contains("instruct") => Some(self.base_url.clone()),
+            _ => None,
+        }
+    }
+
+    /// `response_format` for a reply matching `schema`: a JSON schema where
+    /// the server enforces one, plain JSON mode for DeepSeek, and nothing
+    /// for servers whose support is unknown.
+    fn response_format(&self, schema: &JsonSchema) -> Option<Value> {
+        let json_schema = json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": schema.name,
+                "description": schema.description,
+                "schema": schema.schema,
+                "strict": true,
+            },
+        });
+        match self.provider() {
+            "openai" if self.base_url.contains("api.openai.com") => Some(json_schema),
+            "lmstudio" | "ollama" => Some(json_schema),
+            "deepseek" => Some(json!({ "type": "json_object" })),
+            _ => None,
+        }
+    }
+
+    async fn send_chat(
+        &self,
+        prompt: &str,
+        response_format: Option<Value>,
+    ) -> Result<ReasoningResponse, AppError> {
         if prompt.trim().is_empty() {
             return Err(AppError::AiError("No prompt provided for chat".to_string()));
         }
@@ -67,6 +143,8 @@ impl LLMClient for OpenAIClient {
             }],
             max_tokens: self.max_tokens,
             temperature: self.temperature,
+            stop: None,
+            response_format,
         };
 
         let api_response =
@@ -84,15 +162,83 @@ impl LLMClient for OpenAIClient {
                 ));
             }
             let (reasoning, output) = extract_reasoning_and_output(&chat_response);
-            Ok(ReasoningResponse {
-                reasoning,
-                output,
-            })
+            Ok(ReasoningResponse { reasoning, output })
         } else {
             Err(AppError::AiError("No response from AI service".to_string()))
         }
     }
 
+    async fn complete_with_chat(
+        &self,
+        request: &CompletionRequest,
+        temperature: f32,
+    ) -> Result<String, AppError> {
+        // The chat API accepts at most four stop sequences.
+        let stop: Vec<String> = request.stop.iter().take(4).cloned().collect();
+        let chat_request = OpenAIRequest {
+            model: self.model.clone(),
+            messages: vec![
+                OpenAIMessage {
+                    role: "system".to_string(),
+                    content: COMPLETION_SYSTEM_PROMPT.to_string(),
+                },
+                OpenAIMessage {
+                    role: "user".to_string(),
+                    content: build_completion_prompt(request),
+                },
+            ],
+            max_tokens: request.max_tokens,
+            temperature,
+            stop: (!stop.is_empty()).then_some(stop),
+            response_format: None,
+        };
+
+        let api_response =
+            post_chat_completion(&self.client, &self.base_url, &self.api_key, &chat_request)
+                .await?;
+        let content = api_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default();
+        let (_, output) = extract_reasoning_and_output(&content);
+        Ok(clean_completion(&output, request))
+    }
+
+    fn default_embedding_model(&self) -> Result<&'static str, AppError> {
+        if self.base_url.contains("api.openai.com") {
+            Ok("text-embedding-3-small")
+        } else if self.is_ollama() {
+            Ok("nomic-embed-text")
+        } else if self.base_url.contains("api.together.xyz") {
+            Ok("togethercomputer/m2-bert-80M-8k-retrieval")
+        } else if self.base_url.contains("api.deepseek.com") {
+            Err(AppError::CapabilityError(
+                "DeepSeek does not provide an embeddings API".to_string(),
+            ))
+        } else {
+            Err(AppError::AiError(
+                "Please specify an embedding model for this provider".to_string(),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl LLMClient for OpenAIClient {
+    async fn chat(&self, prompt: &str) -> Result<ReasoningResponse, AppError> {
+        self.send_chat(prompt, None).await
+    }
+
+    async fn chat_structured(
+        &self,
+        prompt: &str,
+        schema: &JsonSchema,
+    ) -> Result<ReasoningResponse, AppError> {
+        self.send_chat(prompt, self.response_format(schema)).await
+    }
+
     async fn summarize(&self, text: &str, prompt: &str) -> Result<String, AppError> {
         if text.trim().is_empty() {
             return Err(AppError::AiError(
@@ -105,11 +251,7 @@ impl LLMClient for OpenAIClient {
         }
 
         let max_chars = self.estimate_max_chars();
-        let truncated_text = if text.len() > max_chars {
-            format!("{}...[truncated for length]", &text[..max_chars])
-        } else {
-            text.to_string()
-        };
+        let truncated_text = truncate_text(text, max_chars);
 
         let full_prompt = format!("{}\n\nDocument content:\n{}", prompt, truncated_text);
 
@@ -120,4 +262,175 @@ impl LLMClient for OpenAIClient {
         let test_prompt = "Say 'Connection test successful' if you can hear me.";
         self.chat(test_prompt).await.map(|r| r.output)
     }
+    fn max_input_chars(&self) -> usize {
+        self.estimate_max_chars()
+    }
+
+    async fn embed(
+        &self,
+        inputs: &[String],
+        options: &EmbeddingOptions,
+    ) -> Result<Embeddings, AppError> {
+        validate_inputs(inputs)?;
+
+        let model = match &options.model {
+            Some(model) if !model.trim().is_empty() => model.clone(),
+            _ => self.default_embedding_model()?.to_string(),
+        };
+
+        let mut vectors = Vec::with_capacity(inputs.len());
+        let mut reported_model = None;
+
+        for batch in inputs.chunks(batch_size(options)) {
+            if self.is_ollama() {
+                let request = OllamaEmbedRequest {
+                    model: model.clone(),
+                    input: batch.to_vec(),
+                    dimensions: options.dimensions,
+                };
+                let response = post_embed(&self.client, &self.base_url, &request).await?;
+                reported_model = reported_model.or(response.model);
+                vectors.extend(response.embeddings);
+            } else {
+                let request = OpenAIEmbeddingRequest {
+                    model: model.clone(),
+                    input: batch.to_vec(),
+                    dimensions: options.dimensions,
+                };
+                let mut response =
+                    post_embeddings(&self.client, &self.base_url, &self.api_key, &request).await?;
+                // The API does not guarantee that `data` is in input order.
+                response.data.sort_by_key(|d| d.index);
+                reported_model = reported_model.or(response.model);
+                vectors.extend(response.data.into_iter().map(|d| d.embedding));
+            }
+        }
+
+        collect_embeddings(reported_model.unwrap_or(model), inputs.len(), vectors)
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<String, AppError> {
+        let temperature = self.temperature.min(MAX_COMPLETION_TEMPERATURE);
+        let suffix = request.suffix().map(str::to_string);
+
+        if self.is_ollama() {
+            let generate_request = OllamaGenerateRequest {
+                model: self.model.clone(),
+                prompt: request.prefix.clone(),
+                raw: suffix.is_none(),
+                suffix,
+                stream: false,
+                options: OllamaOptions {
+                    num_predict: request.max_tokens,
+                    temperature,
+                    stop: request.stop.clone(),
+                },
+            };
+            match post_generate(&self.client, &self.base_url, &generate_request).await {
+                Ok(response) => return Ok(clean_completion(&response.response, request)),
+                // Models without an infill template reject `suffix`.
+                Err(_) if generate_request.suffix.is_some() => {}
+                Err(e) => return Err(e),
+            }
+        } else if let Some(fim_base_url) = self.fim_base_url() {
+            let completion_request = OpenAICompletionRequest {
+                model: self.model.clone(),
+                prompt: request.prefix.clone(),
+                suffix,
+                max_tokens: request.max_tokens,
+                temperature,
+                stop: request.stop.iter().take(4).cloned().collect(),
+            };
+            let response = post_completion(
+                &self.client,
+                &fim_base_url,
+                &self.api_key,
+                &completion_request,
+            )
+            .await?;
+            let text = response
+                .choices
+                .into_iter()
+                .next()
+                .map(|choice| choice.text)
+                .unwrap_or_default();
+            return Ok(clean_completion(&text, request));
+        }
+
+        self.complete_with_chat(request, temperature).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(base_url: &str, model: &str) -> OpenAIClient {
+        OpenAIClient::new(
+            String::new(),
+            Some(base_url.to_string()),
+            Some(model.to_string()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn response_format_is_sent_only_where_supported() {
+        let schema = JsonSchema {
+            name: "reply",
+            description: "A reply",
+            schema: json!({ "type": "object" }),
+        };
+        let format = |base_url: &str| {
+            client(base_url, "model")
+                .response_format(&schema)
+                .map(|format| format["type"].clone())
+        };
+        assert_eq!(
+            format("https://api.openai.com/v1"),
+            Some(json!("json_schema"))
+        );
+        assert_eq!(
+            format("http://localhost:11434/v1"),
+            Some(json!("json_schema"))
+        );
+        assert_eq!(
+            format("https://api.deepseek.com"),
+            Some(json!("json_object"))
+        );
+        assert_eq!(format("https://llm.example.com/v1"), None);
+    }
+
+    #[test]
+    fn fim_endpoint_follows_the_profile_base_url() {
+        for base_url in [
+            "https://api.deepseek.com",
+            "https://api.deepseek.com/",
+            "https://api.deepseek.com/v1",
+            "https://api.deepseek.com/beta",
+        ] {
+            assert_eq!(
+                client(base_url, "deepseek-chat").fim_base_url().as_deref(),
+                Some("https://api.deepseek.com/beta")
+            );
+        }
+        assert_eq!(
+            client("https://proxy.api.deepseek.com/v1", "deepseek-chat")
+                .fim_base_url()
+                .as_deref(),
+            Some("https://proxy.api.deepseek.com/beta")
+        );
+        assert_eq!(
+            client("https://api.deepseek.com", "deepseek-reasoner").fim_base_url(),
+            None
+        );
+        assert_eq!(
+            client("https://api.openai.com/v1", "gpt-3.5-turbo-instruct")
+                .fim_base_url()
+                .as_deref(),
+            Some("https://api.openai.com/v1")
+        );
+    }
 }
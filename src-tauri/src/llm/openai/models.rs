@@ -7,6 +7,10 @@ pub struct OpenAIRequest {
     pub messages: Vec<OpenAIMessage>,
     pub max_tokens: u32,
     pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -41,3 +45,45 @@ pub struct OpenAIErrorDetails {
     #[serde(rename = "type")]
     pub error_type: Option<String>,
 }
+
+#[derive(Serialize)]
+pub struct OpenAIEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAIEmbeddingResponse {
+    pub data: Vec<OpenAIEmbeddingData>,
+    pub model: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAIEmbeddingData {
+    pub embedding: Vec<f32>,
+    pub index: usize,
+}
+
+#[derive(Serialize)]
+pub struct OpenAICompletionRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAICompletionResponse {
+    pub choices: Vec<OpenAICompletionChoice>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAICompletionChoice {
+    pub text: String,
+}
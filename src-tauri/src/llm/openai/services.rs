@@ -1,7 +1,12 @@
 // src-tauri/src/llm/openai/services.rs
-use super::models::{OpenAIError, OpenAIRequest, OpenAIResponse};
+use super::models::{
+    OpenAICompletionRequest, OpenAICompletionResponse, OpenAIEmbeddingRequest,
+    OpenAIEmbeddingResponse, OpenAIError, OpenAIRequest, OpenAIResponse,
+};
 use crate::errors::AppError;
 use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 pub async fn post_chat_completion(
     client: &Client,
@@ -9,27 +14,42 @@ pub async fn post_chat_completion(
     api_key: &str,
     request: &OpenAIRequest,
 ) -> Result<OpenAIResponse, AppError> {
+    post_json(client, &format!("{}/chat/completions", base_url), api_key, request).await
+}
+
+pub async fn post_embeddings(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    request: &OpenAIEmbeddingRequest,
+) -> Result<OpenAIEmbeddingResponse, AppError> {
+    post_json(client, &format!("{}/embeddings", base_url), api_key, request).await
+}
+
+/// Legacy text completion endpoint, used for fill-in-the-middle.
+pub async fn post_completion(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    request: &OpenAICompletionRequest,
+) -> Result<OpenAICompletionResponse, AppError> {
+    post_json(client, &format!("{}/completions", base_url), api_key, request).await
+}
+
+async fn post_json<Req: Serialize, Res: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    api_key: &str,
+    request: &Req,
+) -> Result<Res, AppError> {
     let response = client
-        .post(&format!("{}/chat/completions", base_url))
+        .post(url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(request)
         .send()
         .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                AppError::AiError(
-                    "Request timed out. Try increasing the timeout in settings.".to_string(),
-                )
-            } else if e.is_connect() {
-                AppError::AiError(
-                    "Failed to connect to AI service. Check your base URL and internet connection."
-                        .to_string(),
-                )
-            } else {
-                AppError::AiError(format!("Network error: {}", e))
-            }
-        })?;
+        .map_err(map_request_error)?;
 
     let status = response.status();
     let response_text = response
@@ -75,3 +95,16 @@ pub async fn post_chat_completion(
     serde_json::from_str(&response_text)
         .map_err(|e| AppError::AiError(format!("Failed to parse API response: {}", e)))
 }
+
+pub fn map_request_error(e: reqwest::Error) -> AppError {
+    if e.is_timeout() {
+        AppError::AiError("Request timed out. Try increasing the timeout in settings.".to_string())
+    } else if e.is_connect() {
+        AppError::AiError(
+            "Failed to connect to AI service. Check your base URL and internet connection."
+                .to_string(),
+        )
+    } else {
+        AppError::AiError(format!("Network error: {}", e))
+    }
+}
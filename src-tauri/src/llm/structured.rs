@@ -0,0 +1,35 @@
+// src-tauri/src/llm/structured.rs
+use crate::errors::AppError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// The shape a structured reply must have, for providers that can enforce
+/// it. Providers take an object at the top level only.
+pub struct JsonSchema {
+    /// Letters, digits, `_` and `-` only.
+    pub name: &'static str,
+    pub description: &'static str,
+    pub schema: Value,
+}
+
+/// Parses a JSON value out of a model reply, tolerating code fences and
+/// prose before or after the JSON.
+pub fn parse_json_output<T: DeserializeOwned>(output: &str) -> Result<T, AppError> {
+    let candidate = extract_json(output)
+        .ok_or_else(|| AppError::AiError("The AI response did not contain JSON".to_string()))?;
+
+    serde_json::from_str(candidate)
+        .map_err(|e| AppError::AiError(format!("The AI response had an unexpected shape: {}", e)))
+}
+
+fn extract_json(output: &str) -> Option<&str> {
+    let text = output.trim();
+    let start = text.find(['[', '{'])?;
+    let close = if text[start..].starts_with('[') {
+        ']'
+    } else {
+        '}'
+    };
+    let end = text.rfind(close)?;
+    (end > start).then(|| &text[start..=end])
+}
@@ -1,4 +1,16 @@
 // src-tauri/src/pdf/mod.rs
+pub mod pdf_annotations;
 pub mod pdf_extractor;
+pub mod pdf_fonts;
+pub mod pdf_html;
+pub mod pdf_images;
 pub mod pdf_info;
+pub mod pdf_layout;
+pub mod pdf_loader;
+pub mod pdf_metadata;
+pub mod pdf_ocr;
+pub mod pdf_outline;
+pub mod pdf_page_range;
+pub mod pdf_repair;
+pub mod pdf_tables;
 pub mod pdf_validation;
@@ -0,0 +1,264 @@
+// src-tauri/src/pdf/pdf_annotations.rs
+//! Review annotations (highlights, sticky notes and the like) read from
+//! each page's `/Annots`, with the marked-up text recovered from the
+//! highlight's QuadPoints.
+use super::pdf_fonts::resolve;
+use super::pdf_layout::{collect_text_runs, TextRun};
+use super::pdf_loader::load_pdf;
+use super::pdf_metadata::{decode_text_string, parse_pdf_date};
+use super::pdf_page_range::PageSelection;
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnotationKind {
+    Highlight,
+    Underline,
+    StrikeOut,
+    Squiggly,
+    /// A sticky note.
+    Text,
+    /// Text typed directly onto the page.
+    FreeText,
+}
+
+impl AnnotationKind {
+    fn from_subtype(subtype: &[u8]) -> Option<Self> {
+        match subtype {
+            b"Highlight" => Some(AnnotationKind::Highlight),
+            b"Underline" => Some(AnnotationKind::Underline),
+            b"StrikeOut" => Some(AnnotationKind::StrikeOut),
+            b"Squiggly" => Some(AnnotationKind::Squiggly),
+            b"Text" => Some(AnnotationKind::Text),
+            b"FreeText" => Some(AnnotationKind::FreeText),
+            _ => None,
+        }
+    }
+
+    /// Whether the annotation marks up a stretch of the page's text.
+    pub fn is_text_markup(self) -> bool {
+        matches!(
+            self,
+            AnnotationKind::Highlight
+                | AnnotationKind::Underline
+                | AnnotationKind::StrikeOut
+                | AnnotationKind::Squiggly
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PdfAnnotation {
+    pub id: ObjectId,
+    pub page_number: u32,
+    pub kind: AnnotationKind,
+    pub author: Option<String>,
+    pub contents: Option<String>,
+    pub created: Option<DateTime<Utc>>,
+    pub modified: Option<DateTime<Utc>>,
+    /// The text under a highlight, underline or strike-out.
+    pub quoted_text: Option<String>,
+    /// As "#rrggbb".
+    pub color: Option<String>,
+    /// The annotation this one replies to.
+    pub in_reply_to: Option<ObjectId>,
+    /// Review state set by this reply, such as "Accepted" or "Completed".
+    pub state: Option<String>,
+}
+
+/// Reads the supported annotations on the selected pages, in page order.
+pub fn read_annotations(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<Vec<PdfAnnotation>, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let mut annotations = Vec::new();
+
+    for (page_number, page_id) in selection.select(doc.get_pages())? {
+        let mut page_annotations: Vec<(PdfAnnotation, Vec<[f32; 4]>)> =
+            page_annotation_ids(&doc, page_id)
+                .into_iter()
+                .filter_map(|id| read_annotation(&doc, id, page_number))
+                .collect();
+
+        // Only pages with marked-up text need their text laid out.
+        if page_annotations.iter().any(|(_, quads)| !quads.is_empty()) {
+            let runs = collect_text_runs(&doc, page_id).unwrap_or_default();
+            for (annotation, quads) in &mut page_annotations {
+                annotation.quoted_text = quoted_text(&runs, quads);
+            }
+        }
+        annotations.extend(
+            page_annotations
+                .into_iter()
+                .map(|(annotation, _)| annotation),
+        );
+    }
+
+    Ok(annotations)
+}
+
+fn page_annotation_ids(doc: &Document, page_id: ObjectId) -> Vec<ObjectId> {
+    doc.get_dictionary(page_id)
+        .ok()
+        .and_then(|page| page.get(b"Annots").ok())
+        .and_then(|annots| resolve(doc, annots).as_array().ok())
+        .map(|annots| {
+            annots
+                .iter()
+                .filter_map(|o| o.as_reference().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The annotation and the quadrilaterals of text it marks, each as
+/// `[left, bottom, right, top]`.
+fn read_annotation(
+    doc: &Document,
+    id: ObjectId,
+    page_number: u32,
+) -> Option<(PdfAnnotation, Vec<[f32; 4]>)> {
+    let dict = doc.get_dictionary(id).ok()?;
+    let kind = AnnotationKind::from_subtype(dict.get(b"Subtype").and_then(Object::as_name).ok()?)?;
+
+    let text = |key: &[u8]| {
+        dict.get(key)
+            .ok()
+            .and_then(|o| resolve(doc, o).as_str().ok())
+            .map(decode_text_string)
+            .map(|s| s.replace('\r', "\n").trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let date = |key: &[u8]| text(key).and_then(|value| parse_pdf_date(&value));
+
+    let quads = if kind.is_text_markup() {
+        quad_boxes(doc, dict)
+    } else {
+        Vec::new()
+    };
+
+    Some((
+        PdfAnnotation {
+            id,
+            page_number,
+            kind,
+            author: text(b"T"),
+            contents: text(b"Contents"),
+            created: date(b"CreationDate"),
+            modified: date(b"M"),
+            quoted_text: None,
+            color: color(doc, dict),
+            in_reply_to: dict.get(b"IRT").and_then(Object::as_reference).ok(),
+            state: text(b"State"),
+        },
+        quads,
+    ))
+}
+
+/// QuadPoints hold eight numbers per marked line. Producers disagree on
+/// the corner order, so only the bounding box of each is used.
+fn quad_boxes(doc: &Document, dict: &Dictionary) -> Vec<[f32; 4]> {
+    let Some(points) = dict
+        .get(b"QuadPoints")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+    else {
+        return Vec::new();
+    };
+    let values: Vec<f32> = points.iter().filter_map(|o| o.as_float().ok()).collect();
+
+    values
+        .chunks_exact(8)
+        .map(|quad| {
+            let xs = [quad[0], quad[2], quad[4], quad[6]];
+            let ys = [quad[1], quad[3], quad[5], quad[7]];
+            [
+                xs.into_iter().fold(f32::INFINITY, f32::min),
+                ys.into_iter().fold(f32::INFINITY, f32::min),
+                xs.into_iter().fold(f32::NEG_INFINITY, f32::max),
+                ys.into_iter().fold(f32::NEG_INFINITY, f32::max),
+            ]
+        })
+        .collect()
+}
+
+/// The characters whose centers fall inside the quads, one quad per line.
+fn quoted_text(runs: &[TextRun], quads: &[[f32; 4]]) -> Option<String> {
+    let mut quoted = String::new();
+    for [left, bottom, right, top] in quads {
+        // (start, end, text, font size) of each run's share of the quad.
+        let mut pieces: Vec<(f32, f32, String, f32)> = Vec::new();
+        for run in runs {
+            let middle = run.y + run.size * 0.3;
+            if middle < *bottom || middle > *top {
+                continue;
+            }
+
+            let mut piece: Option<(f32, f32, String, f32)> = None;
+            for (i, c) in run.text.chars().enumerate() {
+                let Some(start) = run.char_x.get(i).copied() else {
+                    break;
+                };
+                let end = run.char_x.get(i + 1).copied().unwrap_or(run.end_x);
+                let center = (start + end) / 2.0;
+                if center < *left || center > *right {
+                    continue;
+                }
+                let piece = piece.get_or_insert((start, end, String::new(), run.size));
+                piece.1 = end;
+                piece.2.push(c);
+            }
+            pieces.extend(piece);
+        }
+
+        pieces.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut line = String::new();
+        let mut previous_end: Option<f32> = None;
+        for (start, end, text, size) in pieces {
+            if previous_end.is_some_and(|previous| start - previous > 0.15 * size) {
+                line.push(' ');
+            }
+            line.push_str(&text);
+            previous_end = Some(end);
+        }
+
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            continue;
+        }
+        if quoted.ends_with('-') && line.starts_with(char::is_lowercase) {
+            quoted.pop();
+        } else if !quoted.is_empty() {
+            quoted.push(' ');
+        }
+        quoted.push_str(&line);
+    }
+
+    (!quoted.is_empty()).then_some(quoted)
+}
+
+/// The `/C` color, given as gray, RGB or CMYK components from 0 to 1.
+fn color(doc: &Document, dict: &Dictionary) -> Option<String> {
+    let components: Vec<f32> = dict
+        .get(b"C")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())?
+        .iter()
+        .filter_map(|o| o.as_float().ok())
+        .map(|c| c.clamp(0.0, 1.0))
+        .collect();
+
+    let rgb = match components.as_slice() {
+        [gray] => [*gray; 3],
+        [r, g, b] => [*r, *g, *b],
+        [c, m, y, k] => [c, m, y].map(|v| (1.0 - v) * (1.0 - k)),
+        _ => return None,
+    };
+    let [r, g, b] = rgb.map(|v| (v * 255.0).round() as u8);
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
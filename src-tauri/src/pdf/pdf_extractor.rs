@@ -1,55 +1,379 @@
 // src-tauri/src/pdf/pdf_extractor.rs
+use super::pdf_layout::{collect_text_runs, layout_page, FontStats, LayoutBlock, TextRun};
+use super::pdf_loader::load_pdf;
+use super::pdf_ocr::{self, PageOcr};
+use super::pdf_page_range::PageSelection;
 use crate::errors::AppError;
-use lopdf::Document;
+use lopdf::{Document, ObjectId};
+use serde::Serialize;
+use std::collections::HashMap;
 
-pub fn extract_text(file_path: &str) -> Result<String, AppError> {
-    let doc = Document::load(file_path)
-        .map_err(|e| AppError::PdfError(format!("Failed to load PDF: {}", e)))?;
+const NO_TEXT_ERROR: &str =
+    "No readable text found in PDF. This might be an image-based PDF or contain only graphics.";
+/// Pages with less native text than this are checked for scanned images.
+const MIN_NATIVE_CHARS: usize = 32;
+/// Pages sampled for the body and heading font sizes.
+const FONT_STATS_SAMPLE_PAGES: usize = 40;
 
-    let mut extracted_text = String::new();
-    let pages = doc.get_pages();
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedPage {
+    pub page_number: u32,
+    /// Paragraphs separated by blank lines.
+    pub text: String,
+    pub char_count: usize,
+    /// Number of text columns detected; 0 when layout analysis found no text.
+    pub columns: usize,
+    /// Headings and paragraphs in reading order.
+    pub blocks: Vec<LayoutBlock>,
+    /// Set when some of the text was recognized from page images.
+    pub ocr: Option<PageOcr>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedDocument {
+    pub pages: Vec<ExtractedPage>,
+    /// Number of pages extracted, which is less than
+    /// `document_page_count` when a page range was given.
+    pub page_count: usize,
+    pub document_page_count: usize,
+    pub total_chars: usize,
+    /// Pages whose text came, at least in part, from OCR.
+    pub ocr_pages: Vec<u32>,
+}
+
+/// Totals for pages extracted one at a time.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionSummary {
+    pub page_count: usize,
+    pub document_page_count: usize,
+    pub total_chars: usize,
+    pub ocr_pages: Vec<u32>,
+}
 
-    if pages.is_empty() {
-        return Err(AppError::PdfError("PDF contains no pages".to_string()));
+impl ExtractedDocument {
+    pub fn from_pages(pages: Vec<ExtractedPage>, document_page_count: usize) -> Self {
+        ExtractedDocument {
+            page_count: pages.len(),
+            document_page_count,
+            total_chars: pages.iter().map(|p| p.char_count).sum(),
+            ocr_pages: pages
+                .iter()
+                .filter(|p| p.ocr.is_some())
+                .map(|p| p.page_number)
+                .collect(),
+            pages,
+        }
+    }
+
+    /// All page texts joined into one string, separated by blank lines.
+    pub fn flattened(&self) -> String {
+        self.pages
+            .iter()
+            .filter(|p| !p.text.is_empty())
+            .map(|p| p.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
     }
 
-    for (page_num, _) in pages {
-        match doc.extract_text(&[page_num]) {
-            Ok(page_text) => {
-                if !page_text.trim().is_empty() {
-                    extracted_text.push_str(&page_text);
-                    extracted_text.push('\n');
-                }
-            }
-            Err(e) => {
-                eprintln!(
-                    "Warning: Failed to extract text from page {}: {}",
-                    page_num, e
-                );
-                continue;
-            }
+    /// `(page number, text)` for every page with text.
+    pub fn text_pages(&self) -> Vec<(u32, String)> {
+        self.pages
+            .iter()
+            .filter(|p| !p.text.is_empty())
+            .map(|p| (p.page_number, p.text.clone()))
+            .collect()
+    }
+}
+
+/// Extracts the selected pages, recording pages that fail instead of
+/// skipping them. See [`PageExtractor`] for how pages are read.
+pub fn extract_document(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<ExtractedDocument, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let extractor = PageExtractor::new(&doc, selection)?;
+    let document_page_count = extractor.document_page_count();
+    Ok(ExtractedDocument::from_pages(
+        extractor.collect(),
+        document_page_count,
+    ))
+}
+
+/// Extracts the selected pages one at a time, handing each to `on_page`
+/// with the number of pages done and the total, so callers can pass
+/// results on without holding the whole document's text.
+pub fn for_each_page(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+    mut on_page: impl FnMut(ExtractedPage, usize, usize),
+) -> Result<ExtractionSummary, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let extractor = PageExtractor::new(&doc, selection)?;
+    let total = extractor.len();
+    let mut summary = ExtractionSummary {
+        page_count: total,
+        document_page_count: extractor.document_page_count(),
+        total_chars: 0,
+        ocr_pages: Vec::new(),
+    };
+
+    for (index, page) in extractor.enumerate() {
+        summary.total_chars += page.char_count;
+        if page.ocr.is_some() {
+            summary.ocr_pages.push(page.page_number);
         }
+        on_page(page, index + 1, total);
     }
 
-    let cleaned_text = clean_text(&extracted_text);
+    Ok(summary)
+}
+
+/// Lazily extracts pages in order. Pages are read through layout analysis
+/// so multi-column text comes out in reading order with headings marked;
+/// pages the analysis cannot read fall back to lopdf's content-order
+/// extraction, and scanned pages are run through OCR when Tesseract is
+/// installed.
+pub struct PageExtractor<'a> {
+    doc: &'a Document,
+    pages: std::vec::IntoIter<(u32, ObjectId)>,
+    document_page_count: usize,
+    stats: FontStats,
+    /// Text runs already read for the font statistics, reused once.
+    sampled_runs: HashMap<u32, Vec<TextRun>>,
+}
+
+impl<'a> PageExtractor<'a> {
+    pub fn new(doc: &'a Document, selection: &PageSelection) -> Result<Self, AppError> {
+        let pages = doc.get_pages();
+        if pages.is_empty() {
+            return Err(AppError::PdfError("PDF contains no pages".to_string()));
+        }
+        let document_page_count = pages.len();
+        let pages = selection.select(pages)?;
+
+        // Heading sizes are judged against a sample spread over the
+        // selection rather than every page, which bounds the work done
+        // before the first page comes out.
+        let step = pages.len().div_ceil(FONT_STATS_SAMPLE_PAGES).max(1);
+        let sampled_runs: HashMap<u32, Vec<TextRun>> = pages
+            .iter()
+            .step_by(step)
+            .map(|(page_number, page_id)| {
+                let runs = collect_text_runs(doc, *page_id).unwrap_or_default();
+                (*page_number, runs)
+            })
+            .collect();
+        let stats = FontStats::from_runs(sampled_runs.values().flatten());
+
+        Ok(PageExtractor {
+            doc,
+            pages: pages.into_iter(),
+            document_page_count,
+            stats,
+            sampled_runs,
+        })
+    }
+
+    pub fn document_page_count(&self) -> usize {
+        self.document_page_count
+    }
+}
+
+impl Iterator for PageExtractor<'_> {
+    type Item = ExtractedPage;
+
+    fn next(&mut self) -> Option<ExtractedPage> {
+        let (page_number, page_id) = self.pages.next()?;
+        let runs = self
+            .sampled_runs
+            .remove(&page_number)
+            .unwrap_or_else(|| collect_text_runs(self.doc, page_id).unwrap_or_default());
+
+        let mut page = extract_native_page(self.doc, page_number, &runs, &self.stats);
+        if page.char_count < MIN_NATIVE_CHARS {
+            apply_ocr(self.doc, page_id, &mut page);
+        }
+        Some(page)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pages.size_hint()
+    }
+}
+
+impl ExactSizeIterator for PageExtractor<'_> {}
+
+fn extract_native_page(
+    doc: &Document,
+    page_number: u32,
+    runs: &[TextRun],
+    stats: &FontStats,
+) -> ExtractedPage {
+    let layout = layout_page(runs, stats);
+    let (text, columns, blocks, error) = if !layout.blocks.is_empty() {
+        (layout.text(), layout.columns, layout.blocks, None)
+    } else {
+        match doc.extract_text(&[page_number]) {
+            Ok(page_text) => (clean_page_text(&page_text), 0, Vec::new(), None),
+            Err(e) => (
+                String::new(),
+                0,
+                Vec::new(),
+                Some(format!("Failed to extract text: {}", e)),
+            ),
+        }
+    };
 
-    if cleaned_text.trim().is_empty() {
-        return Err(AppError::PdfError(
-            "No readable text found in PDF. This might be an image-based PDF or contain only graphics."
-                .to_string(),
-        ));
+    ExtractedPage {
+        page_number,
+        char_count: text.chars().count(),
+        text,
+        columns,
+        blocks,
+        ocr: None,
+        error,
     }
+}
+
+/// Recognizes the images of a page with little or no native text and
+/// merges the result into the page.
+fn apply_ocr(doc: &Document, page_id: ObjectId, page: &mut ExtractedPage) {
+    let Some(tesseract) = pdf_ocr::tesseract_path() else {
+        if page.text.is_empty() && pdf_ocr::has_page_images(doc, page_id) {
+            page.error = Some(
+                "Page appears to be scanned, but OCR is unavailable because Tesseract was not found"
+                    .to_string(),
+            );
+        }
+        return;
+    };
 
-    Ok(cleaned_text)
+    match pdf_ocr::ocr_page(doc, page_id, tesseract) {
+        Ok(Some(recognized)) if !recognized.text.trim().is_empty() => {
+            page.text = merge_ocr_text(&page.text, &clean_page_text(&recognized.text));
+            page.char_count = page.text.chars().count();
+            page.ocr = Some(PageOcr {
+                confidence: recognized.confidence,
+                images: recognized.images,
+            });
+            page.error = None;
+        }
+        Ok(_) => {}
+        Err(e) => page.error = Some(format!("OCR failed: {}", e)),
+    }
 }
 
-fn clean_text(text: &str) -> String {
-    text.lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
+/// Keeps native paragraphs the OCR text does not already contain (such as
+/// a page number stamped over a scan) ahead of the recognized text.
+fn merge_ocr_text(native: &str, ocr: &str) -> String {
+    let normalized = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let ocr_normalized = normalized(ocr);
+
+    native
+        .split("\n\n")
+        .filter(|paragraph| {
+            let paragraph = normalized(paragraph);
+            !paragraph.is_empty() && !ocr_normalized.contains(&paragraph)
+        })
+        .chain(std::iter::once(ocr))
         .collect::<Vec<_>>()
-        .join(" ")
-        .chars()
+        .join("\n\n")
+}
+
+pub fn extract_text(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<String, AppError> {
+    let text = extract_document(file_path, password, selection)?.flattened();
+
+    if text.trim().is_empty() {
+        return Err(AppError::PdfError(NO_TEXT_ERROR.to_string()));
+    }
+
+    Ok(text)
+}
+
+/// Extracts cleaned text per page, skipping pages without readable text.
+pub fn extract_pages(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<Vec<(u32, String)>, AppError> {
+    let page_texts = extract_document(file_path, password, selection)?.text_pages();
+
+    if page_texts.is_empty() {
+        return Err(AppError::PdfError(NO_TEXT_ERROR.to_string()));
+    }
+
+    Ok(page_texts)
+}
+
+/// Joins the lines of a page into paragraphs. A blank line, or a short
+/// line ending a sentence, closes a paragraph; words hyphenated across a
+/// line break are rejoined.
+fn clean_page_text(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(clean_line).collect();
+    let typical_len = typical_line_length(&lines);
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for line in &lines {
+        if line.is_empty() {
+            close_paragraph(&mut paragraphs, &mut current);
+            continue;
+        }
+
+        if current.ends_with('-') && line.starts_with(char::is_lowercase) {
+            current.pop();
+        } else if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+
+        let ends_sentence = line.ends_with(['.', '!', '?', ':']);
+        if ends_sentence && line.chars().count() * 10 < typical_len * 7 {
+            close_paragraph(&mut paragraphs, &mut current);
+        }
+    }
+    close_paragraph(&mut paragraphs, &mut current);
+
+    paragraphs.join("\n\n")
+}
+
+fn close_paragraph(paragraphs: &mut Vec<String>, current: &mut String) {
+    if !current.is_empty() {
+        paragraphs.push(std::mem::take(current));
+    }
+}
+
+/// Median length of the non-empty lines, which approximates the width of
+/// a full line of body text.
+fn typical_line_length(lines: &[String]) -> usize {
+    let mut lengths: Vec<usize> = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.chars().count())
+        .collect();
+    lengths.sort_unstable();
+    lengths.get(lengths.len() / 2).copied().unwrap_or(0)
+}
+
+fn clean_line(line: &str) -> String {
+    line.chars()
         .filter(|c| {
             c.is_ascii_graphic()
                 || c.is_whitespace()
@@ -63,4 +387,4 @@ fn clean_text(text: &str) -> String {
         .split_whitespace()
         .collect::<Vec<_>>()
         .join(" ")
-}
\ No newline at end of file
+}
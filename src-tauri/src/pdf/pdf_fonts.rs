@@ -0,0 +1,570 @@
+// src-tauri/src/pdf/pdf_fonts.rs
+//! Font handling for layout analysis: turning character codes into text
+//! (via `ToUnicode` CMaps, encoding differences or the base encoding) and
+//! looking up glyph widths so text positions can be tracked.
+use lopdf::{dictionary, Dictionary, Document, Object};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Glyph width used when a font does not declare one, in 1/1000 em.
+const FALLBACK_GLYPH_WIDTH: f32 = 500.0;
+/// Guards against malformed `bfrange` entries that would map millions of codes.
+const MAX_RANGE_LEN: u32 = 0x10000;
+const BASE_ENCODINGS: [&str; 4] = [
+    "StandardEncoding",
+    "WinAnsiEncoding",
+    "MacRomanEncoding",
+    "MacExpertEncoding",
+];
+
+#[derive(Clone)]
+pub struct PdfFont {
+    encoding: Option<String>,
+    differences: HashMap<u32, String>,
+    to_unicode: HashMap<u32, String>,
+    two_byte: bool,
+    widths: HashMap<u32, f32>,
+    default_width: f32,
+    pub bold: bool,
+}
+
+impl PdfFont {
+    pub fn load(doc: &Document, font: &Dictionary) -> Self {
+        let is_type0 = name_of(doc, font.get(b"Subtype").ok()) == Some("Type0");
+        let descendant = is_type0
+            .then(|| {
+                font.get(b"DescendantFonts")
+                    .ok()
+                    .and_then(|o| resolve(doc, o).as_array().ok())
+                    .and_then(|fonts| fonts.first())
+                    .and_then(|o| resolve(doc, o).as_dict().ok())
+            })
+            .flatten();
+
+        let base_font = name_of(doc, font.get(b"BaseFont").ok()).unwrap_or_default();
+        let descriptor = descendant
+            .unwrap_or(font)
+            .get(b"FontDescriptor")
+            .ok()
+            .and_then(|o| resolve(doc, o).as_dict().ok());
+
+        let (widths, default_width) = match descendant {
+            Some(cid_font) => cid_widths(doc, cid_font),
+            None => simple_widths(doc, font, descriptor),
+        };
+
+        let (encoding, differences) = match font.get(b"Encoding").ok().map(|o| resolve(doc, o)) {
+            Some(Object::Name(name)) => (
+                Some(String::from_utf8_lossy(name).to_string()),
+                HashMap::new(),
+            ),
+            Some(Object::Dictionary(dict)) => (
+                name_of(doc, dict.get(b"BaseEncoding").ok()).map(str::to_string),
+                encoding_differences(doc, dict),
+            ),
+            _ => (None, HashMap::new()),
+        };
+
+        let to_unicode = font
+            .get(b"ToUnicode")
+            .ok()
+            .and_then(|o| resolve(doc, o).as_stream().ok())
+            .map(|stream| {
+                let data = stream
+                    .decompressed_content()
+                    .unwrap_or_else(|_| stream.content.clone());
+                parse_to_unicode(&data)
+            })
+            .unwrap_or_default();
+
+        let weight = descriptor
+            .and_then(|d| d.get(b"FontWeight").ok())
+            .and_then(|o| resolve(doc, o).as_float().ok())
+            .unwrap_or(0.0);
+        let flags = descriptor
+            .and_then(|d| d.get(b"Flags").ok())
+            .and_then(|o| resolve(doc, o).as_i64().ok())
+            .unwrap_or(0);
+        let lower_name = base_font.to_lowercase();
+
+        PdfFont {
+            encoding,
+            differences,
+            to_unicode,
+            two_byte: is_type0,
+            widths,
+            default_width,
+            bold: weight >= 600.0
+                || flags & (1 << 18) != 0
+                || ["bold", "black", "heavy", "semibold", "demi"]
+                    .iter()
+                    .any(|w| lower_name.contains(w)),
+        }
+    }
+
+    /// Stand-in for fonts missing from the resources.
+    pub fn fallback() -> Self {
+        PdfFont {
+            encoding: None,
+            differences: HashMap::new(),
+            to_unicode: HashMap::new(),
+            two_byte: false,
+            widths: HashMap::new(),
+            default_width: FALLBACK_GLYPH_WIDTH,
+            bold: false,
+        }
+    }
+
+    /// Splits a string operand into character codes.
+    pub fn codes(&self, bytes: &[u8]) -> Vec<u32> {
+        if self.two_byte {
+            bytes
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0u32, |code, &b| (code << 8) | b as u32))
+                .collect()
+        } else {
+            bytes.iter().map(|&b| b as u32).collect()
+        }
+    }
+
+    pub fn decode(&self, code: u32) -> String {
+        if let Some(text) = self.to_unicode.get(&code) {
+            return text.clone();
+        }
+        if self.two_byte {
+            // Without a ToUnicode map, CID codes carry no text.
+            return String::new();
+        }
+        if let Some(text) = self.differences.get(&code) {
+            return text.clone();
+        }
+        base_encoding_text(self.encoding.as_deref(), code as u8)
+    }
+
+    /// Advance width of a glyph in 1/1000 em.
+    pub fn width(&self, code: u32) -> f32 {
+        self.widths
+            .get(&code)
+            .copied()
+            .unwrap_or(self.default_width)
+    }
+
+    /// Word spacing (`Tw`) applies only to the single-byte space code.
+    pub fn is_word_space(&self, code: u32) -> bool {
+        !self.two_byte && code == 32
+    }
+}
+
+pub fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    doc.dereference(object)
+        .map(|(_, object)| object)
+        .unwrap_or(object)
+}
+
+/// Text for a single-byte code in a standard base encoding; fonts without
+/// one use StandardEncoding.
+fn base_encoding_text(encoding: Option<&str>, code: u8) -> String {
+    static TABLES: OnceLock<HashMap<&'static str, Vec<String>>> = OnceLock::new();
+    // lopdf only exposes its encoding tables through font dictionaries.
+    let tables = TABLES.get_or_init(|| {
+        let doc = Document::new();
+        BASE_ENCODINGS
+            .iter()
+            .filter_map(|&name| {
+                let font = dictionary! { "Type" => "Font", "Encoding" => name };
+                let encoding = font.get_font_encoding(&doc).ok()?;
+                let table = (0..=255u8)
+                    .map(|byte| Document::decode_text(&encoding, &[byte]).unwrap_or_default())
+                    .collect();
+                Some((name, table))
+            })
+            .collect()
+    });
+
+    match tables.get(encoding.unwrap_or("StandardEncoding")) {
+        Some(table) => table[code as usize].clone(),
+        None => String::from_utf8_lossy(&[code]).into_owned(),
+    }
+}
+
+fn name_of<'a>(doc: &'a Document, object: Option<&'a Object>) -> Option<&'a str> {
+    object
+        .and_then(|o| resolve(doc, o).as_name().ok())
+        .and_then(|name| std::str::from_utf8(name).ok())
+}
+
+fn simple_widths(
+    doc: &Document,
+    font: &Dictionary,
+    descriptor: Option<&Dictionary>,
+) -> (HashMap<u32, f32>, f32) {
+    let first_char = font
+        .get(b"FirstChar")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_i64().ok())
+        .and_then(|first| u32::try_from(first).ok())
+        .unwrap_or(0);
+
+    let widths = font
+        .get(b"Widths")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+        .map(|widths| {
+            widths
+                .iter()
+                .enumerate()
+                .filter_map(|(i, w)| {
+                    let width = resolve(doc, w).as_float().ok()?;
+                    let code = first_char.checked_add(u32::try_from(i).ok()?)?;
+                    Some((code, width))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let missing_width = descriptor
+        .and_then(|d| d.get(b"MissingWidth").ok())
+        .and_then(|o| resolve(doc, o).as_float().ok())
+        .filter(|w| *w > 0.0)
+        .unwrap_or(FALLBACK_GLYPH_WIDTH);
+
+    (widths, missing_width)
+}
+
+/// Reads the `W` array of a CIDFont: `c [w1 w2 ...]` or `c_first c_last w`.
+fn cid_widths(doc: &Document, cid_font: &Dictionary) -> (HashMap<u32, f32>, f32) {
+    let default_width = cid_font
+        .get(b"DW")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_float().ok())
+        .unwrap_or(1000.0);
+
+    let mut widths = HashMap::new();
+    let entries = match cid_font
+        .get(b"W")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+    {
+        Some(entries) => entries,
+        None => return (widths, default_width),
+    };
+
+    let mut i = 0;
+    while i + 1 < entries.len() {
+        let first = match resolve(doc, &entries[i]).as_i64() {
+            Ok(first) => match u32::try_from(first) {
+                Ok(first) => first,
+                Err(_) => break,
+            },
+            Err(_) => break,
+        };
+        match resolve(doc, &entries[i + 1]) {
+            Object::Array(list) => {
+                for (offset, w) in list.iter().enumerate() {
+                    let code = match u32::try_from(offset)
+                        .ok()
+                        .and_then(|offset| first.checked_add(offset))
+                    {
+                        Some(code) => code,
+                        None => break,
+                    };
+                    if let Ok(width) = resolve(doc, w).as_float() {
+                        widths.insert(code, width);
+                    }
+                }
+                i += 2;
+            }
+            last => {
+                let last = last
+                    .as_i64()
+                    .map(|last| u32::try_from(last.max(0)).unwrap_or(u32::MAX))
+                    .unwrap_or(0);
+                let width = entries
+                    .get(i + 2)
+                    .and_then(|w| resolve(doc, w).as_float().ok())
+                    .unwrap_or(default_width);
+                for code in first..=last.min(first.saturating_add(MAX_RANGE_LEN)) {
+                    widths.insert(code, width);
+                }
+                i += 3;
+            }
+        }
+    }
+
+    (widths, default_width)
+}
+
+fn encoding_differences(doc: &Document, encoding: &Dictionary) -> HashMap<u32, String> {
+    let mut differences = HashMap::new();
+    let entries = match encoding
+        .get(b"Differences")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+    {
+        Some(entries) => entries,
+        None => return differences,
+    };
+
+    // `None` once the codes run out of range, until the next start code.
+    let mut code = Some(0u32);
+    for entry in entries {
+        match resolve(doc, entry) {
+            Object::Integer(start) => code = u32::try_from(*start).ok(),
+            Object::Name(name) => {
+                let Some(current) = code else { continue };
+                if let Some(text) = glyph_name_to_text(&String::from_utf8_lossy(name)) {
+                    differences.insert(current, text);
+                }
+                code = current.checked_add(1);
+            }
+            _ => {}
+        }
+    }
+    differences
+}
+
+/// Maps the glyph names commonly found in `Differences` arrays.
+fn glyph_name_to_text(name: &str) -> Option<String> {
+    if name.chars().count() == 1 {
+        return Some(name.to_string());
+    }
+    if let Some(hex) = name.strip_prefix("uni").filter(|h| h.len() == 4) {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .map(String::from);
+    }
+
+    let text = match name {
+        "space" | "nbspace" => " ",
+        "zero" => "0",
+        "one" => "1",
+        "two" => "2",
+        "three" => "3",
+        "four" => "4",
+        "five" => "5",
+        "six" => "6",
+        "seven" => "7",
+        "eight" => "8",
+        "nine" => "9",
+        "period" => ".",
+        "comma" => ",",
+        "colon" => ":",
+        "semicolon" => ";",
+        "hyphen" | "minus" => "-",
+        "endash" => "\u{2013}",
+        "emdash" => "\u{2014}",
+        "exclam" => "!",
+        "question" => "?",
+        "quotesingle" => "'",
+        "quotedbl" => "\"",
+        "quoteleft" => "\u{2018}",
+        "quoteright" => "\u{2019}",
+        "quotedblleft" => "\u{201C}",
+        "quotedblright" => "\u{201D}",
+        "parenleft" => "(",
+        "parenright" => ")",
+        "bracketleft" => "[",
+        "bracketright" => "]",
+        "slash" => "/",
+        "ampersand" => "&",
+        "percent" => "%",
+        "dollar" => "$",
+        "at" => "@",
+        "asterisk" => "*",
+        "plus" => "+",
+        "equal" => "=",
+        "bullet" => "\u{2022}",
+        "ellipsis" => "\u{2026}",
+        "fi" => "fi",
+        "fl" => "fl",
+        "ff" => "ff",
+        "ffi" => "ffi",
+        "ffl" => "ffl",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+enum CmapToken {
+    Hex(Vec<u8>),
+    Word(String),
+    Open,
+    Close,
+}
+
+/// Parses the `bfchar` and `bfrange` sections of a ToUnicode CMap.
+fn parse_to_unicode(data: &[u8]) -> HashMap<u32, String> {
+    let tokens = cmap_tokens(&String::from_utf8_lossy(data));
+    let mut map = HashMap::new();
+    let mut section = "";
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match (&tokens[i], section) {
+            (CmapToken::Word(word), _) => {
+                section = match word.as_str() {
+                    "beginbfchar" => "bfchar",
+                    "beginbfrange" => "bfrange",
+                    _ => "",
+                };
+                i += 1;
+            }
+            (CmapToken::Hex(src), "bfchar") => {
+                if let Some(CmapToken::Hex(dst)) = tokens.get(i + 1) {
+                    map.insert(code_of(src), utf16_text(dst));
+                }
+                i += 2;
+            }
+            (CmapToken::Hex(low), "bfrange") => {
+                let (low, high) = match tokens.get(i + 1) {
+                    Some(CmapToken::Hex(high)) => (code_of(low), code_of(high)),
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                };
+                let high = high.min(low.saturating_add(MAX_RANGE_LEN));
+                match tokens.get(i + 2) {
+                    Some(CmapToken::Hex(dst)) => {
+                        let mut units = utf16_units(dst);
+                        for code in low..=high {
+                            map.insert(code, String::from_utf16_lossy(&units));
+                            if let Some(last) = units.last_mut() {
+                                *last = last.wrapping_add(1);
+                            }
+                        }
+                        i += 3;
+                    }
+                    Some(CmapToken::Open) => {
+                        let mut j = i + 3;
+                        let mut code = Some(low);
+                        while let Some(CmapToken::Hex(dst)) = tokens.get(j) {
+                            if let Some(current) = code.filter(|&c| c <= high) {
+                                map.insert(current, utf16_text(dst));
+                            }
+                            code = code.and_then(|c| c.checked_add(1));
+                            j += 1;
+                        }
+                        i = j + 1;
+                    }
+                    _ => i += 2,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    map
+}
+
+fn cmap_tokens(text: &str) -> Vec<CmapToken> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '<' if chars.peek() == Some(&'<') => {
+                chars.next();
+            }
+            '<' => {
+                let hex: String = chars.by_ref().take_while(|&c| c != '>').collect();
+                let digits: Vec<u8> = hex
+                    .chars()
+                    .filter_map(|c| c.to_digit(16).map(|d| d as u8))
+                    .collect();
+                let bytes = digits
+                    .chunks(2)
+                    .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+                    .collect();
+                tokens.push(CmapToken::Hex(bytes));
+            }
+            '[' => tokens.push(CmapToken::Open),
+            ']' => tokens.push(CmapToken::Close),
+            c if c.is_ascii_alphabetic() => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(CmapToken::Word(word));
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn code_of(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |code, &b| (code << 8) | b as u32)
+}
+
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => u16::from_be_bytes([*high, *low]),
+            [single] => *single as u16,
+            _ => 0,
+        })
+        .collect()
+}
+
+fn utf16_text(bytes: &[u8]) -> String {
+    String::from_utf16_lossy(&utf16_units(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    #[test]
+    fn to_unicode_ranges_stop_at_the_last_code() {
+        let cmap = b"beginbfrange\n<FFFFFFFE> <FFFFFFFF> [<0041> <0042> <0043>]\n\
+                     <FFFFFFFF> <FFFFFFFF> <0044>\nendbfrange";
+        let map = parse_to_unicode(cmap);
+        assert_eq!(map.get(&0xFFFF_FFFE).map(String::as_str), Some("A"));
+        assert_eq!(map.get(&0xFFFF_FFFF).map(String::as_str), Some("D"));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn widths_near_the_top_of_the_code_space_do_not_overflow() {
+        let doc = Document::with_version("1.7");
+        let cid_font = dictionary! {
+            "W" => vec![
+                Object::Integer(i64::from(u32::MAX)),
+                Object::Array(vec![500.into(), 600.into()]),
+                Object::Integer(i64::from(u32::MAX) - 1),
+                Object::Integer(i64::MAX),
+                700.into(),
+            ],
+        };
+        let (widths, _) = cid_widths(&doc, &cid_font);
+        assert_eq!(widths.get(&u32::MAX), Some(&700.0));
+        assert_eq!(widths.get(&(u32::MAX - 1)), Some(&700.0));
+        assert_eq!(widths.len(), 2);
+    }
+
+    #[test]
+    fn differences_skip_names_past_the_last_code() {
+        let doc = Document::with_version("1.7");
+        let encoding = dictionary! {
+            "Differences" => vec![
+                Object::Integer(i64::from(u32::MAX)),
+                Object::Name(b"A".to_vec()),
+                Object::Name(b"B".to_vec()),
+                Object::Integer(-1),
+                Object::Name(b"C".to_vec()),
+                Object::Integer(65),
+                Object::Name(b"D".to_vec()),
+            ],
+        };
+        let differences = encoding_differences(&doc, &encoding);
+        assert_eq!(differences.get(&u32::MAX).map(String::as_str), Some("A"));
+        assert_eq!(differences.get(&65).map(String::as_str), Some("D"));
+        assert_eq!(differences.len(), 2);
+    }
+}
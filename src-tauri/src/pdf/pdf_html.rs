@@ -0,0 +1,221 @@
+// src-tauri/src/pdf/pdf_html.rs
+//! Converts a PDF into HTML the editor can open and edit: headings,
+//! paragraphs, lists, tables and images in reading order, with a marker
+//! where each new page starts.
+use super::pdf_images::encode_image;
+use super::pdf_layout::{
+    collect_page_content, layout_page, list_marker, FontStats, ImagePlacement, LayoutBlock,
+    ListMarker, PageContent,
+};
+use super::pdf_loader::load_pdf;
+use super::pdf_page_range::PageSelection;
+use super::pdf_tables::separate_tables;
+use crate::errors::AppError;
+use crate::html::{escape_attribute, escape_text};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use lopdf::{Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Images drawn smaller than this, in points, are rules, bullets or
+/// spacers rather than pictures.
+const MIN_IMAGE_SIZE: f32 = 8.0;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfHtmlDocument {
+    pub html: String,
+    pub page_count: usize,
+    pub table_count: usize,
+    pub image_count: usize,
+    /// Images left out because their encoding is not supported.
+    pub skipped_images: usize,
+}
+
+/// Converts the selected pages. Pages are separated by
+/// `<hr data-page-break="true" data-page="N">`, where N is the number of
+/// the page that follows; images are embedded as data URIs so the HTML
+/// stands on its own.
+pub fn convert_to_html(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<PdfHtmlDocument, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let pages = doc.get_pages();
+    if pages.is_empty() {
+        return Err(AppError::PdfError("PDF contains no pages".to_string()));
+    }
+    let pages: Vec<(u32, PageContent)> = selection
+        .select(pages)?
+        .into_iter()
+        .map(|(page_number, page_id)| {
+            let content = collect_page_content(&doc, page_id).unwrap_or_default();
+            (page_number, content)
+        })
+        .collect();
+    let stats = FontStats::from_runs(pages.iter().flat_map(|(_, content)| &content.runs));
+
+    let mut converter = Converter {
+        doc: &doc,
+        image_sources: HashMap::new(),
+        html: String::new(),
+        has_content: false,
+        open_list: None,
+        table_count: 0,
+        image_count: 0,
+        skipped_images: 0,
+    };
+    for (index, (page_number, content)) in pages.iter().enumerate() {
+        if index > 0 {
+            converter.html.push_str(&format!(
+                "<hr data-page-break=\"true\" data-page=\"{}\">",
+                page_number
+            ));
+        }
+        converter.page(*page_number, content, &stats);
+    }
+
+    if !converter.has_content {
+        return Err(AppError::PdfError(
+            "No text, tables or images found in PDF".to_string(),
+        ));
+    }
+    Ok(PdfHtmlDocument {
+        html: converter.html,
+        page_count: pages.len(),
+        table_count: converter.table_count,
+        image_count: converter.image_count,
+        skipped_images: converter.skipped_images,
+    })
+}
+
+struct Converter<'a> {
+    doc: &'a Document,
+    /// Data URIs of images already encoded; `None` for images that
+    /// cannot be.
+    image_sources: HashMap<ObjectId, Option<String>>,
+    html: String,
+    has_content: bool,
+    /// The tag of the list being written, if any.
+    open_list: Option<&'static str>,
+    table_count: usize,
+    image_count: usize,
+    skipped_images: usize,
+}
+
+impl Converter<'_> {
+    /// Writes the page's text blocks in reading order, with each table and
+    /// image placed before the first block below its top edge.
+    fn page(&mut self, page_number: u32, content: &PageContent, stats: &FontStats) {
+        let (tables, runs) = separate_tables(page_number, &content.runs, &content.rules);
+        let layout = layout_page(&runs, stats);
+
+        self.table_count += tables.len();
+        let mut figures: Vec<(f32, String)> = tables
+            .into_iter()
+            .map(|table| (table.top, table.html))
+            .collect();
+        for placement in &content.images {
+            if let Some(html) = self.image_html(placement) {
+                figures.push((placement.top, html));
+            }
+        }
+        figures.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut figures = figures.into_iter().peekable();
+        for block in &layout.blocks {
+            while let Some((_, html)) = figures.next_if(|(top, _)| *top > block.top) {
+                self.push_block(&html);
+            }
+            self.push_text_block(block);
+        }
+        for (_, html) in figures {
+            self.push_block(&html);
+        }
+        self.close_list();
+    }
+
+    fn push_text_block(&mut self, block: &LayoutBlock) {
+        if let Some(level) = block.heading_level {
+            self.push_block(&format!(
+                "<h{level}>{}</h{level}>",
+                escape_text(&block.text)
+            ));
+            return;
+        }
+
+        let Some((marker, text)) = list_marker(&block.text) else {
+            self.push_block(&format!("<p>{}</p>", escape_text(&block.text)));
+            return;
+        };
+        let tag = match marker {
+            ListMarker::Bullet => "ul",
+            ListMarker::Ordered(_) => "ol",
+        };
+        if self.open_list != Some(tag) {
+            self.close_list();
+            match marker {
+                ListMarker::Ordered(start) if start != 1 => {
+                    self.html.push_str(&format!("<ol start=\"{}\">", start));
+                }
+                _ => self.html.push_str(&format!("<{}>", tag)),
+            }
+            self.open_list = Some(tag);
+        }
+        self.html
+            .push_str(&format!("<li><p>{}</p></li>", escape_text(text)));
+        self.has_content = true;
+    }
+
+    fn push_block(&mut self, html: &str) {
+        self.close_list();
+        self.html.push_str(html);
+        self.has_content = true;
+    }
+
+    fn close_list(&mut self) {
+        if let Some(tag) = self.open_list.take() {
+            self.html.push_str(&format!("</{}>", tag));
+        }
+    }
+
+    fn image_html(&mut self, placement: &ImagePlacement) -> Option<String> {
+        if placement.right - placement.left < MIN_IMAGE_SIZE
+            || placement.top - placement.bottom < MIN_IMAGE_SIZE
+        {
+            return None;
+        }
+
+        let doc = self.doc;
+        let source = self
+            .image_sources
+            .entry(placement.id)
+            .or_insert_with(|| {
+                let stream = doc
+                    .get_object(placement.id)
+                    .and_then(Object::as_stream)
+                    .ok()?;
+                let (extension, data) = encode_image(doc, stream).ok()?;
+                let mime = if extension == "jpg" {
+                    "image/jpeg"
+                } else {
+                    "image/png"
+                };
+                Some(format!("data:{};base64,{}", mime, BASE64.encode(data)))
+            })
+            .clone();
+
+        match source {
+            Some(source) => {
+                self.image_count += 1;
+                Some(format!("<img src=\"{}\">", escape_attribute(&source)))
+            }
+            None => {
+                self.skipped_images += 1;
+                None
+            }
+        }
+    }
+}
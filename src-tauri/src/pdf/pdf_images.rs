@@ -0,0 +1,540 @@
+// src-tauri/src/pdf/pdf_images.rs
+//! Image XObjects drawn on PDF pages, saved as files the editor can show.
+//! JPEG data is copied as is; raw bitmaps are converted to PNG.
+use super::pdf_fonts::resolve;
+use super::pdf_loader::load_pdf;
+use super::pdf_page_range::PageSelection;
+use crate::errors::AppError;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Form XObjects can nest; images in deeper forms are not listed.
+const MAX_FORM_DEPTH: usize = 4;
+/// Filters whose output is raw samples lopdf can decode.
+const RAW_FILTERS: [&[u8]; 3] = [b"FlateDecode", b"LZWDecode", b"ASCII85Decode"];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfImage {
+    pub page_number: u32,
+    /// Position among the images on the page.
+    pub index: usize,
+    /// The image object, as "12 0 R".
+    pub object: String,
+    pub width: u32,
+    pub height: u32,
+    /// Color space family, such as "DeviceRGB", "ICCBased" or "Indexed".
+    pub color_space: Option<String>,
+    pub bits_per_component: Option<u8>,
+    pub filters: Vec<String>,
+    /// Whether the image has a soft mask, which becomes the alpha channel.
+    pub has_mask: bool,
+    /// The saved file.
+    pub path: Option<String>,
+    /// Why the image could not be saved.
+    pub error: Option<String>,
+}
+
+/// Lists the images on the selected pages and saves each one to
+/// `output_dir`. Files are named after the PDF and a hash of their
+/// contents, so an image repeated on many pages is written once.
+pub fn extract_images(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+    output_dir: &Path,
+) -> Result<Vec<PdfImage>, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let stem: String = Path::new(file_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut images = Vec::new();
+    for (page_number, page_id) in selection.select(doc.get_pages())? {
+        for (index, image_id) in page_images(&doc, page_id).into_iter().enumerate() {
+            let Ok(stream) = doc.get_object(image_id).and_then(Object::as_stream) else {
+                continue;
+            };
+            let mut image = describe(&doc, page_number, index, image_id, stream);
+            match encode_image(&doc, stream) {
+                Ok((extension, data)) => {
+                    let path = output_dir.join(format!(
+                        "{}-{:016x}.{}",
+                        stem,
+                        content_hash(&data),
+                        extension
+                    ));
+                    let saved = if path.exists() {
+                        Ok(())
+                    } else {
+                        write_image(&path, &data)
+                    };
+                    match saved {
+                        Ok(()) => image.path = Some(path.to_string_lossy().to_string()),
+                        Err(e) => {
+                            image.error = Some(format!("Cannot save {}: {}", path.display(), e))
+                        }
+                    }
+                }
+                Err(reason) => image.error = Some(reason),
+            }
+            images.push(image);
+        }
+    }
+    Ok(images)
+}
+
+/// Writes through a temporary file so an interrupted or concurrent
+/// extraction never leaves a truncated image under the final name, which
+/// later runs would reuse because it exists.
+fn write_image(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    std::fs::write(&tmp_path, data)?;
+    std::fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher gives the
+/// same file names across Rust versions.
+fn content_hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn describe(
+    doc: &Document,
+    page_number: u32,
+    index: usize,
+    id: ObjectId,
+    stream: &Stream,
+) -> PdfImage {
+    let dict = &stream.dict;
+    let number = |key: &[u8]| {
+        dict.get(key)
+            .ok()
+            .and_then(|o| resolve(doc, o).as_i64().ok())
+    };
+    let color_space = dict
+        .get(b"ColorSpace")
+        .ok()
+        .map(|o| resolve(doc, o))
+        .and_then(|space| match space {
+            Object::Name(name) => Some(name.as_slice()),
+            Object::Array(items) => items.first().and_then(|o| o.as_name().ok()),
+            _ => None,
+        })
+        .map(|name| String::from_utf8_lossy(name).to_string());
+
+    PdfImage {
+        page_number,
+        index,
+        object: format!("{} {} R", id.0, id.1),
+        width: number(b"Width")
+            .and_then(|w| u32::try_from(w).ok())
+            .unwrap_or(0),
+        height: number(b"Height")
+            .and_then(|h| u32::try_from(h).ok())
+            .unwrap_or(0),
+        color_space,
+        bits_per_component: number(b"BitsPerComponent").and_then(|b| u8::try_from(b).ok()),
+        filters: filters(stream)
+            .iter()
+            .map(|f| String::from_utf8_lossy(f).to_string())
+            .collect(),
+        has_mask: dict.has(b"SMask"),
+        path: None,
+        error: None,
+    }
+}
+
+/// Image XObjects the page uses, directly or through form XObjects, in
+/// resource order and without repeats.
+fn page_images(doc: &Document, page_id: ObjectId) -> Vec<ObjectId> {
+    let (page_resources, inherited) = doc.get_page_resources(page_id).unwrap_or_default();
+    let mut images = Vec::new();
+    let mut visited = HashSet::new();
+    for resources in page_resources.into_iter().chain(
+        inherited
+            .iter()
+            .filter_map(|id| doc.get_dictionary(*id).ok()),
+    ) {
+        collect_images(doc, resources, 0, &mut visited, &mut images);
+    }
+    images
+}
+
+fn collect_images(
+    doc: &Document,
+    resources: &Dictionary,
+    depth: usize,
+    visited: &mut HashSet<ObjectId>,
+    images: &mut Vec<ObjectId>,
+) {
+    let Some(xobjects) = resources
+        .get(b"XObject")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_dict().ok())
+    else {
+        return;
+    };
+
+    for (_, object) in xobjects.iter() {
+        let Ok(id) = object.as_reference() else {
+            continue;
+        };
+        if !visited.insert(id) {
+            continue;
+        }
+        let Ok(stream) = doc.get_object(id).and_then(Object::as_stream) else {
+            continue;
+        };
+        match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+            Ok(b"Image") => images.push(id),
+            Ok(b"Form") if depth < MAX_FORM_DEPTH => {
+                if let Some(form_resources) = stream
+                    .dict
+                    .get(b"Resources")
+                    .ok()
+                    .and_then(|o| resolve(doc, o).as_dict().ok())
+                {
+                    collect_images(doc, form_resources, depth + 1, visited, images);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn filters(stream: &Stream) -> Vec<&[u8]> {
+    match stream.dict.get(b"Filter") {
+        Ok(Object::Name(name)) => vec![name.as_slice()],
+        Ok(Object::Array(items)) => items.iter().filter_map(|o| o.as_name().ok()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The image as a file: its extension and contents.
+pub fn encode_image(doc: &Document, stream: &Stream) -> Result<(&'static str, Vec<u8>), String> {
+    match filters(stream).as_slice() {
+        [b"DCTDecode"] => Ok(("jpg", stream.content.clone())),
+        [b"JPXDecode"] => Err("JPEG 2000 images are not supported".to_string()),
+        [b"JBIG2Decode"] => Err("JBIG2 images are not supported".to_string()),
+        [b"CCITTFaxDecode"] => Err("CCITT fax images are not supported".to_string()),
+        filters if filters.iter().all(|f| RAW_FILTERS.contains(f)) => {
+            let samples = if filters.is_empty() {
+                stream.content.clone()
+            } else {
+                stream
+                    .decompressed_content()
+                    .map_err(|e| format!("Image data cannot be decompressed: {}", e))?
+            };
+            raw_to_png(doc, &stream.dict, &samples).map(|png| ("png", png))
+        }
+        filters => Err(format!(
+            "Unsupported image encoding: {}",
+            filters
+                .iter()
+                .map(|f| String::from_utf8_lossy(f))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    /// A palette of RGB colors.
+    Indexed(Vec<u8>),
+}
+
+impl ColorSpace {
+    fn components(&self) -> usize {
+        match self {
+            ColorSpace::Gray | ColorSpace::Indexed(_) => 1,
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        }
+    }
+
+    fn parse(doc: &Document, object: &Object) -> Option<ColorSpace> {
+        match resolve(doc, object) {
+            Object::Name(name) => match name.as_slice() {
+                b"DeviceGray" | b"CalGray" | b"G" => Some(ColorSpace::Gray),
+                b"DeviceRGB" | b"CalRGB" | b"RGB" => Some(ColorSpace::Rgb),
+                b"DeviceCMYK" | b"CMYK" => Some(ColorSpace::Cmyk),
+                _ => None,
+            },
+            Object::Array(items) => match items.first()?.as_name().ok()? {
+                b"CalGray" => Some(ColorSpace::Gray),
+                b"CalRGB" | b"Lab" => Some(ColorSpace::Rgb),
+                // The profile stream gives the component count.
+                b"ICCBased" => {
+                    let profile = resolve(doc, items.get(1)?).as_stream().ok()?;
+                    match profile.dict.get(b"N").and_then(Object::as_i64).ok()? {
+                        1 => Some(ColorSpace::Gray),
+                        3 => Some(ColorSpace::Rgb),
+                        4 => Some(ColorSpace::Cmyk),
+                        _ => None,
+                    }
+                }
+                b"Indexed" | b"I" => {
+                    let base = ColorSpace::parse(doc, items.get(1)?)?;
+                    let colors = resolve(doc, items.get(2)?).as_i64().ok()? + 1;
+                    let lookup = match resolve(doc, items.get(3)?) {
+                        Object::String(bytes, _) => bytes.clone(),
+                        Object::Stream(stream) => stream
+                            .get_plain_content()
+                            .unwrap_or_else(|_| stream.content.clone()),
+                        _ => return None,
+                    };
+                    let entry = base.components();
+                    let palette: Vec<u8> = lookup
+                        .chunks_exact(entry)
+                        .take(colors.clamp(1, 256) as usize)
+                        .flat_map(|color| to_rgb(&base, color))
+                        .collect();
+                    Some(ColorSpace::Indexed(palette))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// One 8-bit sample of a non-indexed color space as RGB.
+fn to_rgb(space: &ColorSpace, color: &[u8]) -> [u8; 3] {
+    match space {
+        ColorSpace::Gray => [color[0]; 3],
+        ColorSpace::Cmyk => {
+            let k = 255 - u16::from(color[3]);
+            [0, 1, 2].map(|i| ((255 - u16::from(color[i])) * k / 255) as u8)
+        }
+        _ => [color[0], color[1], color[2]],
+    }
+}
+
+fn raw_to_png(doc: &Document, dict: &Dictionary, samples: &[u8]) -> Result<Vec<u8>, String> {
+    let number = |key: &[u8]| {
+        dict.get(key)
+            .ok()
+            .and_then(|o| resolve(doc, o).as_i64().ok())
+    };
+    let width = number(b"Width")
+        .and_then(|w| u32::try_from(w).ok())
+        .unwrap_or(0);
+    let height = number(b"Height")
+        .and_then(|h| u32::try_from(h).ok())
+        .unwrap_or(0);
+    if width == 0 || height == 0 {
+        return Err("The image has no size".to_string());
+    }
+
+    let stencil = matches!(dict.get(b"ImageMask"), Ok(Object::Boolean(true)));
+    let bits = if stencil {
+        1
+    } else {
+        number(b"BitsPerComponent").unwrap_or(8)
+    };
+    let space = if stencil {
+        Some(ColorSpace::Gray)
+    } else {
+        dict.get(b"ColorSpace")
+            .ok()
+            .and_then(|o| ColorSpace::parse(doc, o))
+    };
+    let Some(space) = space else {
+        return Err("The image's color space is not supported".to_string());
+    };
+
+    if !matches!(bits, 1 | 2 | 4 | 8 | 16) {
+        return Err(format!("{}-bit images are not supported", bits));
+    }
+
+    let (w, h) = (width as usize, height as usize);
+    let Some(row_bytes) = w
+        .checked_mul(space.components())
+        .and_then(|n| n.checked_mul(bits as usize))
+        .map(|bits| bits.div_ceil(8))
+    else {
+        return Err("The image is too large".to_string());
+    };
+    let Some(samples) = row_bytes
+        .checked_mul(h)
+        .and_then(|size| samples.get(..size))
+    else {
+        return Err("The image data is truncated".to_string());
+    };
+
+    // A Decode array starting at 1 swaps black and white in gray images.
+    let inverted = matches!(space, ColorSpace::Gray)
+        && dict
+            .get(b"Decode")
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|decode| decode.first().and_then(|o| o.as_float().ok()))
+            .is_some_and(|first| first > 0.5);
+
+    let depth = png::BitDepth::from_u8(bits as u8)
+        .ok_or_else(|| format!("{}-bit images are not supported", bits))?;
+    let (mut color, depth, mut data, palette) = match (&space, depth) {
+        (ColorSpace::Gray, _) => {
+            let data = if inverted {
+                samples.iter().map(|b| !b).collect()
+            } else {
+                samples.to_vec()
+            };
+            (png::ColorType::Grayscale, depth, data, None)
+        }
+        (ColorSpace::Rgb, png::BitDepth::Eight | png::BitDepth::Sixteen) => {
+            (png::ColorType::Rgb, depth, samples.to_vec(), None)
+        }
+        (ColorSpace::Cmyk, png::BitDepth::Eight) => {
+            let data = samples
+                .chunks_exact(4)
+                .flat_map(|cmyk| to_rgb(&space, cmyk))
+                .collect();
+            (png::ColorType::Rgb, depth, data, None)
+        }
+        (ColorSpace::Indexed(_), png::BitDepth::Sixteen) => {
+            return Err("16-bit indexed images are not supported".to_string());
+        }
+        (ColorSpace::Indexed(palette), _) if palette.is_empty() => {
+            return Err("The image's palette is empty".to_string());
+        }
+        (ColorSpace::Indexed(palette), _) => (
+            png::ColorType::Indexed,
+            depth,
+            samples.to_vec(),
+            Some(palette.clone()),
+        ),
+        _ => {
+            return Err(format!(
+                "{}-bit images in this color space are not supported",
+                bits
+            ))
+        }
+    };
+
+    // The soft mask becomes an alpha channel when both are 8-bit.
+    if let Some(alpha) = soft_mask(doc, dict, w, h).filter(|_| depth == png::BitDepth::Eight) {
+        let (expanded, with_alpha) = match (&color, &palette) {
+            (png::ColorType::Indexed, Some(palette)) => (
+                data.iter()
+                    .flat_map(|i| {
+                        let at = usize::from(*i) * 3;
+                        palette
+                            .get(at..at + 3)
+                            .map_or([0; 3], |c| [c[0], c[1], c[2]])
+                    })
+                    .collect(),
+                png::ColorType::Rgba,
+            ),
+            (png::ColorType::Grayscale, _) => (data.clone(), png::ColorType::GrayscaleAlpha),
+            _ => (data.clone(), png::ColorType::Rgba),
+        };
+        let channels = if with_alpha == png::ColorType::GrayscaleAlpha {
+            1
+        } else {
+            3
+        };
+        data = expanded
+            .chunks_exact(channels)
+            .zip(alpha)
+            .flat_map(|(pixel, a)| pixel.iter().copied().chain(std::iter::once(a)))
+            .collect();
+        color = with_alpha;
+    }
+
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_data, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(depth);
+        if let (png::ColorType::Indexed, Some(palette)) = (color, palette) {
+            encoder.set_palette(palette);
+        }
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+        writer
+            .write_image_data(&data)
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    }
+    Ok(png_data)
+}
+
+/// 8-bit alpha values from the image's soft mask, if it has one the same
+/// size as the image.
+fn soft_mask(doc: &Document, dict: &Dictionary, width: usize, height: usize) -> Option<Vec<u8>> {
+    let mask = resolve(doc, dict.get(b"SMask").ok()?).as_stream().ok()?;
+    let number = |key: &[u8]| mask.dict.get(key).and_then(Object::as_i64).ok();
+    let size = |key: &[u8]| number(key).and_then(|n| usize::try_from(n).ok());
+    if size(b"Width")? != width
+        || size(b"Height")? != height
+        || number(b"BitsPerComponent").unwrap_or(8) != 8
+    {
+        return None;
+    }
+    let filters = filters(mask);
+    if !filters.iter().all(|f| RAW_FILTERS.contains(f)) {
+        return None;
+    }
+    let alpha = mask.get_plain_content().ok()?;
+    alpha.get(..width.checked_mul(height)?).map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    #[test]
+    fn content_hash_is_fnv1a() {
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(content_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn rejects_images_whose_size_overflows() {
+        let doc = Document::with_version("1.5");
+        let dict = dictionary! {
+            "Width" => u32::MAX,
+            "Height" => u32::MAX,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 16,
+        };
+        assert!(raw_to_png(&doc, &dict, &[0; 64]).is_err());
+    }
+
+    #[test]
+    fn converts_gray_samples_to_png() {
+        let doc = Document::with_version("1.5");
+        let dict = dictionary! {
+            "Width" => 2,
+            "Height" => 2,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8,
+        };
+        let png = raw_to_png(&doc, &dict, &[0, 255, 255, 0]).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
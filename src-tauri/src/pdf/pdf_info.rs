@@ -1,39 +1,106 @@
 // src-tauri/src/pdf/pdf_info.rs
+use super::pdf_fonts::resolve;
+use super::pdf_loader::{load_pdf, LoadedPdf, PdfPermissions};
+use super::pdf_metadata::{catalog_entry, info_metadata, xmp_metadata, DocumentMetadata};
+use super::pdf_page_range::PageSelection;
 use crate::errors::AppError;
-use lopdf::Document;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::Path;
 
+/// Default MediaBox (US Letter) for pages that do not set one.
+const DEFAULT_PAGE_SIZE: (f32, f32) = (612.0, 792.0);
+
 #[derive(Debug)]
 pub struct PdfInfo {
     pub page_count: usize,
+    /// The document title, or the file name when it has none.
     pub title: String,
     pub has_text: bool,
+    pub version: String,
+    pub encrypted: bool,
+    pub permissions: Option<PdfPermissions>,
+    /// Whether the cross-reference table had to be rebuilt.
+    pub repaired: bool,
+    pub tagged: bool,
+    pub linearized: bool,
+    pub metadata: DocumentMetadata,
+    pub has_xmp: bool,
+    pub page_sizes: Vec<PageSize>,
+    pub fonts: Vec<FontInfo>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSize {
+    pub page_number: u32,
+    /// Visible width and height in points, after rotation.
+    pub width: f32,
+    pub height: f32,
+    pub rotation: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FontInfo {
+    /// Base font name without the subset tag.
+    pub name: String,
+    pub subtype: String,
+    pub embedded: bool,
+    pub subset: bool,
 }
 
-pub fn get_pdf_info(file_path: &str) -> Result<PdfInfo, AppError> {
-    let doc = Document::load(file_path)
-        .map_err(|e| AppError::PdfError(format!("Failed to load PDF: {}", e)))?;
+/// Reads document-level information. Page sizes, fonts and the text check
+/// cover only the selected pages.
+pub fn get_pdf_info(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<PdfInfo, AppError> {
+    let LoadedPdf {
+        doc,
+        encrypted,
+        permissions,
+        repaired,
+    } = load_pdf(file_path, password)?;
 
-    let page_count = doc.get_pages().len();
+    let pages = doc.get_pages();
+    let page_count = pages.len();
+    let selected = selection.select(pages)?;
+
+    let xmp = xmp_metadata(&doc);
+    let has_xmp = xmp.is_some();
+    let metadata = info_metadata(&doc).or(xmp.unwrap_or_default());
 
-    let title = Path::new(file_path)
-        .file_stem()
-        .and_then(|name| name.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
+    let title = metadata.title.clone().unwrap_or_else(|| {
+        Path::new(file_path)
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or("Unknown")
+            .to_string()
+    });
 
-    let has_text = check_if_has_extractable_text(&doc);
+    let has_text = check_if_has_extractable_text(&doc, &selected);
 
     Ok(PdfInfo {
         page_count,
         title,
         has_text,
+        version: pdf_version(&doc),
+        encrypted,
+        permissions,
+        repaired,
+        tagged: is_tagged(&doc),
+        linearized: is_linearized(&doc),
+        metadata,
+        has_xmp,
+        page_sizes: page_sizes(&doc, &selected),
+        fonts: fonts(&doc, &selected),
     })
 }
 
-fn check_if_has_extractable_text(doc: &Document) -> bool {
-    let pages = doc.get_pages();
-
+fn check_if_has_extractable_text(doc: &Document, pages: &[(u32, ObjectId)]) -> bool {
     for (page_num, _) in pages.iter().take(3) {
         if let Ok(text) = doc.extract_text(&[*page_num]) {
             if !text.trim().is_empty() {
@@ -44,3 +111,162 @@ fn check_if_has_extractable_text(doc: &Document) -> bool {
 
     false
 }
+
+/// The header version, unless the catalog declares a later one.
+fn pdf_version(doc: &Document) -> String {
+    let catalog_version = catalog_entry(doc, b"Version")
+        .and_then(|o| o.as_name().ok())
+        .map(|name| String::from_utf8_lossy(name).into_owned());
+    let as_number = |v: &str| v.parse::<f32>().unwrap_or(0.0);
+
+    match catalog_version {
+        Some(version) if as_number(&version) > as_number(&doc.version) => version,
+        _ => doc.version.clone(),
+    }
+}
+
+fn is_tagged(doc: &Document) -> bool {
+    let marked = catalog_entry(doc, b"MarkInfo")
+        .and_then(|o| o.as_dict().ok())
+        .and_then(|mark_info| mark_info.get(b"Marked").ok())
+        .and_then(|o| resolve(doc, o).as_bool().ok())
+        .unwrap_or(false);
+    marked || catalog_entry(doc, b"StructTreeRoot").is_some()
+}
+
+/// Linearized files start with a dictionary carrying a `Linearized` key.
+fn is_linearized(doc: &Document) -> bool {
+    doc.objects
+        .values()
+        .any(|object| object.as_dict().is_ok_and(|dict| dict.has(b"Linearized")))
+}
+
+fn page_sizes(doc: &Document, pages: &[(u32, ObjectId)]) -> Vec<PageSize> {
+    pages
+        .iter()
+        .map(|&(page_number, page_id)| {
+            let bounds = inherited_attribute(doc, page_id, b"CropBox")
+                .or_else(|| inherited_attribute(doc, page_id, b"MediaBox"))
+                .and_then(|o| o.as_array().ok())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| resolve(doc, v).as_float().ok())
+                        .collect::<Vec<f32>>()
+                })
+                .filter(|values| values.len() == 4);
+            let (width, height) = match bounds {
+                Some(b) => ((b[2] - b[0]).abs(), (b[3] - b[1]).abs()),
+                None => DEFAULT_PAGE_SIZE,
+            };
+            let rotation = inherited_attribute(doc, page_id, b"Rotate")
+                .and_then(|o| o.as_i64().ok())
+                .unwrap_or(0)
+                .rem_euclid(360);
+            let (width, height) = if rotation % 180 == 90 {
+                (height, width)
+            } else {
+                (width, height)
+            };
+
+            PageSize {
+                page_number,
+                width: (width * 100.0).round() / 100.0,
+                height: (height * 100.0).round() / 100.0,
+                rotation,
+            }
+        })
+        .collect()
+}
+
+/// Looks up a page attribute that may be inherited from the page tree.
+fn inherited_attribute<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = doc.get_dictionary(page_id).ok();
+    // Bounded so a cyclic Parent chain cannot loop forever.
+    for _ in 0..64 {
+        let dict = node?;
+        if let Ok(value) = dict.get(key) {
+            return Some(resolve(doc, value));
+        }
+        node = dict
+            .get(b"Parent")
+            .ok()
+            .and_then(|o| resolve(doc, o).as_dict().ok());
+    }
+    None
+}
+
+/// Fonts used by the pages, one entry per base font.
+fn fonts(doc: &Document, pages: &[(u32, ObjectId)]) -> Vec<FontInfo> {
+    let mut fonts: BTreeMap<(String, String), FontInfo> = BTreeMap::new();
+
+    for &(_, page_id) in pages {
+        for font in doc
+            .get_page_fonts(page_id)
+            .unwrap_or_default()
+            .into_values()
+        {
+            let base_font = font
+                .get(b"BaseFont")
+                .ok()
+                .and_then(|o| resolve(doc, o).as_name().ok())
+                .and_then(|name| std::str::from_utf8(name).ok())
+                .unwrap_or("Unnamed");
+            let subtype = font
+                .get(b"Subtype")
+                .ok()
+                .and_then(|o| o.as_name().ok())
+                .and_then(|name| std::str::from_utf8(name).ok())
+                .unwrap_or("Unknown")
+                .to_string();
+
+            // Subset fonts carry a six-letter tag, as in "ABCDEF+Helvetica".
+            let (name, subset) = match base_font.split_once('+') {
+                Some((tag, name))
+                    if tag.len() == 6 && tag.chars().all(|c| c.is_ascii_uppercase()) =>
+                {
+                    (name.to_string(), true)
+                }
+                _ => (base_font.to_string(), false),
+            };
+
+            let entry = fonts
+                .entry((name.clone(), subtype.clone()))
+                .or_insert_with(|| FontInfo {
+                    name,
+                    subtype,
+                    embedded: false,
+                    subset: false,
+                });
+            entry.embedded |= is_embedded(doc, font);
+            entry.subset |= subset;
+        }
+    }
+
+    fonts.into_values().collect()
+}
+
+fn is_embedded(doc: &Document, font: &Dictionary) -> bool {
+    if font.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Type3".as_slice()) {
+        return true;
+    }
+
+    // Composite fonts keep the descriptor on their descendant font.
+    let descendant = font
+        .get(b"DescendantFonts")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+        .and_then(|fonts| fonts.first())
+        .and_then(|o| resolve(doc, o).as_dict().ok());
+
+    descendant
+        .unwrap_or(font)
+        .get(b"FontDescriptor")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_dict().ok())
+        .is_some_and(|descriptor| {
+            [b"FontFile".as_slice(), b"FontFile2", b"FontFile3"]
+                .iter()
+                .any(|key| descriptor.has(key))
+        })
+}
@@ -0,0 +1,1163 @@
+// src-tauri/src/pdf/pdf_layout.rs
+//! Layout analysis over page content streams. Text runs are collected with
+//! their positions and font sizes, grouped into columns and lines, put in
+//! reading order and rebuilt into headings and paragraphs.
+use super::pdf_fonts::{resolve, PdfFont};
+use crate::errors::AppError;
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Form XObjects can nest; deeper nesting is ignored.
+const MAX_FORM_DEPTH: usize = 4;
+/// Text at least this much larger than body text is a heading.
+const HEADING_SIZE_RATIO: f32 = 1.15;
+const MAX_HEADING_CHARS: usize = 200;
+const MAX_BOLD_HEADING_CHARS: usize = 100;
+/// Gutters are only looked for in the middle of the text area.
+const GUTTER_SEARCH_MARGIN: f32 = 0.2;
+const MIN_LINES_PER_COLUMN: usize = 5;
+/// Line gaps larger than this multiple of the usual spacing end a paragraph.
+const PARAGRAPH_GAP_RATIO: f32 = 1.45;
+/// Filled rectangles at most this thick are drawn lines.
+const MAX_RULE_THICKNESS: f32 = 2.0;
+/// Shorter lines are tick marks or glyph parts rather than rules.
+const MIN_RULE_LENGTH: f32 = 4.0;
+/// Characters that open a bulleted list item when followed by a space.
+const BULLETS: [char; 12] = ['•', '◦', '▪', '▫', '‣', '●', '○', '■', '□', '–', '-', '*'];
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Text drawn by one show-text operator, in user-space coordinates.
+#[derive(Debug, Clone)]
+pub struct TextRun {
+    pub x: f32,
+    pub y: f32,
+    pub end_x: f32,
+    pub size: f32,
+    pub text: String,
+    pub bold: bool,
+    /// Where each char of `text` starts along the x axis.
+    pub char_x: Vec<f32>,
+}
+
+/// Where an image XObject is drawn, as its bounding box in user space.
+#[derive(Debug, Clone)]
+pub struct ImagePlacement {
+    pub id: ObjectId,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+/// Everything read from a page's content stream.
+#[derive(Debug, Clone, Default)]
+pub struct PageContent {
+    pub runs: Vec<TextRun>,
+    pub rules: Vec<Rule>,
+    pub images: Vec<ImagePlacement>,
+}
+
+/// A horizontal or vertical line drawn on the page, such as a table
+/// border, in user-space coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub horizontal: bool,
+    /// The y of a horizontal rule or the x of a vertical one.
+    pub position: f32,
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutBlock {
+    /// `Some(1)` for the largest headings; `None` for paragraphs.
+    pub heading_level: Option<u8>,
+    pub text: String,
+    pub font_size: f32,
+    /// Top of the block's first line, for placing tables and images
+    /// among the blocks.
+    #[serde(skip)]
+    pub top: f32,
+}
+
+/// How a list item is numbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMarker {
+    Bullet,
+    /// The item's number; letters count from 1 for "a".
+    Ordered(u32),
+}
+
+/// The list marker a line of text starts with, and the text after it.
+/// Bullets, "1." or "1)" and "a)" are recognized.
+pub fn list_marker(text: &str) -> Option<(ListMarker, &str)> {
+    let (marker, rest) = text.split_once(' ')?;
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut chars = marker.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        // Symbol fonts put their bullets in the private use area.
+        if BULLETS.contains(&c) || ('\u{f0a7}'..='\u{f0b7}').contains(&c) {
+            return Some((ListMarker::Bullet, rest));
+        }
+    }
+
+    let label = marker
+        .strip_suffix(')')
+        .or_else(|| marker.strip_suffix('.'))?;
+    if !label.is_empty() && label.len() <= 3 && label.chars().all(|c| c.is_ascii_digit()) {
+        let number = label.parse().ok()?;
+        return Some((ListMarker::Ordered(number), rest));
+    }
+    match label.as_bytes() {
+        [letter @ b'a'..=b'z'] if marker.ends_with(')') => {
+            Some((ListMarker::Ordered(u32::from(letter - b'a') + 1), rest))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PageLayout {
+    pub columns: usize,
+    pub blocks: Vec<LayoutBlock>,
+}
+
+impl PageLayout {
+    /// Blocks in reading order separated by blank lines, with headings
+    /// marked by `#` as in Markdown.
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(|block| match block.heading_level {
+                Some(level) => format!("{} {}", "#".repeat(level as usize), block.text),
+                None => block.text.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Font sizes across the whole document, so every page classifies
+/// headings the same way.
+#[derive(Debug, Clone)]
+pub struct FontStats {
+    body_size: f32,
+    heading_sizes: Vec<f32>,
+}
+
+impl FontStats {
+    pub fn from_runs<'a>(runs: impl IntoIterator<Item = &'a TextRun>) -> Self {
+        // Sizes are bucketed to half points.
+        let mut chars_by_size: HashMap<i32, usize> = HashMap::new();
+        for run in runs {
+            *chars_by_size
+                .entry((run.size * 2.0).round() as i32)
+                .or_default() += run.text.chars().filter(|c| !c.is_whitespace()).count();
+        }
+
+        let body_size = chars_by_size
+            .iter()
+            .max_by_key(|(size, chars)| (**chars, -**size))
+            .map(|(size, _)| *size as f32 / 2.0)
+            .unwrap_or(10.0);
+
+        let mut heading_sizes: Vec<f32> = chars_by_size
+            .keys()
+            .map(|size| *size as f32 / 2.0)
+            .filter(|size| *size >= body_size * HEADING_SIZE_RATIO)
+            .collect();
+        heading_sizes.sort_by(|a, b| b.total_cmp(a));
+
+        FontStats {
+            body_size,
+            heading_sizes,
+        }
+    }
+
+    fn heading_level(&self, line: &Line) -> Option<u8> {
+        let chars = line.text.chars().count();
+        if chars > MAX_HEADING_CHARS || !line.text.chars().any(char::is_alphabetic) {
+            return None;
+        }
+
+        if line.size >= self.body_size * HEADING_SIZE_RATIO {
+            let rank = self
+                .heading_sizes
+                .iter()
+                .position(|size| line.size >= size - 0.25)
+                .unwrap_or(self.heading_sizes.len());
+            return Some((rank + 1).min(6) as u8);
+        }
+
+        let bold_heading = line.bold
+            && line.size >= self.body_size * 0.95
+            && chars <= MAX_BOLD_HEADING_CHARS
+            && !line.text.ends_with(['.', ',', ';']);
+        bold_heading.then(|| (self.heading_sizes.len() + 1).min(6) as u8)
+    }
+}
+
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    font: Vec<u8>,
+    size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    horizontal_scale: f32,
+    leading: f32,
+    rise: f32,
+}
+
+impl GraphicsState {
+    fn new(ctm: Matrix) -> Self {
+        GraphicsState {
+            ctm,
+            font: Vec::new(),
+            size: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scale: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+        }
+    }
+}
+
+struct Resources {
+    fonts: HashMap<Vec<u8>, PdfFont>,
+    xobjects: HashMap<Vec<u8>, ObjectId>,
+}
+
+/// Collects the text runs drawn on a page, including those inside form
+/// XObjects.
+pub fn collect_text_runs(doc: &Document, page_id: ObjectId) -> Result<Vec<TextRun>, AppError> {
+    collect_page_content(doc, page_id).map(|content| content.runs)
+}
+
+/// Collects the text runs, the straight horizontal and vertical lines and
+/// the images drawn on a page.
+pub fn collect_page_content(doc: &Document, page_id: ObjectId) -> Result<PageContent, AppError> {
+    let fonts = doc
+        .get_page_fonts(page_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, font)| (name, PdfFont::load(doc, font)))
+        .collect();
+
+    let (page_resources, inherited) = doc.get_page_resources(page_id).unwrap_or_default();
+    let mut xobjects = HashMap::new();
+    let resource_dicts = page_resources.into_iter().chain(
+        inherited
+            .iter()
+            .filter_map(|id| doc.get_dictionary(*id).ok()),
+    );
+    for resources in resource_dicts {
+        for (name, id) in xobject_ids(doc, resources) {
+            xobjects.entry(name).or_insert(id);
+        }
+    }
+
+    let content = doc
+        .get_page_content(page_id)
+        .map_err(|e| AppError::PdfError(format!("Failed to read page content: {}", e)))?;
+
+    let mut collector = RunCollector {
+        doc,
+        page: PageContent::default(),
+        fallback_font: PdfFont::fallback(),
+    };
+    collector.process(&content, &Resources { fonts, xobjects }, IDENTITY, 0)?;
+    Ok(collector.page)
+}
+
+fn xobject_ids(doc: &Document, resources: &Dictionary) -> Vec<(Vec<u8>, ObjectId)> {
+    resources
+        .get(b"XObject")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_dict().ok())
+        .map(|dict| {
+            dict.iter()
+                .filter_map(|(name, o)| o.as_reference().ok().map(|id| (name.clone(), id)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+struct RunCollector<'a> {
+    doc: &'a Document,
+    page: PageContent,
+    fallback_font: PdfFont,
+}
+
+/// Segments of the path under construction, in user space.
+#[derive(Default)]
+struct PathBuilder {
+    segments: Vec<((f32, f32), (f32, f32))>,
+    current: Option<(f32, f32)>,
+    subpath_start: Option<(f32, f32)>,
+}
+
+impl PathBuilder {
+    fn move_to(&mut self, point: (f32, f32)) {
+        self.current = Some(point);
+        self.subpath_start = Some(point);
+    }
+
+    fn line_to(&mut self, point: (f32, f32)) {
+        if let Some(from) = self.current {
+            self.segments.push((from, point));
+        }
+        self.current = Some(point);
+    }
+
+    fn close(&mut self) {
+        if let Some(start) = self.subpath_start {
+            self.line_to(start);
+        }
+    }
+
+    /// Turns the finished path into rules. Rectangles thinner than
+    /// `MAX_RULE_THICKNESS` count as a single line, as many producers draw
+    /// table borders that way.
+    fn finish(&mut self, rects: &mut Vec<[(f32, f32); 4]>, rules: &mut Vec<Rule>) {
+        for corners in rects.drain(..) {
+            let (mut left, mut right) = (f32::INFINITY, f32::NEG_INFINITY);
+            let (mut bottom, mut top) = (f32::INFINITY, f32::NEG_INFINITY);
+            for (x, y) in corners {
+                (left, right) = (left.min(x), right.max(x));
+                (bottom, top) = (bottom.min(y), top.max(y));
+            }
+            let (middle_x, middle_y) = ((left + right) / 2.0, (top + bottom) / 2.0);
+
+            if top - bottom <= MAX_RULE_THICKNESS {
+                self.segments.push(((left, middle_y), (right, middle_y)));
+            } else if right - left <= MAX_RULE_THICKNESS {
+                self.segments.push(((middle_x, bottom), (middle_x, top)));
+            } else {
+                for i in 0..4 {
+                    self.segments.push((corners[i], corners[(i + 1) % 4]));
+                }
+            }
+        }
+
+        for ((x0, y0), (x1, y1)) in self.segments.drain(..) {
+            let rule = if (y0 - y1).abs() < 0.5 {
+                Rule {
+                    horizontal: true,
+                    position: (y0 + y1) / 2.0,
+                    start: x0.min(x1),
+                    end: x0.max(x1),
+                }
+            } else if (x0 - x1).abs() < 0.5 {
+                Rule {
+                    horizontal: false,
+                    position: (x0 + x1) / 2.0,
+                    start: y0.min(y1),
+                    end: y0.max(y1),
+                }
+            } else {
+                continue;
+            };
+            if rule.end - rule.start >= MIN_RULE_LENGTH {
+                rules.push(rule);
+            }
+        }
+        self.current = None;
+        self.subpath_start = None;
+    }
+
+    fn discard(&mut self, rects: &mut Vec<[(f32, f32); 4]>) {
+        self.segments.clear();
+        rects.clear();
+        self.current = None;
+        self.subpath_start = None;
+    }
+}
+
+impl RunCollector<'_> {
+    fn process(
+        &mut self,
+        content: &[u8],
+        resources: &Resources,
+        ctm: Matrix,
+        depth: usize,
+    ) -> Result<(), AppError> {
+        let content = Content::decode(content)
+            .map_err(|e| AppError::PdfError(format!("Failed to parse page content: {}", e)))?;
+
+        let mut state = GraphicsState::new(ctm);
+        let mut saved: Vec<GraphicsState> = Vec::new();
+        let mut text_matrix = IDENTITY;
+        let mut line_matrix = IDENTITY;
+        let mut path = PathBuilder::default();
+        let mut rects: Vec<[(f32, f32); 4]> = Vec::new();
+
+        for operation in &content.operations {
+            let operands = &operation.operands;
+            let number = |i: usize| operands.get(i).and_then(|o| o.as_float().ok());
+
+            match operation.operator.as_str() {
+                "q" => saved.push(state.clone()),
+                "Q" => {
+                    if let Some(previous) = saved.pop() {
+                        state = previous;
+                    }
+                }
+                "cm" => {
+                    if let Some(m) = matrix_operand(operands) {
+                        state.ctm = multiply(&m, &state.ctm);
+                    }
+                }
+                "BT" => {
+                    text_matrix = IDENTITY;
+                    line_matrix = IDENTITY;
+                }
+                "Tf" => {
+                    if let Some(Ok(name)) = operands.first().map(Object::as_name) {
+                        state.font = name.to_vec();
+                    }
+                    state.size = number(1).unwrap_or(state.size);
+                }
+                "Tc" => state.char_spacing = number(0).unwrap_or(0.0),
+                "Tw" => state.word_spacing = number(0).unwrap_or(0.0),
+                "Tz" => state.horizontal_scale = number(0).unwrap_or(100.0) / 100.0,
+                "TL" => state.leading = number(0).unwrap_or(0.0),
+                "Ts" => state.rise = number(0).unwrap_or(0.0),
+                "Td" | "TD" => {
+                    let (tx, ty) = (number(0).unwrap_or(0.0), number(1).unwrap_or(0.0));
+                    if operation.operator == "TD" {
+                        state.leading = -ty;
+                    }
+                    line_matrix = multiply(&translation(tx, ty), &line_matrix);
+                    text_matrix = line_matrix;
+                }
+                "Tm" => {
+                    if let Some(m) = matrix_operand(operands) {
+                        text_matrix = m;
+                        line_matrix = m;
+                    }
+                }
+                "T*" => {
+                    line_matrix = multiply(&translation(0.0, -state.leading), &line_matrix);
+                    text_matrix = line_matrix;
+                }
+                "Tj" => self.show(&state, &mut text_matrix, resources, operands),
+                "TJ" => {
+                    if let Some(Ok(items)) = operands.first().map(Object::as_array) {
+                        self.show(&state, &mut text_matrix, resources, items);
+                    }
+                }
+                "'" | "\"" => {
+                    if operation.operator == "\"" {
+                        state.word_spacing = number(0).unwrap_or(state.word_spacing);
+                        state.char_spacing = number(1).unwrap_or(state.char_spacing);
+                    }
+                    line_matrix = multiply(&translation(0.0, -state.leading), &line_matrix);
+                    text_matrix = line_matrix;
+                    if let Some(text) = operands.last() {
+                        self.show(
+                            &state,
+                            &mut text_matrix,
+                            resources,
+                            std::slice::from_ref(text),
+                        );
+                    }
+                }
+                "m" | "l" => {
+                    if let (Some(x), Some(y)) = (number(0), number(1)) {
+                        let point = apply(&state.ctm, x, y);
+                        if operation.operator == "m" {
+                            path.move_to(point);
+                        } else {
+                            path.line_to(point);
+                        }
+                    }
+                }
+                // Curves are never rules; only the current point moves.
+                "c" | "v" | "y" => {
+                    let end = if operation.operator == "c" { 4 } else { 2 };
+                    if let (Some(x), Some(y)) = (number(end), number(end + 1)) {
+                        path.current = Some(apply(&state.ctm, x, y));
+                    }
+                }
+                "re" => {
+                    if let (Some(x), Some(y), Some(w), Some(h)) =
+                        (number(0), number(1), number(2), number(3))
+                    {
+                        rects.push([
+                            apply(&state.ctm, x, y),
+                            apply(&state.ctm, x + w, y),
+                            apply(&state.ctm, x + w, y + h),
+                            apply(&state.ctm, x, y + h),
+                        ]);
+                        path.move_to(apply(&state.ctm, x, y));
+                    }
+                }
+                "h" => path.close(),
+                "S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*" => {
+                    if matches!(operation.operator.as_str(), "s" | "b" | "b*") {
+                        path.close();
+                    }
+                    path.finish(&mut rects, &mut self.page.rules);
+                }
+                "n" => path.discard(&mut rects),
+                "Do" if depth < MAX_FORM_DEPTH => {
+                    if let Some(Ok(name)) = operands.first().map(Object::as_name) {
+                        if let Some(id) = resources.xobjects.get(name) {
+                            self.process_xobject(*id, resources, &state, depth)?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_xobject(
+        &mut self,
+        id: ObjectId,
+        parent: &Resources,
+        state: &GraphicsState,
+        depth: usize,
+    ) -> Result<(), AppError> {
+        let stream = match self.doc.get_object(id).and_then(Object::as_stream) {
+            Ok(stream) => stream,
+            Err(_) => return Ok(()),
+        };
+        match stream.dict.get(b"Subtype").and_then(Object::as_name).ok() {
+            Some(b"Form") => {}
+            Some(b"Image") => {
+                // Images fill the unit square of the current matrix.
+                let corners = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+                    .map(|(x, y)| apply(&state.ctm, x, y));
+                let xs = corners.map(|(x, _)| x);
+                let ys = corners.map(|(_, y)| y);
+                self.page.images.push(ImagePlacement {
+                    id,
+                    left: xs.into_iter().fold(f32::INFINITY, f32::min),
+                    bottom: ys.into_iter().fold(f32::INFINITY, f32::min),
+                    right: xs.into_iter().fold(f32::NEG_INFINITY, f32::max),
+                    top: ys.into_iter().fold(f32::NEG_INFINITY, f32::max),
+                });
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+
+        let form_matrix = stream
+            .dict
+            .get(b"Matrix")
+            .ok()
+            .and_then(|o| resolve(self.doc, o).as_array().ok())
+            .and_then(|items| matrix_operand(items))
+            .unwrap_or(IDENTITY);
+
+        let own_resources = stream
+            .dict
+            .get(b"Resources")
+            .ok()
+            .and_then(|o| resolve(self.doc, o).as_dict().ok());
+        let mut fonts = parent.fonts.clone();
+        let mut xobjects = parent.xobjects.clone();
+        if let Some(own) = own_resources {
+            let own_fonts = own
+                .get(b"Font")
+                .ok()
+                .and_then(|o| resolve(self.doc, o).as_dict().ok());
+            for (name, font) in own_fonts.into_iter().flat_map(Dictionary::iter) {
+                if let Ok(font) = resolve(self.doc, font).as_dict() {
+                    fonts.insert(name.clone(), PdfFont::load(self.doc, font));
+                }
+            }
+            xobjects.extend(xobject_ids(self.doc, own));
+        }
+
+        let content = stream
+            .decompressed_content()
+            .unwrap_or_else(|_| stream.content.clone());
+        self.process(
+            &content,
+            &Resources { fonts, xobjects },
+            multiply(&form_matrix, &state.ctm),
+            depth + 1,
+        )
+    }
+
+    /// Decodes a show-text operand list into one run, advancing the text
+    /// matrix glyph by glyph.
+    fn show(
+        &mut self,
+        state: &GraphicsState,
+        text_matrix: &mut Matrix,
+        resources: &Resources,
+        items: &[Object],
+    ) {
+        let font = resources
+            .fonts
+            .get(&state.font)
+            .unwrap_or(&self.fallback_font);
+        let start_matrix = multiply(text_matrix, &state.ctm);
+        let (x, y) = apply(&start_matrix, 0.0, state.rise);
+        let size = state.size * start_matrix[2].hypot(start_matrix[3]);
+        let mut text = String::new();
+        let mut char_x = Vec::new();
+        let current_x =
+            |text_matrix: &Matrix| apply(&multiply(text_matrix, &state.ctm), 0.0, state.rise).0;
+
+        for item in items {
+            match item {
+                Object::String(bytes, _) => {
+                    for code in font.codes(bytes) {
+                        let decoded = font.decode(code);
+                        let glyph_x = current_x(text_matrix);
+                        char_x.extend(decoded.chars().map(|_| glyph_x));
+                        text.push_str(&decoded);
+                        let mut advance =
+                            font.width(code) / 1000.0 * state.size + state.char_spacing;
+                        if font.is_word_space(code) {
+                            advance += state.word_spacing;
+                        }
+                        *text_matrix = multiply(
+                            &translation(advance * state.horizontal_scale, 0.0),
+                            text_matrix,
+                        );
+                    }
+                }
+                Object::Integer(_) | Object::Real(_) => {
+                    let adjustment = item.as_float().unwrap_or(0.0);
+                    // Large negative adjustments in TJ arrays stand in for spaces.
+                    if adjustment < -200.0 && !text.ends_with(' ') {
+                        char_x.push(current_x(text_matrix));
+                        text.push(' ');
+                    }
+                    let shift = -adjustment / 1000.0 * state.size * state.horizontal_scale;
+                    *text_matrix = multiply(&translation(shift, 0.0), text_matrix);
+                }
+                _ => {}
+            }
+        }
+
+        let end_x = current_x(text_matrix);
+        if text.trim().is_empty() || size <= 0.0 {
+            return;
+        }
+
+        self.page.runs.push(TextRun {
+            x: x.min(end_x),
+            y,
+            end_x: x.max(end_x),
+            size,
+            text,
+            bold: font.bold,
+            char_x,
+        });
+    }
+}
+
+fn matrix_operand(operands: &[Object]) -> Option<Matrix> {
+    let values: Vec<f32> = operands.iter().filter_map(|o| o.as_float().ok()).collect();
+    values.try_into().ok()
+}
+
+fn translation(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn apply(m: &Matrix, x: f32, y: f32) -> (f32, f32) {
+    (x * m[0] + y * m[2] + m[4], x * m[1] + y * m[3] + m[5])
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    x: f32,
+    end_x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    text: String,
+    /// `None` for lines spanning every column, such as titles.
+    column: Option<usize>,
+}
+
+pub fn layout_page(runs: &[TextRun], stats: &FontStats) -> PageLayout {
+    if runs.is_empty() {
+        return PageLayout::default();
+    }
+
+    let gutters = find_gutters(runs);
+    let mut groups: HashMap<Option<usize>, Vec<&TextRun>> = HashMap::new();
+    for run in runs {
+        let spans_gutter = gutters
+            .iter()
+            .any(|&(start, end)| run.x < start && run.end_x > end);
+        let column = (!spans_gutter).then(|| {
+            let center = (run.x + run.end_x) / 2.0;
+            gutters.iter().filter(|&&(start, _)| start < center).count()
+        });
+        groups.entry(column).or_default().push(run);
+    }
+
+    let mut full_width = Vec::new();
+    let mut columns: Vec<Vec<Line>> = vec![Vec::new(); gutters.len() + 1];
+    for (column, runs) in groups {
+        let lines = build_lines(runs, column);
+        match column {
+            Some(index) => columns[index].extend(lines),
+            None => full_width.extend(lines),
+        }
+    }
+
+    let ordered = reading_order(full_width, columns);
+    PageLayout {
+        columns: gutters.len() + 1,
+        blocks: build_blocks(&ordered, stats),
+    }
+}
+
+/// Finds vertical strips in the middle of the page that no column-width
+/// run crosses, with enough lines of text on either side to be columns.
+fn find_gutters(runs: &[TextRun]) -> Vec<(f32, f32)> {
+    let left = runs.iter().map(|r| r.x).fold(f32::INFINITY, f32::min);
+    let right = runs
+        .iter()
+        .map(|r| r.end_x)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let width = right - left;
+    if !width.is_finite() || width < 1.0 {
+        return Vec::new();
+    }
+
+    let narrow: Vec<&TextRun> = runs
+        .iter()
+        .filter(|r| r.end_x - r.x < width * 0.5)
+        .collect();
+    let body_size = FontStats::from_runs(narrow.iter().copied()).body_size;
+
+    // One bin per point of width.
+    let bins = (width.ceil() as usize).clamp(1, 5000);
+    let scale = bins as f32 / width;
+    let mut covered = vec![false; bins];
+    for run in &narrow {
+        let start = (((run.x - left) * scale) as usize).min(bins - 1);
+        let end = (((run.end_x - left) * scale).ceil() as usize).min(bins);
+        covered[start..end.max(start + 1)].fill(true);
+    }
+
+    let search_start = (bins as f32 * GUTTER_SEARCH_MARGIN) as usize;
+    let search_end = (bins as f32 * (1.0 - GUTTER_SEARCH_MARGIN)) as usize;
+    let min_gap = (body_size * 0.8).max(width * 0.015) * scale;
+
+    let mut gutters = Vec::new();
+    let mut bin = search_start;
+    while bin < search_end {
+        if covered[bin] {
+            bin += 1;
+            continue;
+        }
+        let gap_start = bin;
+        while bin < search_end && !covered[bin] {
+            bin += 1;
+        }
+        if (bin - gap_start) as f32 >= min_gap {
+            gutters.push((left + gap_start as f32 / scale, left + bin as f32 / scale));
+        }
+    }
+
+    // Every column must hold enough distinct lines to be a real column
+    // rather than, say, a stray caption.
+    let boundaries: Vec<f32> = std::iter::once(f32::NEG_INFINITY)
+        .chain(gutters.iter().map(|g| g.0))
+        .chain(std::iter::once(f32::INFINITY))
+        .collect();
+    let columns_are_real = boundaries.windows(2).all(|bounds| {
+        let mut lines: Vec<i32> = narrow
+            .iter()
+            .filter(|r| r.x >= bounds[0] && r.end_x <= bounds[1] + body_size)
+            .map(|r| r.y.round() as i32)
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines.len() >= MIN_LINES_PER_COLUMN
+    });
+
+    if columns_are_real {
+        gutters
+    } else {
+        Vec::new()
+    }
+}
+
+/// Groups runs sharing a baseline into lines, top to bottom.
+fn build_lines(mut runs: Vec<&TextRun>, column: Option<usize>) -> Vec<Line> {
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+
+    let mut lines = Vec::new();
+    let mut current: Vec<&TextRun> = Vec::new();
+    for run in runs {
+        if let Some(first) = current.first() {
+            let tolerance = 0.5 * first.size.max(run.size);
+            if (first.y - run.y).abs() > tolerance {
+                lines.push(merge_line(std::mem::take(&mut current), column));
+            }
+        }
+        current.push(run);
+    }
+    if !current.is_empty() {
+        lines.push(merge_line(current, column));
+    }
+    lines
+}
+
+fn merge_line(mut runs: Vec<&TextRun>, column: Option<usize>) -> Line {
+    runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    let mut text = String::new();
+    let mut previous_end: Option<f32> = None;
+    let mut chars_by_size: Vec<(f32, usize)> = Vec::new();
+    let (mut bold_chars, mut total_chars) = (0, 0);
+
+    for run in &runs {
+        if let Some(end) = previous_end {
+            let gap = run.x - end;
+            if gap > 0.15 * run.size && !text.ends_with(' ') && !run.text.starts_with(' ') {
+                text.push(' ');
+            }
+        }
+        text.push_str(&run.text);
+        previous_end = Some(run.end_x);
+
+        let chars = run.text.chars().filter(|c| !c.is_whitespace()).count();
+        total_chars += chars;
+        if run.bold {
+            bold_chars += chars;
+        }
+        match chars_by_size
+            .iter_mut()
+            .find(|(size, _)| (size - run.size).abs() < 0.25)
+        {
+            Some(entry) => entry.1 += chars,
+            None => chars_by_size.push((run.size, chars)),
+        }
+    }
+
+    let size = chars_by_size
+        .iter()
+        .max_by_key(|(_, chars)| *chars)
+        .map(|(size, _)| *size)
+        .unwrap_or(runs[0].size);
+
+    Line {
+        x: runs.iter().map(|r| r.x).fold(f32::INFINITY, f32::min),
+        end_x: runs
+            .iter()
+            .map(|r| r.end_x)
+            .fold(f32::NEG_INFINITY, f32::max),
+        y: runs[0].y,
+        size,
+        bold: total_chars > 0 && bold_chars * 10 >= total_chars * 8,
+        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        column,
+    }
+}
+
+/// Orders lines top to bottom, reading each column in full between two
+/// full-width lines before moving to the next column.
+fn reading_order(mut full_width: Vec<Line>, columns: Vec<Vec<Line>>) -> Vec<Line> {
+    full_width.sort_by(|a, b| b.y.total_cmp(&a.y));
+    let mut columns: Vec<std::collections::VecDeque<Line>> = columns
+        .into_iter()
+        .map(|mut lines| {
+            lines.sort_by(|a, b| b.y.total_cmp(&a.y));
+            lines.into()
+        })
+        .collect();
+
+    let mut ordered = Vec::new();
+    for divider in full_width {
+        for column in columns.iter_mut() {
+            while column.front().is_some_and(|line| line.y > divider.y) {
+                ordered.extend(column.pop_front());
+            }
+        }
+        ordered.push(divider);
+    }
+    for column in columns {
+        ordered.extend(column);
+    }
+    ordered
+}
+
+fn build_blocks(lines: &[Line], stats: &FontStats) -> Vec<LayoutBlock> {
+    let spacing = typical_spacing(lines, stats.body_size);
+    let mut bounds: HashMap<Option<usize>, (f32, f32)> = HashMap::new();
+    for line in lines {
+        let entry = bounds
+            .entry(line.column)
+            .or_insert((f32::INFINITY, f32::NEG_INFINITY));
+        entry.0 = entry.0.min(line.x);
+        entry.1 = entry.1.max(line.end_x);
+    }
+
+    let mut blocks: Vec<LayoutBlock> = Vec::new();
+    let mut previous: Option<(&Line, Option<u8>)> = None;
+
+    for line in lines {
+        let level = stats.heading_level(line);
+        let (left, right) = bounds[&line.column];
+        // Wrapped lines of a list item are indented under its text.
+        let in_list_item = blocks
+            .last()
+            .is_some_and(|block| list_marker(&block.text).is_some());
+
+        let continues = list_marker(&line.text).is_none()
+            && previous.is_some_and(|(prev, prev_level)| {
+                let gap = prev.y - line.y;
+                let same_flow = prev.column == line.column
+                    && prev_level == level
+                    && (prev.size - line.size).abs() < 1.0
+                    && gap > 0.0
+                    && gap <= spacing * PARAGRAPH_GAP_RATIO;
+                let prev_ended = prev.text.ends_with(['.', '!', '?', ':'])
+                    && prev.end_x < right - 2.0 * prev.size;
+                let indented = line.x > left + line.size && prev.x <= left + 0.5 * prev.size;
+                same_flow && (level.is_some() || (!prev_ended && (!indented || in_list_item)))
+            });
+
+        match blocks.last_mut().filter(|_| continues) {
+            Some(block) => {
+                if block.text.ends_with('-') && line.text.starts_with(char::is_lowercase) {
+                    block.text.pop();
+                } else {
+                    block.text.push(' ');
+                }
+                block.text.push_str(&line.text);
+            }
+            None => blocks.push(LayoutBlock {
+                heading_level: level,
+                text: line.text.clone(),
+                font_size: (line.size * 10.0).round() / 10.0,
+                top: line.y + line.size,
+            }),
+        }
+        previous = Some((line, level));
+    }
+
+    blocks
+}
+
+/// Median distance between consecutive body-size lines of one column.
+fn typical_spacing(lines: &[Line], body_size: f32) -> f32 {
+    let mut gaps: Vec<f32> = lines
+        .windows(2)
+        .filter(|pair| pair[0].column == pair[1].column)
+        .filter(|pair| (pair[1].size - body_size).abs() < 0.5)
+        .map(|pair| pair[0].y - pair[1].y)
+        .filter(|gap| *gap > 0.0 && *gap < body_size * 3.0)
+        .collect();
+    gaps.sort_by(f32::total_cmp);
+    gaps.get(gaps.len() / 2).copied().unwrap_or(body_size * 1.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(x: f32, end_x: f32, y: f32, size: f32, text: &str) -> TextRun {
+        TextRun {
+            x,
+            y,
+            end_x,
+            size,
+            text: text.to_string(),
+            bold: false,
+            char_x: Vec::new(),
+        }
+    }
+
+    fn line(y: f32, column: Option<usize>, text: &str) -> Line {
+        Line {
+            x: 72.0,
+            end_x: 290.0,
+            y,
+            size: 10.0,
+            bold: false,
+            text: text.to_string(),
+            column,
+        }
+    }
+
+    /// A title across the page over two columns of six lines each, split
+    /// by a gutter from x = 290 to 320.
+    fn two_column_page() -> Vec<TextRun> {
+        let mut runs = vec![run(72.0, 540.0, 760.0, 18.0, "A Study of Columns")];
+        for i in 0..6 {
+            let y = 700.0 - 14.0 * i as f32;
+            runs.push(run(72.0, 290.0, y, 10.0, &format!("left {}", i + 1)));
+            runs.push(run(320.0, 540.0, y, 10.0, &format!("right {}", i + 1)));
+        }
+        runs
+    }
+
+    #[test]
+    fn gutters_separate_columns_of_text() {
+        let gutters = find_gutters(&two_column_page());
+        assert_eq!(gutters.len(), 1);
+        let (start, end) = gutters[0];
+        assert!(start >= 290.0 && end <= 320.0, "{:?}", gutters[0]);
+    }
+
+    #[test]
+    fn short_columns_and_single_columns_have_no_gutter() {
+        // Three lines on the right are too few to be a column.
+        let mut runs: Vec<TextRun> = (0..6)
+            .map(|i| run(72.0, 290.0, 700.0 - 14.0 * i as f32, 10.0, "left"))
+            .collect();
+        runs.extend((0..3).map(|i| run(320.0, 540.0, 700.0 - 14.0 * i as f32, 10.0, "right")));
+        assert!(find_gutters(&runs).is_empty());
+
+        let single: Vec<TextRun> = (0..6)
+            .map(|i| {
+                run(
+                    72.0,
+                    540.0,
+                    700.0 - 14.0 * i as f32,
+                    10.0,
+                    "full width text",
+                )
+            })
+            .collect();
+        assert!(find_gutters(&single).is_empty());
+    }
+
+    #[test]
+    fn columns_are_read_in_full_between_full_width_lines() {
+        let full_width = vec![line(50.0, None, "footer"), line(760.0, None, "title")];
+        let columns = vec![
+            vec![
+                line(686.0, Some(0), "left 2"),
+                line(700.0, Some(0), "left 1"),
+            ],
+            vec![
+                line(700.0, Some(1), "right 1"),
+                line(686.0, Some(1), "right 2"),
+            ],
+        ];
+        let order: Vec<String> = reading_order(full_width, columns)
+            .into_iter()
+            .map(|l| l.text)
+            .collect();
+        assert_eq!(
+            order,
+            ["title", "left 1", "left 2", "right 1", "right 2", "footer"]
+        );
+    }
+
+    #[test]
+    fn blocks_mark_headings_and_split_paragraphs() {
+        let mut runs = vec![
+            run(72.0, 200.0, 720.0, 16.0, "Introduction"),
+            run(
+                72.0,
+                540.0,
+                700.0,
+                10.0,
+                "The first paragraph starts here and",
+            ),
+            run(72.0, 300.0, 686.0, 10.0, "ends on this line."),
+            run(72.0, 540.0, 640.0, 10.0, "A second paragraph follows a gap"),
+            run(72.0, 540.0, 626.0, 10.0, "and wraps onto another line."),
+        ];
+        let mut methods = run(72.0, 130.0, 600.0, 10.0, "Methods");
+        methods.bold = true;
+        runs.push(methods);
+
+        let stats = FontStats::from_runs(&runs);
+        let lines = build_lines(runs.iter().collect(), Some(0));
+        let blocks: Vec<(Option<u8>, String)> = build_blocks(&lines, &stats)
+            .into_iter()
+            .map(|b| (b.heading_level, b.text))
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                (Some(1), "Introduction".to_string()),
+                (
+                    None,
+                    "The first paragraph starts here and ends on this line.".to_string()
+                ),
+                (
+                    None,
+                    "A second paragraph follows a gap and wraps onto another line.".to_string()
+                ),
+                (Some(2), "Methods".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn two_column_pages_keep_each_column_together() {
+        let runs = two_column_page();
+        let layout = layout_page(&runs, &FontStats::from_runs(&runs));
+        assert_eq!(layout.columns, 2);
+        assert_eq!(
+            layout.text(),
+            "# A Study of Columns\n\n\
+             left 1 left 2 left 3 left 4 left 5 left 6\n\n\
+             right 1 right 2 right 3 right 4 right 5 right 6"
+        );
+    }
+
+    #[test]
+    fn bullets_and_numbers_start_list_items() {
+        assert_eq!(list_marker("• First"), Some((ListMarker::Bullet, "First")));
+        assert_eq!(
+            list_marker("\u{f0b7} Symbol font"),
+            Some((ListMarker::Bullet, "Symbol font"))
+        );
+        assert_eq!(
+            list_marker("12.  Twelfth"),
+            Some((ListMarker::Ordered(12), "Twelfth"))
+        );
+        assert_eq!(
+            list_marker("3) Third"),
+            Some((ListMarker::Ordered(3), "Third"))
+        );
+        assert_eq!(
+            list_marker("b) Second"),
+            Some((ListMarker::Ordered(2), "Second"))
+        );
+    }
+
+    #[test]
+    fn ordinary_text_is_not_a_list_item() {
+        assert_eq!(list_marker("1."), None);
+        assert_eq!(list_marker("- "), None);
+        assert_eq!(list_marker("2024. A year"), None);
+        assert_eq!(list_marker("b. Not a letter label"), None);
+        assert_eq!(list_marker("In 1990 the"), None);
+    }
+}
@@ -0,0 +1,136 @@
+// src-tauri/src/pdf/pdf_loader.rs
+//! Opens PDFs for every other PDF module, decrypting password-protected
+//! files with the standard security handler (RC4 and AES).
+use super::pdf_repair::rebuild_xref;
+use crate::errors::AppError;
+use lopdf::encryption::PasswordAlgorithm;
+use lopdf::{Document, Permissions};
+use serde::Serialize;
+
+pub struct LoadedPdf {
+    pub doc: Document,
+    /// Whether the file is encrypted, even if it opened without a password.
+    pub encrypted: bool,
+    /// Set for encrypted files; unencrypted files allow everything.
+    pub permissions: Option<PdfPermissions>,
+    /// Whether the cross-reference table had to be rebuilt to open the file.
+    pub repaired: bool,
+}
+
+/// The operations the document's author allows, from the `/P` entry of
+/// the encryption dictionary.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfPermissions {
+    pub print: bool,
+    pub print_high_quality: bool,
+    pub modify: bool,
+    /// Copying or extracting text and graphics.
+    pub copy: bool,
+    pub copy_for_accessibility: bool,
+    pub annotate: bool,
+    pub fill_forms: bool,
+    pub assemble: bool,
+}
+
+impl From<Permissions> for PdfPermissions {
+    fn from(permissions: Permissions) -> Self {
+        PdfPermissions {
+            print: permissions.contains(Permissions::PRINTABLE),
+            print_high_quality: permissions.contains(Permissions::PRINTABLE_IN_HIGH_QUALITY),
+            modify: permissions.contains(Permissions::MODIFIABLE),
+            copy: permissions.contains(Permissions::COPYABLE),
+            copy_for_accessibility: permissions.contains(Permissions::COPYABLE_FOR_ACCESSIBILITY),
+            annotate: permissions.contains(Permissions::ANNOTABLE),
+            fill_forms: permissions.contains(Permissions::FILLABLE),
+            assemble: permissions.contains(Permissions::ASSEMBLABLE),
+        }
+    }
+}
+
+/// Loads a PDF, decrypting it with `password` if it needs one. Files that
+/// only restrict permissions open with an empty user password and need
+/// none. Fails with `AppError::PdfPasswordRequired` when a password is
+/// missing or wrong. Files with a damaged cross-reference table are
+/// repaired in memory and reloaded.
+pub fn load_pdf(file_path: &str, password: Option<&str>) -> Result<LoadedPdf, AppError> {
+    // The file's bytes are released as soon as the document is parsed.
+    let (mut doc, repaired) = load_lenient(&std::fs::read(file_path)?)?;
+
+    unlock(&mut doc, password)?;
+
+    let permissions = doc
+        .encryption_state
+        .as_ref()
+        .map(|state| PdfPermissions::from(state.permissions()));
+
+    Ok(LoadedPdf {
+        encrypted: permissions.is_some(),
+        permissions,
+        repaired,
+        doc,
+    })
+}
+
+/// Decrypts `doc` in place if it is still encrypted after loading.
+pub fn unlock(doc: &mut Document, password: Option<&str>) -> Result<(), AppError> {
+    // lopdf has already decrypted files that accept the empty password.
+    if doc.is_encrypted() {
+        let password = password.filter(|p| !p.is_empty()).ok_or_else(|| {
+            AppError::PdfPasswordRequired("Enter the password to open this PDF".to_string())
+        })?;
+
+        // Only the user (open) password is accepted: lopdf authenticates
+        // owner passwords of RC4/AES-128 files but derives the wrong key
+        // from them, which would silently yield garbage text.
+        let algorithm = PasswordAlgorithm::try_from(&*doc)
+            .map_err(|e| AppError::PdfError(format!("Unsupported PDF encryption: {}", e)))?;
+        let authenticated = algorithm
+            .sanitize_password(password)
+            .and_then(|sanitized| algorithm.authenticate_user_password(&*doc, sanitized));
+        if authenticated.is_err() {
+            return Err(AppError::PdfPasswordRequired(
+                "The password is incorrect".to_string(),
+            ));
+        }
+        doc.decrypt(password)
+            .map_err(|e| AppError::PdfError(format!("Failed to decrypt PDF: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Parses the file as written, falling back to a rebuilt cross-reference
+/// table when parsing fails or leaves the document without a page tree.
+/// Returns the document and whether it was repaired.
+pub fn load_lenient(bytes: &[u8]) -> Result<(Document, bool), AppError> {
+    // The unrepaired document is dropped before repairing, so at most the
+    // file, its repaired copy and one parsed document are held at once.
+    let original_error = match Document::load_mem(bytes) {
+        Ok(doc) if is_intact(&doc) => return Ok((doc, false)),
+        Ok(_) => None,
+        Err(e) => Some(e),
+    };
+
+    let repaired = rebuild_xref(bytes)
+        .and_then(|rebuilt| Document::load_mem(&rebuilt).ok())
+        .filter(is_intact);
+    match (repaired, original_error) {
+        (Some(doc), _) => Ok((doc, true)),
+        // Damaged beyond repair but still parseable: better than nothing.
+        (None, None) => Document::load_mem(bytes)
+            .map(|doc| (doc, false))
+            .map_err(load_error),
+        (None, Some(e)) => Err(load_error(e)),
+    }
+}
+
+/// Only the catalog and page tree are checked; both stay readable in
+/// encrypted files.
+pub fn is_intact(doc: &Document) -> bool {
+    doc.catalog().is_ok() && !doc.get_pages().is_empty()
+}
+
+fn load_error(error: lopdf::Error) -> AppError {
+    AppError::PdfError(format!("Failed to load PDF: {}", error))
+}
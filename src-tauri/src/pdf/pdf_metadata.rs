@@ -0,0 +1,253 @@
+// src-tauri/src/pdf/pdf_metadata.rs
+//! Document metadata from the Info dictionary and the XMP packet.
+use super::pdf_fonts::resolve;
+use crate::html::tokenizer::decode_entities;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use lopdf::{Document, Object};
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<DateTime<Utc>>,
+    pub modification_date: Option<DateTime<Utc>>,
+}
+
+impl DocumentMetadata {
+    /// Fills fields missing here from `other`.
+    pub fn or(self, other: DocumentMetadata) -> DocumentMetadata {
+        DocumentMetadata {
+            title: self.title.or(other.title),
+            author: self.author.or(other.author),
+            subject: self.subject.or(other.subject),
+            keywords: self.keywords.or(other.keywords),
+            creator: self.creator.or(other.creator),
+            producer: self.producer.or(other.producer),
+            creation_date: self.creation_date.or(other.creation_date),
+            modification_date: self.modification_date.or(other.modification_date),
+        }
+    }
+}
+
+/// Reads the trailer's Info dictionary.
+pub fn info_metadata(doc: &Document) -> DocumentMetadata {
+    let Some(info) = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_dict().ok())
+    else {
+        return DocumentMetadata::default();
+    };
+
+    let text = |key: &[u8]| {
+        info.get(key)
+            .ok()
+            .and_then(|o| resolve(doc, o).as_str().ok())
+            .map(decode_text_string)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    DocumentMetadata {
+        title: text(b"Title"),
+        author: text(b"Author"),
+        subject: text(b"Subject"),
+        keywords: text(b"Keywords"),
+        creator: text(b"Creator"),
+        producer: text(b"Producer"),
+        creation_date: text(b"CreationDate").and_then(|d| parse_pdf_date(&d)),
+        modification_date: text(b"ModDate").and_then(|d| parse_pdf_date(&d)),
+    }
+}
+
+/// Reads the catalog's XMP metadata stream, if there is one.
+pub fn xmp_metadata(doc: &Document) -> Option<DocumentMetadata> {
+    let catalog = doc.catalog().ok()?;
+    let stream = resolve(doc, catalog.get(b"Metadata").ok()?)
+        .as_stream()
+        .ok()?;
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    Some(parse_xmp(&String::from_utf8_lossy(&content)))
+}
+
+fn parse_xmp(xml: &str) -> DocumentMetadata {
+    DocumentMetadata {
+        title: xmp_property(xml, "dc:title"),
+        author: xmp_property(xml, "dc:creator"),
+        subject: xmp_property(xml, "dc:description"),
+        keywords: xmp_property(xml, "pdf:Keywords"),
+        creator: xmp_property(xml, "xmp:CreatorTool"),
+        producer: xmp_property(xml, "pdf:Producer"),
+        creation_date: xmp_property(xml, "xmp:CreateDate").and_then(|d| parse_xmp_date(&d)),
+        modification_date: xmp_property(xml, "xmp:ModifyDate").and_then(|d| parse_xmp_date(&d)),
+    }
+}
+
+/// A simple or array-valued XMP property, written either as an element or
+/// as an attribute of `rdf:Description`. Array items are joined with "; ".
+fn xmp_property(xml: &str, name: &str) -> Option<String> {
+    let name = regex::escape(name);
+    let element = Regex::new(&format!(r"(?s)<{name}(?:\s[^>]*)?>(.*?)</{name}>")).ok()?;
+    let attribute = Regex::new(&format!(r#"\s{name}\s*=\s*(?:"([^"]*)"|'([^']*)')"#)).ok()?;
+    let item = Regex::new(r"(?s)<rdf:li(?:\s[^>]*)?>(.*?)</rdf:li>").expect("valid regex");
+
+    let value = match element.captures(xml) {
+        Some(captures) => {
+            let inner = &captures[1];
+            let items: Vec<String> = item
+                .captures_iter(inner)
+                .map(|c| decode_entities(c[1].trim()))
+                .filter(|s| !s.is_empty())
+                .collect();
+            if items.is_empty() {
+                decode_entities(inner.trim())
+            } else {
+                items.join("; ")
+            }
+        }
+        None => {
+            let captures = attribute.captures(xml)?;
+            let raw = captures.get(1).or_else(|| captures.get(2))?.as_str();
+            decode_entities(raw.trim())
+        }
+    };
+
+    (!value.is_empty() && !value.starts_with('<')).then_some(value)
+}
+
+/// Decodes a PDF text string: UTF-16BE or UTF-8 with a byte order mark,
+/// otherwise PDFDocEncoding.
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    bytes.iter().map(|b| pdf_doc_char(*b)).collect()
+}
+
+/// PDFDocEncoding matches Latin-1 except for the 0x80-0xA0 range.
+fn pdf_doc_char(byte: u8) -> char {
+    const HIGH: [char; 33] = [
+        '\u{2022}', '\u{2020}', '\u{2021}', '\u{2026}', '\u{2014}', '\u{2013}', '\u{0192}',
+        '\u{2044}', '\u{2039}', '\u{203A}', '\u{2212}', '\u{2030}', '\u{201E}', '\u{201C}',
+        '\u{201D}', '\u{2018}', '\u{2019}', '\u{201A}', '\u{2122}', '\u{FB01}', '\u{FB02}',
+        '\u{0141}', '\u{0152}', '\u{0160}', '\u{0178}', '\u{017D}', '\u{0131}', '\u{0142}',
+        '\u{0153}', '\u{0161}', '\u{017E}', '\u{FFFD}', '\u{20AC}',
+    ];
+    match byte {
+        0x80..=0xA0 => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// Parses `D:YYYYMMDDHHmmSSOHH'mm'`, where everything after the year is
+/// optional and a missing offset means UTC.
+pub fn parse_pdf_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let value = value.strip_prefix("D:").unwrap_or(value);
+    let digits: String = value.chars().take_while(char::is_ascii_digit).collect();
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let field = |start: usize, len: usize, default: u32| {
+        digits
+            .get(start..start + len)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default)
+    };
+    let year: i32 = digits[..4].parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, field(4, 2, 1), field(6, 2, 1))?;
+    let local = date.and_hms_opt(field(8, 2, 0), field(10, 2, 0), field(12, 2, 0))?;
+
+    let rest = &value[digits.len()..];
+    let offset_seconds = match rest.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let mut offset = rest[1..]
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<i32>().ok());
+            let hours = offset.next().unwrap_or(Some(0))?;
+            let minutes = offset.next().unwrap_or(Some(0))?;
+            let seconds = hours
+                .checked_mul(3600)?
+                .checked_add(minutes.checked_mul(60)?)?;
+            if sign == '-' {
+                -seconds
+            } else {
+                seconds
+            }
+        }
+        _ => 0,
+    };
+
+    to_utc(local, offset_seconds)
+}
+
+/// Parses an XMP date, which is ISO 8601 possibly without a time or zone.
+fn parse_xmp_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .and_then(|local| to_utc(local, 0))
+}
+
+fn to_utc(local: NaiveDateTime, offset_seconds: i32) -> Option<DateTime<Utc>> {
+    FixedOffset::east_opt(offset_seconds)?
+        .from_local_datetime(&local)
+        .single()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Reads the catalog's optional value for `key` as an object.
+pub fn catalog_entry<'a>(doc: &'a Document, key: &[u8]) -> Option<&'a Object> {
+    doc.catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(key).ok())
+        .map(|o| resolve(doc, o))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pdf_dates_apply_their_offset() {
+        let date = parse_pdf_date("D:20240301123000+05'30'").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-03-01T07:00:00+00:00");
+        let date = parse_pdf_date("D:2024").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn pdf_dates_with_absurd_offsets_are_rejected() {
+        assert_eq!(parse_pdf_date("D:20240301123000+999999'00'"), None);
+        assert_eq!(parse_pdf_date("D:20240301123000-2000000000'00'"), None);
+        assert_eq!(parse_pdf_date("D:20240301123000+00'99999999999'"), None);
+    }
+}
@@ -0,0 +1,500 @@
+// src-tauri/src/pdf/pdf_ocr.rs
+//! OCR for scanned pages. Page images are pulled out of the PDF and handed
+//! to a local Tesseract binary, which runs entirely on the CPU; nothing
+//! leaves the machine.
+use super::pdf_fonts::resolve;
+use crate::errors::AppError;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Overrides where Tesseract is looked for.
+const TESSERACT_PATH_ENV: &str = "TESSERACT_PATH";
+const OCR_LANGUAGE: &str = "eng";
+/// Images smaller than this (in pixels) are logos or icons, not scans.
+const MIN_OCR_PIXELS: u64 = 250_000;
+const MAX_IMAGES_PER_PAGE: usize = 4;
+/// Tesseract is killed if one image takes longer than this.
+const TESSERACT_TIMEOUT: Duration = Duration::from_secs(120);
+const TESSERACT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PageOcr {
+    /// Mean word confidence reported by the engine, from 0 to 1.
+    pub confidence: f32,
+    pub images: usize,
+}
+
+/// Text recognized on one page: lines separated by newlines and
+/// paragraphs by blank lines.
+#[derive(Debug, Clone)]
+pub struct OcrText {
+    pub text: String,
+    pub confidence: f32,
+    pub images: usize,
+}
+
+/// Tesseract binary, looked up once: `TESSERACT_PATH`, then a copy
+/// bundled next to the app executable, then `PATH`.
+pub fn tesseract_path() -> Option<&'static Path> {
+    static PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let binary = if cfg!(windows) {
+            "tesseract.exe"
+        } else {
+            "tesseract"
+        };
+        let bundled = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .into_iter()
+            .flat_map(|dir| [dir.join(binary), dir.join("tesseract").join(binary)]);
+
+        std::env::var_os(TESSERACT_PATH_ENV)
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(bundled)
+            .chain(std::iter::once(PathBuf::from(binary)))
+            .find(|candidate| {
+                Command::new(candidate)
+                    .arg("--version")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|status| status.success())
+            })
+    })
+    .as_deref()
+}
+
+/// Whether the page draws any image large enough to be a scan.
+pub fn has_page_images(doc: &Document, page_id: ObjectId) -> bool {
+    !large_images(doc, page_id).is_empty()
+}
+
+/// Runs OCR over the large images on a page. Returns `None` when the page
+/// has no image Tesseract can read.
+pub fn ocr_page(
+    doc: &Document,
+    page_id: ObjectId,
+    tesseract: &Path,
+) -> Result<Option<OcrText>, AppError> {
+    let images: Vec<Vec<u8>> = large_images(doc, page_id)
+        .into_iter()
+        .filter_map(|image| encode_image(doc, image))
+        .take(MAX_IMAGES_PER_PAGE)
+        .collect();
+    if images.is_empty() {
+        return Ok(None);
+    }
+
+    let mut texts = Vec::new();
+    let (mut weighted_confidence, mut total_chars) = (0.0, 0usize);
+    for image in &images {
+        let recognized = run_tesseract(tesseract, image)?;
+        weighted_confidence += recognized.confidence * recognized.chars as f32;
+        total_chars += recognized.chars;
+        if !recognized.text.is_empty() {
+            texts.push(recognized.text);
+        }
+    }
+
+    Ok(Some(OcrText {
+        text: texts.join("\n\n"),
+        confidence: if total_chars > 0 {
+            weighted_confidence / total_chars as f32
+        } else {
+            0.0
+        },
+        images: images.len(),
+    }))
+}
+
+/// Image XObjects of the page, largest first.
+fn large_images(doc: &Document, page_id: ObjectId) -> Vec<&Stream> {
+    let (page_resources, inherited) = doc.get_page_resources(page_id).unwrap_or_default();
+    let mut images: Vec<(u64, &Stream)> = page_resources
+        .into_iter()
+        .chain(
+            inherited
+                .iter()
+                .filter_map(|id| doc.get_dictionary(*id).ok()),
+        )
+        .filter_map(|resources| resources.get(b"XObject").ok())
+        .filter_map(|xobjects| resolve(doc, xobjects).as_dict().ok())
+        .flat_map(Dictionary::iter)
+        .filter_map(|(_, object)| resolve(doc, object).as_stream().ok())
+        .filter(|stream| {
+            stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice())
+        })
+        .filter_map(|stream| {
+            let (width, height) = image_size(doc, &stream.dict)?;
+            Some((u64::from(width) * u64::from(height), stream))
+        })
+        .filter(|(pixels, _)| *pixels >= MIN_OCR_PIXELS)
+        .collect();
+
+    images.sort_by_key(|(pixels, _)| std::cmp::Reverse(*pixels));
+    images.dedup_by(|a, b| std::ptr::eq(a.1, b.1));
+    images.into_iter().map(|(_, stream)| stream).collect()
+}
+
+/// Width and height of an image, or `None` unless both are positive and
+/// fit in a `u32`.
+fn image_size(doc: &Document, dict: &Dictionary) -> Option<(u32, u32)> {
+    let dimension = |key: &[u8]| {
+        let value = resolve(doc, dict.get(key).ok()?).as_i64().ok()?;
+        u32::try_from(value).ok().filter(|value| *value > 0)
+    };
+    Some((dimension(b"Width")?, dimension(b"Height")?))
+}
+
+/// Converts an image stream into a file format Tesseract reads: JPEG and
+/// JPEG 2000 pass through, CCITT fax data is wrapped in a TIFF header and
+/// raw samples become PNM.
+fn encode_image(doc: &Document, stream: &Stream) -> Option<Vec<u8>> {
+    let dict = &stream.dict;
+    let filters: Vec<&[u8]> = match dict.get(b"Filter") {
+        Ok(Object::Name(name)) => vec![name.as_slice()],
+        Ok(Object::Array(items)) => items.iter().filter_map(|o| o.as_name().ok()).collect(),
+        _ => Vec::new(),
+    };
+    let (width, height) = image_size(doc, dict)?;
+
+    match filters.as_slice() {
+        [b"DCTDecode"] | [b"JPXDecode"] => Some(stream.content.clone()),
+        [b"CCITTFaxDecode"] => {
+            let params = dict.get(b"DecodeParms").and_then(Object::as_dict).ok();
+            ccitt_to_tiff(&stream.content, params, width, height)
+        }
+        [] | [b"FlateDecode"] | [b"LZWDecode"] | [b"RunLengthDecode"] => {
+            let samples = if filters.is_empty() {
+                stream.content.clone()
+            } else {
+                stream.decompressed_content().ok()?
+            };
+            raw_to_pnm(
+                doc,
+                &samples,
+                dict,
+                usize::try_from(width).ok()?,
+                usize::try_from(height).ok()?,
+            )
+        }
+        // JBIG2 and chained filters are not decoded.
+        _ => None,
+    }
+}
+
+fn raw_to_pnm(
+    doc: &Document,
+    samples: &[u8],
+    dict: &Dictionary,
+    width: usize,
+    height: usize,
+) -> Option<Vec<u8>> {
+    let bits = dict
+        .get(b"BitsPerComponent")
+        .and_then(Object::as_i64)
+        .unwrap_or(8);
+    let components = match resolve(doc, dict.get(b"ColorSpace").ok()?) {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" | b"CalGray" => 1,
+            b"DeviceRGB" | b"CalRGB" => 3,
+            b"DeviceCMYK" => 4,
+            _ => return None,
+        },
+        // [/ICCBased stream]: the profile stream gives the component count.
+        Object::Array(space)
+            if space.first().and_then(|o| o.as_name().ok()) == Some(b"ICCBased") =>
+        {
+            resolve(doc, space.get(1)?)
+                .as_stream()
+                .ok()?
+                .dict
+                .get(b"N")
+                .and_then(Object::as_i64)
+                .ok()?
+        }
+        _ => return None,
+    };
+
+    let pixels = width.checked_mul(height)?;
+    let mut pnm = Vec::new();
+    match (bits, components) {
+        (1, 1) => {
+            // PBM uses 1 for black, the reverse of DeviceGray, unless the
+            // image has an inverting Decode array.
+            let inverted = dict
+                .get(b"Decode")
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|decode| decode.first().and_then(|o| o.as_float().ok()))
+                .is_some_and(|first| first > 0.5);
+            let row_bytes = width.div_ceil(8);
+            let data = samples.get(..row_bytes.checked_mul(height)?)?;
+            write!(pnm, "P4\n{} {}\n", width, height).ok()?;
+            pnm.extend(data.iter().map(|b| if inverted { *b } else { !b }));
+        }
+        (8, 1) => {
+            write!(pnm, "P5\n{} {}\n255\n", width, height).ok()?;
+            pnm.extend_from_slice(samples.get(..pixels)?);
+        }
+        (8, 3) => {
+            write!(pnm, "P6\n{} {}\n255\n", width, height).ok()?;
+            pnm.extend_from_slice(samples.get(..pixels.checked_mul(3)?)?);
+        }
+        (8, 4) => {
+            write!(pnm, "P6\n{} {}\n255\n", width, height).ok()?;
+            for cmyk in samples.get(..pixels.checked_mul(4)?)?.chunks_exact(4) {
+                let k = 255 - u16::from(cmyk[3]);
+                pnm.extend(
+                    cmyk[..3]
+                        .iter()
+                        .map(|c| ((255 - u16::from(*c)) * k / 255) as u8),
+                );
+            }
+        }
+        _ => return None,
+    }
+    Some(pnm)
+}
+
+/// Wraps CCITT Group 3/4 data in a single-strip little-endian TIFF.
+fn ccitt_to_tiff(
+    data: &[u8],
+    params: Option<&Dictionary>,
+    width: u32,
+    height: u32,
+) -> Option<Vec<u8>> {
+    let param = |key: &[u8]| params.and_then(|p| p.get(key).ok());
+    let positive = |key: &[u8]| {
+        param(key)
+            .and_then(|o| o.as_i64().ok())
+            .and_then(|value| u32::try_from(value).ok())
+            .filter(|value| *value > 0)
+    };
+    let k = param(b"K").and_then(|o| o.as_i64().ok()).unwrap_or(0);
+    let columns = positive(b"Columns").unwrap_or(width);
+    let rows = positive(b"Rows").unwrap_or(height);
+    let black_is_one = matches!(param(b"BlackIs1"), Some(Object::Boolean(true)));
+
+    const SHORT: u16 = 3;
+    const LONG: u16 = 4;
+    let mut entries: Vec<(u16, u16, u32)> = vec![
+        (256, LONG, columns),
+        (257, LONG, rows),
+        (258, SHORT, 1),
+        (259, SHORT, if k < 0 { 4 } else { 3 }),
+        (262, SHORT, u32::from(black_is_one)),
+        (273, LONG, 0),
+        (277, SHORT, 1),
+        (278, LONG, rows),
+        (279, LONG, u32::try_from(data.len()).ok()?),
+    ];
+    if k > 0 {
+        // T4Options: two-dimensional coding.
+        entries.push((292, LONG, 1));
+    }
+
+    let ifd_len = 2 + entries.len() * 12 + 4;
+    let data_offset = (8 + ifd_len) as u32;
+
+    let mut tiff = Vec::with_capacity(data_offset as usize + data.len());
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    tiff.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, kind, value) in entries {
+        let value = if tag == 273 { data_offset } else { value };
+        tiff.extend_from_slice(&tag.to_le_bytes());
+        tiff.extend_from_slice(&kind.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        if kind == SHORT {
+            tiff.extend_from_slice(&(value as u16).to_le_bytes());
+            tiff.extend_from_slice(&[0, 0]);
+        } else {
+            tiff.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(data);
+    Some(tiff)
+}
+
+struct Recognized {
+    text: String,
+    confidence: f32,
+    chars: usize,
+}
+
+/// Runs Tesseract on one image, killing it after [`TESSERACT_TIMEOUT`].
+fn run_tesseract(tesseract: &Path, image: &[u8]) -> Result<Recognized, AppError> {
+    let mut child = Command::new(tesseract)
+        .args(["stdin", "stdout", "-l", OCR_LANGUAGE, "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::PdfError(format!("Failed to start Tesseract: {}", e)))?;
+
+    // Feed and drain the pipes from other threads so a full pipe cannot
+    // deadlock us while we wait.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let image = image.to_vec();
+    let writer = thread::spawn(move || stdin.write_all(&image));
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    let deadline = Instant::now() + TESSERACT_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(TESSERACT_POLL_INTERVAL),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(AppError::PdfError(format!(
+                    "Tesseract did not finish within {} seconds",
+                    TESSERACT_TIMEOUT.as_secs()
+                )));
+            }
+            Err(e) => {
+                let _ = child.kill();
+                return Err(AppError::PdfError(format!("Tesseract failed: {}", e)));
+            }
+        }
+    };
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(AppError::PdfError(format!(
+            "Tesseract failed: {}",
+            stderr.lines().last().unwrap_or("unknown error")
+        )));
+    }
+
+    Ok(parse_tsv(&String::from_utf8_lossy(&stdout)))
+}
+
+/// Reads a pipe to the end on its own thread.
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// Rebuilds lines and paragraphs from Tesseract's TSV word boxes.
+fn parse_tsv(tsv: &str) -> Recognized {
+    // (block, paragraph, line) -> words
+    let mut lines: BTreeMap<(u32, u32, u32), Vec<&str>> = BTreeMap::new();
+    let (mut weighted_confidence, mut chars) = (0.0, 0usize);
+
+    for row in tsv.lines().skip(1) {
+        let fields: Vec<&str> = row.split('\t').collect();
+        if fields.len() < 12 || fields[0] != "5" {
+            continue;
+        }
+        let word = fields[11].trim();
+        let confidence: f32 = fields[10].parse().unwrap_or(-1.0);
+        if word.is_empty() || confidence < 0.0 {
+            continue;
+        }
+        let position = |i: usize| fields[i].parse().unwrap_or(0);
+        lines
+            .entry((position(2), position(3), position(4)))
+            .or_default()
+            .push(word);
+
+        let word_chars = word.chars().count();
+        weighted_confidence += confidence / 100.0 * word_chars as f32;
+        chars += word_chars;
+    }
+
+    let mut text = String::new();
+    let mut previous_paragraph = None;
+    for ((block, paragraph, _), words) in lines {
+        if !text.is_empty() {
+            text.push_str(if previous_paragraph == Some((block, paragraph)) {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        text.push_str(&words.join(" "));
+        previous_paragraph = Some((block, paragraph));
+    }
+
+    Recognized {
+        text,
+        confidence: if chars > 0 {
+            weighted_confidence / chars as f32
+        } else {
+            0.0
+        },
+        chars,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    fn gray_image(width: i64, height: i64) -> Dictionary {
+        dictionary! {
+            "Width" => width,
+            "Height" => height,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8,
+        }
+    }
+
+    #[test]
+    fn image_size_rejects_non_positive_and_oversized_dimensions() {
+        let doc = Document::with_version("1.7");
+        assert_eq!(image_size(&doc, &gray_image(640, 480)), Some((640, 480)));
+        assert_eq!(image_size(&doc, &gray_image(-1000, -1000)), None);
+        assert_eq!(image_size(&doc, &gray_image(0, 480)), None);
+        assert_eq!(image_size(&doc, &gray_image(1 << 40, 1)), None);
+    }
+
+    #[test]
+    fn raw_to_pnm_rejects_sizes_that_overflow() {
+        let doc = Document::with_version("1.7");
+        let dict = gray_image(2, 2);
+        let pnm = raw_to_pnm(&doc, &[0, 64, 128, 255], &dict, 2, 2).unwrap();
+        assert_eq!(pnm, b"P5\n2 2\n255\n\x00\x40\x80\xff");
+
+        assert_eq!(raw_to_pnm(&doc, &[0; 16], &dict, usize::MAX, 2), None);
+        let mut rgb = dict.clone();
+        rgb.set("ColorSpace", "DeviceRGB");
+        assert_eq!(raw_to_pnm(&doc, &[0; 16], &rgb, usize::MAX / 2, 1), None);
+    }
+
+    #[test]
+    fn ccitt_ignores_non_positive_columns_and_rows() {
+        let params = dictionary! { "K" => -1, "Columns" => -8, "Rows" => 0 };
+        let tiff = ccitt_to_tiff(&[1, 2, 3], Some(&params), 100, 50).unwrap();
+        // Entries start after the 8-byte header and the entry count; the
+        // value of each is at byte 8 of its 12.
+        let value = |entry: usize| {
+            let at = 10 + entry * 12 + 8;
+            u32::from_le_bytes(tiff[at..at + 4].try_into().unwrap())
+        };
+        assert_eq!((value(0), value(1)), (100, 50));
+        assert_eq!(&tiff[tiff.len() - 3..], &[1, 2, 3]);
+    }
+}
@@ -0,0 +1,439 @@
+// src-tauri/src/pdf/pdf_outline.rs
+//! The document outline (bookmarks) as a nested table of contents, with
+//! each entry mapped to the pages it covers.
+use super::pdf_fonts::resolve;
+use super::pdf_loader::load_pdf;
+use super::pdf_metadata::{catalog_entry, decode_text_string};
+use super::pdf_page_range::PageSelection;
+use crate::errors::AppError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Outlines are linked lists that malformed files can make cyclic or
+/// absurdly deep; both are cut off.
+const MAX_OUTLINE_DEPTH: usize = 16;
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TocEntry {
+    /// Position in the tree, such as "2.1" for the first child of the
+    /// second top-level entry.
+    pub id: String,
+    pub title: String,
+    pub level: usize,
+    /// First and last page of the section, inclusive. `None` when the
+    /// bookmark points nowhere in this document.
+    pub start_page: Option<u32>,
+    pub end_page: Option<u32>,
+    pub children: Vec<TocEntry>,
+}
+
+/// The outline with the page count of the whole document, read in one load.
+#[derive(Debug, Clone)]
+pub struct PdfOutline {
+    pub entries: Vec<TocEntry>,
+    pub page_count: usize,
+}
+
+impl TocEntry {
+    pub fn find<'a>(entries: &'a [TocEntry], id: &str) -> Option<&'a TocEntry> {
+        entries.iter().find_map(|entry| {
+            if entry.id == id {
+                Some(entry)
+            } else {
+                TocEntry::find(&entry.children, id)
+            }
+        })
+    }
+
+    /// Entries in document order, parents before their children.
+    pub fn flatten(entries: &[TocEntry]) -> Vec<&TocEntry> {
+        let mut flat = Vec::new();
+        for entry in entries {
+            flat.push(entry);
+            flat.extend(TocEntry::flatten(&entry.children));
+        }
+        flat
+    }
+}
+
+/// Reads the outline tree. Returns an empty list for documents without
+/// bookmarks. With a page selection, only entries whose pages overlap it
+/// are kept.
+pub fn get_outline(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<PdfOutline, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let pages = doc.get_pages();
+    let page_count = pages.len();
+    selection.select(pages)?;
+    let mut entries = read_outline(&doc);
+    if !selection.is_all() {
+        retain_overlapping(&mut entries, selection);
+    }
+    Ok(PdfOutline {
+        entries,
+        page_count,
+    })
+}
+
+/// Drops entries that cover none of the selected pages. Entry ids keep
+/// their positions in the full outline.
+fn retain_overlapping(entries: &mut Vec<TocEntry>, selection: &PageSelection) {
+    entries.retain_mut(|entry| {
+        retain_overlapping(&mut entry.children, selection);
+        let overlaps = match (entry.start_page, entry.end_page) {
+            (Some(start), Some(end)) => (start..=end).any(|page| selection.contains(page)),
+            _ => false,
+        };
+        overlaps || !entry.children.is_empty()
+    });
+}
+
+pub fn read_outline(doc: &Document) -> Vec<TocEntry> {
+    let Some(outlines) = catalog_entry(doc, b"Outlines").and_then(|o| o.as_dict().ok()) else {
+        return Vec::new();
+    };
+
+    let page_numbers: HashMap<ObjectId, u32> = doc
+        .get_pages()
+        .into_iter()
+        .map(|(number, id)| (id, number))
+        .collect();
+    let page_count = page_numbers.len() as u32;
+
+    let reader = OutlineReader {
+        doc,
+        page_numbers,
+        page_count,
+    };
+    let mut visited = HashSet::new();
+    let mut entries = reader.read_children(outlines, "", 1, &mut visited);
+
+    fill_missing_starts(&mut entries);
+    let starts: Vec<(usize, Option<u32>)> = TocEntry::flatten(&entries)
+        .into_iter()
+        .map(|e| (e.level, e.start_page))
+        .collect();
+    let mut index = 0;
+    assign_end_pages(&mut entries, &starts, &mut index, page_count);
+
+    entries
+}
+
+struct OutlineReader<'a> {
+    doc: &'a Document,
+    page_numbers: HashMap<ObjectId, u32>,
+    page_count: u32,
+}
+
+impl OutlineReader<'_> {
+    fn read_children(
+        &self,
+        parent: &Dictionary,
+        parent_id: &str,
+        level: usize,
+        visited: &mut HashSet<ObjectId>,
+    ) -> Vec<TocEntry> {
+        let mut entries = Vec::new();
+        if level > MAX_OUTLINE_DEPTH {
+            return entries;
+        }
+
+        let mut next = parent.get(b"First").and_then(Object::as_reference).ok();
+        while let Some(item_id) = next {
+            if !visited.insert(item_id) {
+                break;
+            }
+            let Ok(item) = self.doc.get_dictionary(item_id) else {
+                break;
+            };
+
+            let id = if parent_id.is_empty() {
+                (entries.len() + 1).to_string()
+            } else {
+                format!("{}.{}", parent_id, entries.len() + 1)
+            };
+            let title = item
+                .get(b"Title")
+                .ok()
+                .and_then(|o| resolve(self.doc, o).as_str().ok())
+                .map(decode_text_string)
+                .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default();
+
+            entries.push(TocEntry {
+                start_page: self.destination_page(item),
+                end_page: None,
+                children: self.read_children(item, &id, level + 1, visited),
+                id,
+                title,
+                level,
+            });
+
+            next = item.get(b"Next").and_then(Object::as_reference).ok();
+        }
+
+        entries
+    }
+
+    /// The page an outline item jumps to, through `Dest` or a GoTo action.
+    fn destination_page(&self, item: &Dictionary) -> Option<u32> {
+        let destination = match item.get(b"Dest") {
+            Ok(dest) => resolve(self.doc, dest),
+            Err(_) => {
+                let action = item
+                    .get(b"A")
+                    .ok()
+                    .and_then(|o| resolve(self.doc, o).as_dict().ok())?;
+                if action.get(b"S").and_then(Object::as_name).ok() != Some(b"GoTo".as_slice()) {
+                    return None;
+                }
+                resolve(self.doc, action.get(b"D").ok()?)
+            }
+        };
+        self.page_of(destination)
+    }
+
+    fn page_of(&self, destination: &Object) -> Option<u32> {
+        match destination {
+            Object::Array(items) => match items.first()? {
+                Object::Reference(page_id) => self.page_numbers.get(page_id).copied(),
+                // Integer targets are zero-based page indexes.
+                Object::Integer(index) => index
+                    .checked_add(1)
+                    .and_then(|page| u32::try_from(page).ok())
+                    .filter(|page| (1..=self.page_count).contains(page)),
+                _ => None,
+            },
+            // A destination dictionary wraps the array in `D`.
+            Object::Dictionary(dict) => self.page_of(resolve(self.doc, dict.get(b"D").ok()?)),
+            Object::Name(name) => {
+                let dests = catalog_entry(self.doc, b"Dests").and_then(|o| o.as_dict().ok())?;
+                self.page_of(resolve(self.doc, dests.get(name).ok()?))
+            }
+            Object::String(name, _) => {
+                let names = catalog_entry(self.doc, b"Names").and_then(|o| o.as_dict().ok())?;
+                let tree = resolve(self.doc, names.get(b"Dests").ok()?)
+                    .as_dict()
+                    .ok()?;
+                let target = lookup_name_tree(self.doc, tree, name, 0)?;
+                self.page_of(resolve(self.doc, target))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn lookup_name_tree<'a>(
+    doc: &'a Document,
+    node: &'a Dictionary,
+    key: &[u8],
+    depth: usize,
+) -> Option<&'a Object> {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return None;
+    }
+
+    if let Some(names) = node
+        .get(b"Names")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+    {
+        let found = names.chunks_exact(2).find_map(|pair| {
+            (resolve(doc, &pair[0]).as_str().ok() == Some(key)).then_some(&pair[1])
+        });
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    node.get(b"Kids")
+        .ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())?
+        .iter()
+        .filter_map(|kid| resolve(doc, kid).as_dict().ok())
+        .find_map(|kid| lookup_name_tree(doc, kid, key, depth + 1))
+}
+
+/// Entries without a destination of their own start where their first
+/// child does.
+fn fill_missing_starts(entries: &mut [TocEntry]) {
+    for entry in entries {
+        fill_missing_starts(&mut entry.children);
+        if entry.start_page.is_none() {
+            entry.start_page = entry.children.iter().find_map(|c| c.start_page);
+        }
+    }
+}
+
+/// A section runs until the page before the next entry at the same or a
+/// higher level begins, or to the end of the document. Sections that share
+/// a page with the next one end on that page.
+fn assign_end_pages(
+    entries: &mut [TocEntry],
+    starts: &[(usize, Option<u32>)],
+    index: &mut usize,
+    page_count: u32,
+) {
+    for entry in entries {
+        let position = *index;
+        *index += 1;
+
+        if let Some(start) = entry.start_page {
+            let next_start = starts[position + 1..]
+                .iter()
+                .filter(|(level, _)| *level <= entry.level)
+                .find_map(|(_, start)| *start);
+            let end = match next_start {
+                Some(next) if next > start => next - 1,
+                Some(_) => start,
+                None => page_count,
+            };
+            entry.end_page = Some(end.max(start));
+        }
+
+        assign_end_pages(&mut entry.children, starts, index, page_count);
+    }
+}
+
+/// Cuts a section's text out of its pages: the first page is trimmed to
+/// start at the section title and the last page to stop before the next
+/// section's title, when those titles can be found in the text.
+pub fn section_text(pages: &[(u32, String)], entry: &TocEntry, next: Option<&TocEntry>) -> String {
+    let (Some(start), Some(end)) = (entry.start_page, entry.end_page) else {
+        return String::new();
+    };
+    let mut text = pages
+        .iter()
+        .filter(|(page, _)| (start..=end).contains(page))
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if let Some(position) = find_title(&text, &entry.title) {
+        text.drain(..position);
+    }
+    if let Some(next) = next.filter(|n| n.start_page == Some(end)) {
+        // Skip the section's own first line so the search cannot cut
+        // everything when both titles start the same way.
+        let search_from = text.find('\n').map_or(text.len(), |i| i + 1);
+        if let Some(position) = find_title(&text[search_from..], &next.title) {
+            text.truncate(search_from + position);
+        }
+    }
+
+    text.trim().to_string()
+}
+
+/// Byte offset of the line starting with `title`, ignoring case and a
+/// leading Markdown heading marker.
+fn find_title(text: &str, title: &str) -> Option<usize> {
+    let title = title.trim().to_lowercase();
+    if title.is_empty() {
+        return None;
+    }
+
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_start_matches('#').trim().to_lowercase();
+        if content.starts_with(&title) {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    /// A two-page document whose bookmarks point at the given page indexes.
+    fn document(targets: &[i64]) -> Document {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_ids: Vec<Object> = (0..2)
+            .map(|_| {
+                let page = dictionary! { "Type" => "Page", "Parent" => pages_id };
+                doc.add_object(page).into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => 2,
+                "Kids" => page_ids,
+            }),
+        );
+
+        let outlines_id = doc.new_object_id();
+        let item_ids: Vec<ObjectId> = targets.iter().map(|_| doc.new_object_id()).collect();
+        for (i, (&target, &item_id)) in targets.iter().zip(&item_ids).enumerate() {
+            let mut item = dictionary! {
+                "Title" => Object::string_literal(format!("Item {}", i + 1)),
+                "Parent" => outlines_id,
+                "Dest" => vec![Object::Integer(target), "Fit".into()],
+            };
+            if let Some(&next) = item_ids.get(i + 1) {
+                item.set("Next", next);
+            }
+            doc.objects.insert(item_id, Object::Dictionary(item));
+        }
+        doc.objects.insert(
+            outlines_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Outlines",
+                "First" => item_ids[0],
+            }),
+        );
+        let catalog = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Outlines" => outlines_id,
+        });
+        doc.trailer.set("Root", catalog);
+        doc
+    }
+
+    #[test]
+    fn integer_destinations_are_zero_based() {
+        let entries = read_outline(&document(&[0, 1]));
+        let starts: Vec<Option<u32>> = entries.iter().map(|e| e.start_page).collect();
+        assert_eq!(starts, [Some(1), Some(2)]);
+        assert_eq!(entries[1].end_page, Some(2));
+    }
+
+    #[test]
+    fn out_of_range_destinations_point_nowhere() {
+        let doc = document(&[i64::MAX, -1, 2]);
+        let reader = OutlineReader {
+            doc: &doc,
+            page_numbers: HashMap::new(),
+            page_count: 2,
+        };
+        for target in [i64::MAX, -1, 2] {
+            let destination = Object::Array(vec![Object::Integer(target), "Fit".into()]);
+            assert_eq!(reader.page_of(&destination), None);
+        }
+    }
+
+    #[test]
+    fn outline_reports_the_document_page_count() {
+        let path = std::env::temp_dir().join(format!("outline-{}.pdf", std::process::id()));
+        document(&[1]).save(&path).unwrap();
+        let outline = get_outline(path.to_str().unwrap(), None, &PageSelection::all());
+        std::fs::remove_file(&path).unwrap();
+
+        let outline = outline.unwrap();
+        assert_eq!(outline.page_count, 2);
+        assert_eq!(outline.entries[0].title, "Item 1");
+        assert_eq!(outline.entries[0].start_page, Some(2));
+    }
+}
@@ -0,0 +1,176 @@
+// src-tauri/src/pdf/pdf_page_range.rs
+//! Page selections such as "3-5, 9, 12-" that limit PDF commands to part
+//! of a document.
+use crate::errors::AppError;
+use lopdf::ObjectId;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// One-based, inclusive page ranges. An empty selection means every page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSelection {
+    /// `(first, last)`; `last` is `None` for open-ended ranges like "12-".
+    ranges: Vec<(u32, Option<u32>)>,
+}
+
+impl PageSelection {
+    pub fn all() -> Self {
+        PageSelection::default()
+    }
+
+    /// Parses a comma-separated list of pages and ranges. `None` or a
+    /// blank spec selects every page.
+    pub fn parse(spec: Option<&str>) -> Result<Self, AppError> {
+        let Some(spec) = spec.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(PageSelection::all());
+        };
+        let invalid =
+            |reason: &str| AppError::PdfError(format!("Invalid page range '{}': {}", spec, reason));
+        let page = |value: &str| -> Result<u32, AppError> {
+            match value.trim().parse::<u32>() {
+                Ok(0) => Err(invalid("pages are numbered from 1")),
+                Ok(page) => Ok(page),
+                Err(_) => Err(invalid(&format!("'{}' is not a page number", value.trim()))),
+            }
+        };
+
+        let mut ranges = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let range = match part.split_once(['-', '\u{2013}']) {
+                Some((first, last)) => {
+                    let first = if first.trim().is_empty() {
+                        1
+                    } else {
+                        page(first)?
+                    };
+                    let last = if last.trim().is_empty() {
+                        None
+                    } else {
+                        Some(page(last)?)
+                    };
+                    if last.is_some_and(|last| last < first) {
+                        return Err(invalid(&format!("'{}' runs backwards", part)));
+                    }
+                    (first, last)
+                }
+                None => {
+                    let page = page(part)?;
+                    (page, Some(page))
+                }
+            };
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            return Err(invalid("no pages given"));
+        }
+        Ok(PageSelection { ranges })
+    }
+
+    /// Selects the pages from `first` to `last`, inclusive.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = (u32, u32)>) -> Self {
+        PageSelection {
+            ranges: ranges
+                .into_iter()
+                .map(|(first, last)| (first.max(1), Some(last.max(first).max(1))))
+                .collect(),
+        }
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, page: u32) -> bool {
+        self.is_all()
+            || self
+                .ranges
+                .iter()
+                .any(|(first, last)| page >= *first && last.is_none_or(|last| page <= last))
+    }
+
+    /// The selected pages of a document, in page order. Ranges running past
+    /// the last page are cut short; a selection with no page in the
+    /// document is an error.
+    pub fn select(&self, pages: BTreeMap<u32, ObjectId>) -> Result<Vec<(u32, ObjectId)>, AppError> {
+        let page_count = pages.len();
+        let selected: Vec<(u32, ObjectId)> = pages
+            .into_iter()
+            .filter(|(page_number, _)| self.contains(*page_number))
+            .collect();
+
+        if selected.is_empty() && page_count > 0 {
+            return Err(AppError::PdfError(format!(
+                "Page range '{}' is outside the document's {} pages",
+                self, page_count
+            )));
+        }
+        Ok(selected)
+    }
+}
+
+impl fmt::Display for PageSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_all() {
+            return f.write_str("all");
+        }
+        let parts: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|range| match range {
+                (first, Some(last)) if first == last => first.to_string(),
+                (first, Some(last)) => format!("{}-{}", first, last),
+                (first, None) => format!("{}-", first),
+            })
+            .collect();
+        f.write_str(&parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(count: u32) -> BTreeMap<u32, ObjectId> {
+        (1..=count).map(|page| (page, (page + 10, 0))).collect()
+    }
+
+    #[test]
+    fn blank_specs_select_every_page() {
+        assert!(PageSelection::parse(None).unwrap().is_all());
+        assert!(PageSelection::parse(Some("  ")).unwrap().is_all());
+    }
+
+    #[test]
+    fn parses_pages_and_ranges() {
+        let selection = PageSelection::parse(Some("3-5, 9,12-, –2")).unwrap();
+        assert_eq!(selection.to_string(), "3-5,9,12-,1-2");
+        for page in [1, 2, 3, 5, 9, 12, 400] {
+            assert!(selection.contains(page), "{}", page);
+        }
+        for page in [6, 8, 10, 11] {
+            assert!(!selection.contains(page), "{}", page);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        for spec in ["0", "5-3", "a", "2-x", ",", "99999999999"] {
+            assert!(PageSelection::parse(Some(spec)).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn select_cuts_ranges_at_the_last_page() {
+        let selection = PageSelection::parse(Some("2-9")).unwrap();
+        let selected = selection.select(pages(4)).unwrap();
+        assert_eq!(
+            selected.iter().map(|(page, _)| *page).collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert!(PageSelection::parse(Some("7"))
+            .unwrap()
+            .select(pages(4))
+            .is_err());
+    }
+}
@@ -0,0 +1,222 @@
+// src-tauri/src/pdf/pdf_repair.rs
+//! Recovers PDFs whose cross-reference table is broken or missing by
+//! scanning the file for objects and appending a freshly built xref
+//! section and trailer, which readers use in place of the damaged one.
+use regex::bytes::Regex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+/// How far into the file the `%PDF-` header may start; some producers
+/// prepend junk before it.
+pub const HEADER_SEARCH_BYTES: usize = 1024;
+
+/// Where the `%%EOF` marker must appear, counted from the end of the file.
+pub const EOF_SEARCH_BYTES: usize = 1024;
+
+/// The largest object number PDF allows (ISO 32000-1, Annex C). Headers
+/// with larger numbers are junk and are skipped.
+pub const MAX_OBJECT_NUMBER: u32 = 8_388_607;
+
+/// Byte offset of the `%PDF-` header, if the file has one near the start.
+pub fn header_offset(bytes: &[u8]) -> Option<usize> {
+    let window = &bytes[..bytes.len().min(HEADER_SEARCH_BYTES)];
+    window.windows(5).position(|w| w == b"%PDF-")
+}
+
+pub fn has_eof_marker(bytes: &[u8]) -> bool {
+    let tail = &bytes[bytes.len().saturating_sub(EOF_SEARCH_BYTES)..];
+    tail.windows(5).any(|w| w == b"%%EOF")
+}
+
+/// Rebuilds the file with a cross-reference table covering every object
+/// found by scanning. Returns `None` when no objects or no document
+/// catalog can be found. Offsets are relative to the `%PDF-` header,
+/// which is where the rebuilt file starts.
+pub fn rebuild_xref(bytes: &[u8]) -> Option<Vec<u8>> {
+    let body = &bytes[header_offset(bytes)?..];
+    let objects = scan_objects(body);
+    if objects.is_empty() {
+        return None;
+    }
+
+    let root = last_reference(body, "Root")
+        .filter(|root| objects.get(&root.0).is_some_and(|o| o.generation == root.1))
+        .or_else(|| find_catalog(body, &objects))?;
+
+    let size = objects.keys().next_back().map_or(1, |id| id + 1);
+    let mut repaired = body.to_vec();
+    if !repaired.ends_with(b"\n") {
+        repaired.push(b'\n');
+    }
+    let xref_start = repaired.len();
+
+    // One subsection per run of consecutive object numbers, so gaps in
+    // the numbering cost nothing. Object 0 heads the free list.
+    let mut section = "xref\n0 1\n0000000000 65535 f\r\n".to_string();
+    let ids: Vec<u32> = objects.keys().copied().collect();
+    for run in ids.chunk_by(|a, b| a + 1 == *b) {
+        let _ = writeln!(section, "{} {}", run[0], run.len());
+        for id in run {
+            let object = &objects[id];
+            let _ = write!(
+                section,
+                "{:010} {:05} n\r\n",
+                object.offset, object.generation
+            );
+        }
+    }
+
+    let mut trailer = format!("/Size {} /Root {} {} R", size, root.0, root.1);
+    for key in ["Info", "Encrypt"] {
+        if let Some((id, generation)) =
+            last_reference(body, key).filter(|r| objects.contains_key(&r.0))
+        {
+            let _ = write!(trailer, " /{} {} {} R", key, id, generation);
+        }
+    }
+    // Encrypted files need the original file identifier to derive keys.
+    if let Some(id) = id_pattern().find_iter(body).last() {
+        trailer.push(' ');
+        trailer.push_str(&String::from_utf8_lossy(id.as_bytes()));
+    }
+
+    let _ = write!(
+        section,
+        "trailer\n<< {} >>\nstartxref\n{}\n%%EOF\n",
+        trailer, xref_start
+    );
+    repaired.extend_from_slice(section.as_bytes());
+    Some(repaired)
+}
+
+struct ScannedObject {
+    offset: usize,
+    generation: u16,
+}
+
+/// Every `N G obj` header in the file. Later definitions of the same
+/// object number win, as they do with incremental updates.
+fn scan_objects(body: &[u8]) -> BTreeMap<u32, ScannedObject> {
+    static OBJECT_HEADER: OnceLock<Regex> = OnceLock::new();
+    let pattern = OBJECT_HEADER.get_or_init(|| {
+        Regex::new(r"(?-u)(?:^|[\r\n])[ \t]*(\d{1,10})[ \t\r\n]+(\d{1,5})[ \t\r\n]+obj\b")
+            .expect("valid regex")
+    });
+
+    let mut objects = BTreeMap::new();
+    for captures in pattern.captures_iter(body) {
+        let (Some(id), Some(generation)) = (captures.get(1), captures.get(2)) else {
+            continue;
+        };
+        let number = parse_number(id.as_bytes()).filter(|n| (1..=MAX_OBJECT_NUMBER).contains(n));
+        if let (Some(number), Some(generation)) = (number, parse_number(generation.as_bytes())) {
+            objects.insert(
+                number,
+                ScannedObject {
+                    offset: id.start(),
+                    generation,
+                },
+            );
+        }
+    }
+    objects
+}
+
+/// The catalog object, for files whose trailer is gone entirely.
+fn find_catalog(body: &[u8], objects: &BTreeMap<u32, ScannedObject>) -> Option<(u32, u16)> {
+    static CATALOG: OnceLock<Regex> = OnceLock::new();
+    let pattern = CATALOG.get_or_init(|| Regex::new(r"/Type\s*/Catalog\b").expect("valid regex"));
+
+    objects.iter().rev().find_map(|(id, object)| {
+        let start = object.offset;
+        let end = find(&body[start..], b"endobj").map_or(body.len(), |i| start + i);
+        pattern
+            .is_match(&body[start..end])
+            .then_some((*id, object.generation))
+    })
+}
+
+/// The last `/Key N G R` in the file, which comes from the newest
+/// trailer or cross-reference stream.
+fn last_reference(body: &[u8], key: &str) -> Option<(u32, u16)> {
+    let pattern = Regex::new(&format!(
+        r"(?-u)/{}\s+(\d{{1,10}})\s+(\d{{1,5}})\s+R\b",
+        key
+    ))
+    .ok()?;
+    let captures = pattern.captures_iter(body).last()?;
+    let id = parse_number(captures.get(1)?.as_bytes())?;
+    let generation = parse_number(captures.get(2)?.as_bytes())?;
+    Some((id, generation))
+}
+
+fn id_pattern() -> &'static Regex {
+    static ID: OnceLock<Regex> = OnceLock::new();
+    ID.get_or_init(|| {
+        Regex::new(r"(?-u)/ID\s*\[\s*<[0-9A-Fa-f\s]*>\s*<[0-9A-Fa-f\s]*>\s*\]")
+            .expect("valid regex")
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(digits: &[u8]) -> Option<T> {
+    std::str::from_utf8(digits).ok()?.parse().ok()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROKEN: &str = "%PDF-1.4\n\
+1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+7 0 obj\n<< /Producer (test) >>\nendobj\n\
+trailer\n<< /Root 1 0 R /Info 7 0 R >>\n";
+
+    fn xref_and_trailer(repaired: &[u8]) -> String {
+        let text = String::from_utf8_lossy(repaired);
+        let start = text.find("\nxref\n").unwrap() + 1;
+        text[start..].to_string()
+    }
+
+    #[test]
+    fn writes_subsections_for_existing_objects_only() {
+        let repaired = rebuild_xref(BROKEN.as_bytes()).unwrap();
+        let xref = xref_and_trailer(&repaired);
+
+        assert!(xref.starts_with("xref\n0 1\n0000000000 65535 f\r\n1 2\n"));
+        assert!(xref.contains("\n7 1\n"));
+        assert!(xref.contains("/Size 8 /Root 1 0 R /Info 7 0 R"));
+        assert!(lopdf::Document::load_mem(&repaired).is_ok());
+    }
+
+    #[test]
+    fn offsets_point_at_object_headers() {
+        let repaired = rebuild_xref(BROKEN.as_bytes()).unwrap();
+        let xref = xref_and_trailer(&repaired);
+        let entry = xref.lines().find(|l| l.ends_with(" n")).unwrap();
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(repaired[offset..].starts_with(b"1 0 obj"));
+    }
+
+    #[test]
+    fn skips_object_numbers_beyond_the_pdf_limit() {
+        let input = format!("{}4000000000 0 obj\n<< >>\nendobj\n", BROKEN);
+        let repaired = rebuild_xref(input.as_bytes()).unwrap();
+        let xref = xref_and_trailer(&repaired);
+
+        assert!(!xref.contains("4000000000 1"));
+        assert!(xref.contains("/Size 8 "));
+        assert!(xref.len() < 512);
+    }
+
+    #[test]
+    fn needs_a_header_and_a_catalog() {
+        assert!(rebuild_xref(b"1 0 obj\n<< >>\nendobj\n").is_none());
+        assert!(rebuild_xref(b"%PDF-1.4\n1 0 obj\n<< /Type /Page >>\nendobj\n").is_none());
+    }
+}
@@ -0,0 +1,769 @@
+// src-tauri/src/pdf/pdf_tables.rs
+//! Table detection. Tables drawn with ruling lines are rebuilt from the
+//! grid the lines form; tables without lines are found as runs of lines
+//! whose text falls into the same columns. Either way the result is a grid
+//! of cells that renders as TipTap table HTML.
+use super::pdf_layout::{collect_page_content, Rule, TextRun};
+use super::pdf_loader::load_pdf;
+use super::pdf_page_range::PageSelection;
+use crate::errors::AppError;
+use crate::html::escape_text;
+use serde::Serialize;
+
+/// Rules closer than this, in points, are treated as touching or as the
+/// same grid line.
+const RULE_TOLERANCE: f32 = 2.0;
+/// Pages with more rules than this are charts or drawings, not tables.
+const MAX_RULES: usize = 4000;
+/// Lines of aligned text needed before they are taken for a table.
+const MIN_ALIGNED_ROWS: usize = 3;
+/// Gaps between words wider than this multiple of the font size separate
+/// cells.
+const CELL_GAP_RATIO: f32 = 1.0;
+/// Cells of tables without ruling lines are short; longer text is prose
+/// set in columns.
+const MAX_ALIGNED_CELL_CHARS: usize = 30;
+/// Pieces of text with more words than this read as prose.
+const MAX_CELL_WORDS: usize = 3;
+/// Rows of an aligned table are at most this many font sizes apart.
+const MAX_ROW_GAP_RATIO: f32 = 2.5;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfTable {
+    pub page_number: u32,
+    /// Position among the tables on the page, counting from the top.
+    pub index: usize,
+    /// Whether the table was found from its ruling lines rather than from
+    /// text alignment alone.
+    pub ruled: bool,
+    /// Whether the first row holds column headings.
+    pub header_row: bool,
+    pub column_count: usize,
+    pub rows: Vec<Vec<TableCell>>,
+    /// The table as TipTap table markup.
+    pub html: String,
+    /// Top of the table on the page, for placing it among the text.
+    #[serde(skip)]
+    pub top: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCell {
+    pub text: String,
+    pub colspan: usize,
+}
+
+/// Finds the tables on the selected pages, top to bottom on each page.
+pub fn extract_tables(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<Vec<PdfTable>, AppError> {
+    let doc = load_pdf(file_path, password)?.doc;
+    let mut tables = Vec::new();
+    for (page_number, page_id) in selection.select(doc.get_pages())? {
+        let Ok(content) = collect_page_content(&doc, page_id) else {
+            continue;
+        };
+        tables.extend(detect_tables(page_number, &content.runs, &content.rules));
+    }
+    Ok(tables)
+}
+
+/// The table at `index` on the given page.
+pub fn find_table(
+    file_path: &str,
+    password: Option<&str>,
+    page_number: u32,
+    index: usize,
+) -> Result<PdfTable, AppError> {
+    let selection = PageSelection::from_ranges([(page_number, page_number)]);
+    extract_tables(file_path, password, &selection)?
+        .into_iter()
+        .find(|table| table.index == index)
+        .ok_or_else(|| {
+            AppError::PdfError(format!(
+                "Page {} has no table number {}",
+                page_number,
+                index + 1
+            ))
+        })
+}
+
+pub fn detect_tables(page_number: u32, runs: &[TextRun], rules: &[Rule]) -> Vec<PdfTable> {
+    separate_tables(page_number, runs, rules).0
+}
+
+/// The tables on a page, and the text runs that fall outside all of them.
+pub fn separate_tables(
+    page_number: u32,
+    runs: &[TextRun],
+    rules: &[Rule],
+) -> (Vec<PdfTable>, Vec<TextRun>) {
+    let mut grids = if rules.len() <= MAX_RULES {
+        ruled_grids(runs, rules)
+    } else {
+        Vec::new()
+    };
+
+    let free_runs: Vec<&TextRun> = runs
+        .iter()
+        .filter(|run| !grids.iter().any(|grid| grid.contains(run)))
+        .collect();
+    grids.extend(aligned_grids(&free_runs));
+    grids.sort_by(|a, b| b.top.total_cmp(&a.top));
+
+    let outside = runs
+        .iter()
+        .filter(|run| !grids.iter().any(|grid| grid.contains(run)))
+        .cloned()
+        .collect();
+    let tables = grids
+        .into_iter()
+        .enumerate()
+        .map(|(index, grid)| {
+            let html = table_html(&grid.rows, grid.header_row);
+            PdfTable {
+                page_number,
+                index,
+                ruled: grid.ruled,
+                header_row: grid.header_row,
+                column_count: grid.column_count,
+                rows: grid.rows,
+                html,
+                top: grid.top,
+            }
+        })
+        .collect();
+    (tables, outside)
+}
+
+/// Renders rows in the shape TipTap's table extension parses: every cell
+/// holds a paragraph, and heading cells are `th`.
+pub fn table_html(rows: &[Vec<TableCell>], header_row: bool) -> String {
+    let mut html = String::from("<table><tbody>");
+    for (index, row) in rows.iter().enumerate() {
+        let tag = if header_row && index == 0 { "th" } else { "td" };
+        html.push_str("<tr>");
+        for cell in row {
+            html.push('<');
+            html.push_str(tag);
+            if cell.colspan > 1 {
+                html.push_str(&format!(" colspan=\"{}\"", cell.colspan));
+            }
+            html.push_str("><p>");
+            html.push_str(&escape_text(&cell.text));
+            html.push_str("</p></");
+            html.push_str(tag);
+            html.push('>');
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table>");
+    html
+}
+
+struct Grid {
+    top: f32,
+    bottom: f32,
+    left: f32,
+    right: f32,
+    ruled: bool,
+    header_row: bool,
+    column_count: usize,
+    rows: Vec<Vec<TableCell>>,
+}
+
+impl Grid {
+    fn contains(&self, run: &TextRun) -> bool {
+        let (x, y) = center(run);
+        x >= self.left && x <= self.right && y >= self.bottom && y <= self.top
+    }
+}
+
+/// Where a run sits, taking its vertical center as a bit above the
+/// baseline.
+fn center(run: &TextRun) -> (f32, f32) {
+    ((run.x + run.end_x) / 2.0, run.y + run.size * 0.3)
+}
+
+/// Tables built from groups of touching rules.
+fn ruled_grids(runs: &[TextRun], rules: &[Rule]) -> Vec<Grid> {
+    let mut groups = UnionFind::new(rules.len());
+    for i in 0..rules.len() {
+        for j in i + 1..rules.len() {
+            if touches(&rules[i], &rules[j]) {
+                groups.union(i, j);
+            }
+        }
+    }
+
+    let mut members: Vec<Vec<&Rule>> = vec![Vec::new(); rules.len()];
+    for (i, rule) in rules.iter().enumerate() {
+        members[groups.find(i)].push(rule);
+    }
+
+    members
+        .into_iter()
+        .filter_map(|group| ruled_grid(runs, &group))
+        .collect()
+}
+
+fn touches(a: &Rule, b: &Rule) -> bool {
+    let overlaps =
+        |a: &Rule, b: &Rule| a.start <= b.end + RULE_TOLERANCE && b.start <= a.end + RULE_TOLERANCE;
+    if a.horizontal == b.horizontal {
+        (a.position - b.position).abs() <= RULE_TOLERANCE && overlaps(a, b)
+    } else {
+        a.position >= b.start - RULE_TOLERANCE
+            && a.position <= b.end + RULE_TOLERANCE
+            && b.position >= a.start - RULE_TOLERANCE
+            && b.position <= a.end + RULE_TOLERANCE
+    }
+}
+
+fn ruled_grid(runs: &[TextRun], rules: &[&Rule]) -> Option<Grid> {
+    let (horizontal, vertical): (Vec<&Rule>, Vec<&Rule>) =
+        rules.iter().copied().partition(|rule| rule.horizontal);
+
+    let left = rules
+        .iter()
+        .map(|r| if r.horizontal { r.start } else { r.position })
+        .fold(f32::INFINITY, f32::min);
+    let right = rules
+        .iter()
+        .map(|r| if r.horizontal { r.end } else { r.position })
+        .fold(f32::NEG_INFINITY, f32::max);
+    let bottom = rules
+        .iter()
+        .map(|r| if r.horizontal { r.position } else { r.start })
+        .fold(f32::INFINITY, f32::min);
+    let top = rules
+        .iter()
+        .map(|r| if r.horizontal { r.position } else { r.end })
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    let inside: Vec<&TextRun> = runs
+        .iter()
+        .filter(|run| {
+            let (x, y) = center(run);
+            x > left && x < right && y > bottom && y < top
+        })
+        .collect();
+    if inside.is_empty() {
+        return None;
+    }
+
+    let mut row_lines = cluster(horizontal.iter().map(|r| r.position));
+    row_lines.reverse();
+    let column_lines = cluster(vertical.iter().map(|r| r.position));
+
+    // With both kinds of rules every cell is boxed and may hold several
+    // lines of text. Otherwise the rules only frame the table or separate
+    // its header, and each line of text is a row.
+    let boxed = row_lines.len() >= 2 && column_lines.len() >= 3;
+    // A frame around prose is not a table.
+    if !boxed && !short_cells(&baselines(inside.clone())) {
+        return None;
+    }
+    let columns = if column_lines.len() >= 3 {
+        column_lines
+    } else {
+        text_columns(&inside, left, right)?
+    };
+
+    let bands: Vec<(f32, f32)> = if row_lines.len() >= 2 {
+        row_lines
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    } else {
+        vec![(top, bottom)]
+    };
+
+    let mut rows = Vec::new();
+    let mut first_band_rows = 0;
+    for (band_index, (band_top, band_bottom)) in bands.iter().enumerate() {
+        let band_runs: Vec<&TextRun> = inside
+            .iter()
+            .copied()
+            .filter(|run| {
+                let (_, y) = center(run);
+                y < *band_top && y > *band_bottom
+            })
+            .collect();
+        if band_runs.is_empty() {
+            continue;
+        }
+
+        let band_rows: Vec<Vec<&TextRun>> = if boxed {
+            vec![band_runs]
+        } else {
+            baselines(band_runs)
+        };
+        if band_index == 0 {
+            first_band_rows = band_rows.len();
+        }
+        for row_runs in band_rows {
+            let middle = (band_top + band_bottom) / 2.0;
+            let open_boundaries: Vec<bool> = columns[1..columns.len() - 1]
+                .iter()
+                .map(|x| {
+                    boxed
+                        && !vertical.iter().any(|rule| {
+                            (rule.position - x).abs() <= RULE_TOLERANCE
+                                && rule.start - RULE_TOLERANCE <= middle
+                                && rule.end + RULE_TOLERANCE >= middle
+                        })
+                })
+                .collect();
+            rows.push(build_row(&row_runs, &columns, &open_boundaries));
+        }
+    }
+
+    let column_count = columns.len() - 1;
+    let filled = rows
+        .iter()
+        .flatten()
+        .filter(|cell| !cell.text.is_empty())
+        .count();
+    if rows.len() < 2 || column_count < 2 || filled < 2 {
+        return None;
+    }
+
+    let header_row =
+        first_row_bold(&inside, &rows) || (!boxed && first_band_rows == 1 && bands.len() > 1);
+    Some(Grid {
+        top,
+        bottom,
+        left,
+        right,
+        ruled: true,
+        header_row,
+        column_count,
+        rows,
+    })
+}
+
+/// Tables found from text alone: consecutive lines split into short cells
+/// that line up in the same columns.
+fn aligned_grids(runs: &[&TextRun]) -> Vec<Grid> {
+    let lines = baselines(runs.to_vec());
+    let mut grids = Vec::new();
+    let mut block: Vec<Vec<&TextRun>> = Vec::new();
+
+    let flush = |block: &mut Vec<Vec<&TextRun>>, grids: &mut Vec<Grid>| {
+        if block.len() >= MIN_ALIGNED_ROWS {
+            grids.extend(aligned_grid(block));
+        }
+        block.clear();
+    };
+
+    for line in lines {
+        let chunks = cell_chunks(&line);
+        let row_like = chunks.len() >= 2;
+        let close_enough = block.last().is_none_or(|previous| {
+            let size = previous[0].size.max(line[0].size);
+            previous[0].y - line[0].y <= size * MAX_ROW_GAP_RATIO
+        });
+        if !row_like || !close_enough {
+            flush(&mut block, &mut grids);
+        }
+        if row_like {
+            block.push(line);
+        }
+    }
+    flush(&mut block, &mut grids);
+    grids
+}
+
+fn aligned_grid(lines: &[Vec<&TextRun>]) -> Option<Grid> {
+    if !short_cells(lines) {
+        return None;
+    }
+
+    let all: Vec<&TextRun> = lines.iter().flatten().copied().collect();
+    let left = all.iter().map(|r| r.x).fold(f32::INFINITY, f32::min);
+    let right = all
+        .iter()
+        .map(|r| r.end_x)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let columns = text_columns(&all, left - 1.0, right + 1.0)?;
+    let open_boundaries = vec![false; columns.len().saturating_sub(2)];
+
+    let rows: Vec<Vec<TableCell>> = lines
+        .iter()
+        .map(|line| build_row(line, &columns, &open_boundaries))
+        .collect();
+    // Every row must use at least two columns, or the lines are not
+    // really aligned.
+    let multi_cell_rows = rows
+        .iter()
+        .filter(|row| row.iter().filter(|c| !c.text.is_empty()).count() >= 2)
+        .count();
+    if multi_cell_rows < MIN_ALIGNED_ROWS {
+        return None;
+    }
+
+    let top = all
+        .iter()
+        .map(|r| r.y + r.size)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let bottom = all
+        .iter()
+        .map(|r| r.y - r.size * 0.3)
+        .fold(f32::INFINITY, f32::min);
+    let header_row = first_row_bold(&all, &rows);
+    Some(Grid {
+        top,
+        bottom,
+        left,
+        right,
+        ruled: false,
+        header_row,
+        column_count: columns.len() - 1,
+        rows,
+    })
+}
+
+/// Whether the pieces of text on the lines look like table cells: short,
+/// and mostly a few words rather than running prose.
+fn short_cells(lines: &[Vec<&TextRun>]) -> bool {
+    let chunks: Vec<String> = lines
+        .iter()
+        .flat_map(|line| cell_chunks(line))
+        .map(|(_, _, text)| text)
+        .collect();
+    let mut chars: Vec<usize> = chunks.iter().map(|text| text.chars().count()).collect();
+    chars.sort_unstable();
+    let wordy = chunks
+        .iter()
+        .filter(|text| text.split_whitespace().count() > MAX_CELL_WORDS)
+        .count();
+    chars
+        .get(chars.len() / 2)
+        .is_some_and(|median| *median <= MAX_ALIGNED_CELL_CHARS)
+        && wordy * 2 <= chunks.len()
+}
+
+/// Groups runs sharing a baseline, top to bottom, each sorted left to
+/// right.
+fn baselines(mut runs: Vec<&TextRun>) -> Vec<Vec<&TextRun>> {
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut lines: Vec<Vec<&TextRun>> = Vec::new();
+    for run in runs {
+        match lines.last_mut() {
+            Some(line) if (line[0].y - run.y).abs() <= 0.5 * line[0].size.max(run.size) => {
+                line.push(run)
+            }
+            _ => lines.push(vec![run]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+    lines
+}
+
+/// Splits a line at gaps wide enough to separate cells, giving each
+/// piece's extent and text.
+fn cell_chunks(line: &[&TextRun]) -> Vec<(f32, f32, String)> {
+    let mut chunks: Vec<(f32, f32, String)> = Vec::new();
+    for run in line {
+        match chunks.last_mut() {
+            Some((_, end, text)) if run.x - *end < run.size * CELL_GAP_RATIO => {
+                if !text.ends_with(' ') && !run.text.starts_with(' ') {
+                    text.push(' ');
+                }
+                text.push_str(&run.text);
+                *end = end.max(run.end_x);
+            }
+            _ => chunks.push((run.x, run.end_x, run.text.clone())),
+        }
+    }
+    chunks
+        .into_iter()
+        .map(|(start, end, text)| (start, end, normalize(&text)))
+        .filter(|(_, _, text)| !text.is_empty())
+        .collect()
+}
+
+/// Column boundaries, left to right, from the gaps no text crosses. A
+/// few crossings are allowed so a heading spanning columns does not hide
+/// the gap under it.
+fn text_columns(runs: &[&TextRun], left: f32, right: f32) -> Option<Vec<f32>> {
+    let width = right - left;
+    if !width.is_finite() || width < 1.0 {
+        return None;
+    }
+
+    let lines = baselines(runs.to_vec());
+    let bins = (width.ceil() as usize).clamp(1, 5000);
+    let scale = bins as f32 / width;
+    let mut coverage = vec![0usize; bins];
+    let mut min_gap = f32::INFINITY;
+    for line in &lines {
+        let mut covered = vec![false; bins];
+        for (start, end, _) in cell_chunks(line) {
+            let first = (((start - left) * scale).max(0.0) as usize).min(bins - 1);
+            let last = (((end - left) * scale).ceil() as usize).min(bins);
+            covered[first..last.max(first + 1)].fill(true);
+        }
+        for (count, covered) in coverage.iter_mut().zip(covered) {
+            *count += usize::from(covered);
+        }
+        min_gap = min_gap.min(line[0].size * CELL_GAP_RATIO * 0.8);
+    }
+    let allowed = lines.len() / 5;
+    let min_gap = min_gap.max(2.0) * scale;
+
+    let mut boundaries = vec![left];
+    let mut bin = 0;
+    // Skip the margin before the first text.
+    while bin < bins && coverage[bin] <= allowed {
+        bin += 1;
+    }
+    while bin < bins {
+        if coverage[bin] > allowed {
+            bin += 1;
+            continue;
+        }
+        let gap_start = bin;
+        while bin < bins && coverage[bin] <= allowed {
+            bin += 1;
+        }
+        if bin < bins && (bin - gap_start) as f32 >= min_gap {
+            boundaries.push(left + (gap_start + bin) as f32 / 2.0 / scale);
+        }
+    }
+    boundaries.push(right);
+
+    (boundaries.len() >= 3).then_some(boundaries)
+}
+
+/// Places each run in the column holding its start and spans cells
+/// across any boundaries it crosses or that are open in this row.
+fn build_row(runs: &[&TextRun], columns: &[f32], open_boundaries: &[bool]) -> Vec<TableCell> {
+    let column_count = columns.len() - 1;
+    let column_of = |x: f32| {
+        columns[1..column_count]
+            .iter()
+            .filter(|boundary| **boundary < x)
+            .count()
+    };
+
+    // Cells a run crosses into are merged with the cell it starts in.
+    let mut merged = open_boundaries.to_vec();
+    let mut texts: Vec<Vec<&TextRun>> = vec![Vec::new(); column_count];
+    for run in runs {
+        let first = column_of(run.x + run.size * 0.1);
+        let last = column_of(run.end_x - run.size * 0.1).max(first);
+        merged[first..last].fill(true);
+        texts[first].push(run);
+    }
+
+    let mut cells: Vec<TableCell> = Vec::new();
+    let mut pending: Vec<&TextRun> = Vec::new();
+    let mut span = 0;
+    for (column, column_runs) in texts.into_iter().enumerate() {
+        pending.extend(column_runs);
+        span += 1;
+        if column < column_count - 1 && merged[column] {
+            continue;
+        }
+        cells.push(TableCell {
+            text: cell_text(&pending),
+            colspan: span,
+        });
+        pending.clear();
+        span = 0;
+    }
+    cells
+}
+
+/// Text of a cell's runs in reading order.
+fn cell_text(runs: &[&TextRun]) -> String {
+    let text = baselines(runs.to_vec())
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|run| run.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    normalize(&text)
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether the first row's text is set in bold, as column headings
+/// usually are.
+fn first_row_bold(runs: &[&TextRun], rows: &[Vec<TableCell>]) -> bool {
+    let Some(first) = rows.first() else {
+        return false;
+    };
+    let lines = baselines(runs.to_vec());
+    let Some(line) = lines.first() else {
+        return false;
+    };
+    first.iter().any(|cell| !cell.text.is_empty()) && line.iter().all(|run| run.bold)
+}
+
+/// Sorted positions with near-duplicates merged.
+fn cluster(positions: impl Iterator<Item = f32>) -> Vec<f32> {
+    let mut positions: Vec<f32> = positions.collect();
+    positions.sort_by(f32::total_cmp);
+    let mut clustered: Vec<f32> = Vec::new();
+    for position in positions {
+        match clustered.last() {
+            Some(last) if position - last <= RULE_TOLERANCE => {}
+            _ => clustered.push(position),
+        }
+    }
+    clustered
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[b] = a;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(x: f32, y: f32, text: &str) -> TextRun {
+        let char_x: Vec<f32> = (0..text.chars().count())
+            .map(|i| x + i as f32 * 5.0)
+            .collect();
+        TextRun {
+            x,
+            y,
+            end_x: x + char_x.len() as f32 * 5.0,
+            size: 10.0,
+            text: text.to_string(),
+            bold: false,
+            char_x,
+        }
+    }
+
+    fn rule(horizontal: bool, position: f32, start: f32, end: f32) -> Rule {
+        Rule {
+            horizontal,
+            position,
+            start,
+            end,
+        }
+    }
+
+    fn texts(table: &PdfTable) -> Vec<Vec<&str>> {
+        table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn boxed_cells_become_a_ruled_table() {
+        let rules = [
+            rule(true, 100.0, 50.0, 250.0),
+            rule(true, 80.0, 50.0, 250.0),
+            rule(true, 60.0, 50.0, 250.0),
+            rule(false, 50.0, 60.0, 100.0),
+            rule(false, 150.0, 60.0, 100.0),
+            rule(false, 250.0, 60.0, 100.0),
+        ];
+        let runs = [
+            run(60.0, 86.0, "Name"),
+            run(160.0, 86.0, "Age"),
+            run(60.0, 66.0, "Ana"),
+            run(160.0, 66.0, "41"),
+            run(60.0, 20.0, "Text below the table."),
+        ];
+        let (tables, outside) = separate_tables(2, &runs, &rules);
+        assert_eq!(tables.len(), 1);
+        assert!(tables[0].ruled);
+        assert_eq!(tables[0].column_count, 2);
+        assert_eq!(texts(&tables[0]), [["Name", "Age"], ["Ana", "41"]]);
+        assert_eq!(outside.len(), 1);
+    }
+
+    #[test]
+    fn aligned_columns_without_rules_become_a_table() {
+        let runs: Vec<TextRun> = [("Item", "Price"), ("Tea", "3.50"), ("Cake", "4.00")]
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (item, price))| {
+                let y = 300.0 - i as f32 * 14.0;
+                [run(50.0, y, item), run(200.0, y, price)]
+            })
+            .collect();
+        let tables = detect_tables(1, &runs, &[]);
+        assert_eq!(tables.len(), 1);
+        assert!(!tables[0].ruled);
+        assert_eq!(
+            texts(&tables[0]),
+            [["Item", "Price"], ["Tea", "3.50"], ["Cake", "4.00"]]
+        );
+    }
+
+    #[test]
+    fn prose_is_not_a_table() {
+        let runs: Vec<TextRun> = (0..4)
+            .map(|i| {
+                run(
+                    50.0,
+                    300.0 - i as f32 * 14.0,
+                    "An ordinary line of running text in a paragraph",
+                )
+            })
+            .collect();
+        assert!(detect_tables(1, &runs, &[]).is_empty());
+    }
+
+    #[test]
+    fn html_escapes_cells_and_marks_headers() {
+        let cell = |text: &str, colspan| TableCell {
+            text: text.to_string(),
+            colspan,
+        };
+        let html = table_html(
+            &[vec![cell("A & B", 2)], vec![cell("1", 1), cell("2", 1)]],
+            true,
+        );
+        assert_eq!(
+            html,
+            "<table><tbody><tr><th colspan=\"2\"><p>A &amp; B</p></th></tr>\
+             <tr><td><p>1</p></td><td><p>2</p></td></tr></tbody></table>"
+        );
+    }
+}
@@ -1,30 +1,443 @@
 // src-tauri/src/pdf/pdf_validation.rs
+use super::pdf_loader::{is_intact, unlock};
+use super::pdf_page_range::PageSelection;
+use super::pdf_repair::{has_eof_marker, header_offset, rebuild_xref, HEADER_SEARCH_BYTES};
 use crate::errors::AppError;
+use lopdf::content::Content;
+use lopdf::xref::XrefEntry;
+use lopdf::{Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::Read;
 use std::path::Path;
 
+/// Missing object references listed in a health report; the rest are
+/// only counted.
+const MAX_LISTED_MISSING_OBJECTS: usize = 50;
+
+/// Checks that the file exists and starts like a PDF, whatever its name.
 pub fn validate_pdf_file(file_path: &str) -> Result<(), AppError> {
     let path = Path::new(file_path);
-    if !path.exists() {
+    if !path.is_file() {
         return Err(AppError::PdfError("File does not exist".to_string()));
     }
 
-    if !file_path.to_lowercase().ends_with(".pdf") {
-        return Err(AppError::PdfError("File is not a PDF".to_string()));
+    let mut head = Vec::with_capacity(HEADER_SEARCH_BYTES);
+    std::fs::File::open(path)?
+        .take(HEADER_SEARCH_BYTES as u64)
+        .read_to_end(&mut head)?;
+    if header_offset(&head).is_none() {
+        return Err(AppError::PdfError(
+            "File is not a PDF (no %PDF- header)".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_file_size(file_path: &str, max_size_bytes: u64) -> Result<(), AppError> {
+    let size = std::fs::metadata(file_path)?.len();
+
+    if size > max_size_bytes {
+        return Err(AppError::PdfError(format!(
+            "File size {} exceeds the limit of {}",
+            format_file_size(size),
+            format_file_size(max_size_bytes)
+        )));
     }
 
     Ok(())
 }
 
-pub fn validate_file_size(file_path: &str, max_size_mb: u64) -> Result<(), AppError> {
-    let metadata = std::fs::metadata(file_path)?;
-    let size_mb = metadata.len() / (1024 * 1024);
+/// Rejects files larger than any PDF command may open. The whole file is
+/// parsed whatever pages are needed, so this is what bounds memory: opening
+/// holds the file, the parsed objects and, for damaged files, a repaired
+/// copy, which together come to a few times the file size.
+pub fn validate_open_size(file_path: &str, max_open_bytes: u64) -> Result<(), AppError> {
+    let size = std::fs::metadata(file_path)?.len();
 
-    if size_mb > max_size_mb {
+    if size > max_open_bytes {
         return Err(AppError::PdfError(format!(
-            "File size {}MB exceeds limit of {}MB",
-            size_mb, max_size_mb
+            "File size {} exceeds the limit of {} for opening PDFs",
+            format_file_size(size),
+            format_file_size(max_open_bytes)
         )));
     }
 
     Ok(())
 }
+
+pub fn format_file_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} bytes", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfHealthReport {
+    pub file_size: u64,
+    /// Version from the `%PDF-x.y` header.
+    pub header_version: Option<String>,
+    /// Bytes of junk before the header; readers tolerate a little.
+    pub header_offset: Option<usize>,
+    pub has_eof_marker: bool,
+    /// Whether the file parses as written, with every cross-reference
+    /// entry pointing at an object.
+    pub xref_intact: bool,
+    /// Whether rebuilding the cross-reference table made the file usable.
+    /// Only attempted when the table is broken.
+    pub repaired: bool,
+    pub encrypted: bool,
+    pub page_count: usize,
+    /// References to objects the file does not contain, as "12 0 R".
+    pub missing_objects: Vec<String>,
+    pub missing_object_count: usize,
+    pub unreadable_pages: Vec<PageProblem>,
+    /// Every problem found, in plain language.
+    pub issues: Vec<String>,
+    pub healthy: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PageProblem {
+    pub page_number: u32,
+    pub reason: String,
+}
+
+/// Inspects the file's structure without giving up on damaged files:
+/// the header and EOF marker, the cross-reference table (repairing it if
+/// needed), dangling object references and the content streams of the
+/// selected pages.
+pub fn check_pdf_health(
+    file_path: &str,
+    password: Option<&str>,
+    selection: &PageSelection,
+) -> Result<PdfHealthReport, AppError> {
+    let bytes = std::fs::read(file_path)?;
+    let mut report = PdfHealthReport {
+        file_size: bytes.len() as u64,
+        header_offset: header_offset(&bytes),
+        has_eof_marker: has_eof_marker(&bytes),
+        ..Default::default()
+    };
+
+    let Some(offset) = report.header_offset else {
+        report
+            .issues
+            .push("The file has no %PDF- header and is not a PDF".to_string());
+        return Ok(report);
+    };
+    report.header_version = header_version(&bytes[offset..]);
+    if offset > 0 {
+        report
+            .issues
+            .push(format!("{} bytes of junk precede the %PDF- header", offset));
+    }
+    if !report.has_eof_marker {
+        report
+            .issues
+            .push("The %%EOF marker is missing; the file may be truncated".to_string());
+    }
+
+    let original = Document::load_mem(&bytes);
+    let broken_entries = original.as_ref().map_or(0, unreadable_xref_entries);
+    report.xref_intact = original.as_ref().is_ok_and(is_intact) && broken_entries == 0;
+
+    let mut doc = if report.xref_intact {
+        original.ok()
+    } else {
+        // The unrepaired document is dropped here and parsed again only if
+        // repair fails, as in `load_lenient`, to keep one copy in memory.
+        let parsed = original.is_ok();
+        match original {
+            Ok(_) if broken_entries > 0 => report.issues.push(format!(
+                "{} cross-reference entries point at no readable object",
+                broken_entries
+            )),
+            Ok(_) => report
+                .issues
+                .push("The document catalog or page tree cannot be found".to_string()),
+            Err(e) => report
+                .issues
+                .push(format!("The cross-reference table is broken: {}", e)),
+        }
+
+        let repaired = rebuild_xref(&bytes)
+            .and_then(|rebuilt| Document::load_mem(&rebuilt).ok())
+            .filter(is_intact);
+        report.repaired = repaired.is_some();
+        if report.repaired {
+            report
+                .issues
+                .push("The cross-reference table was rebuilt by scanning the file".to_string());
+        }
+        match repaired {
+            Some(doc) => Some(doc),
+            None if parsed => Document::load_mem(&bytes).ok(),
+            None => None,
+        }
+    };
+
+    let Some(doc) = doc.as_mut() else {
+        report
+            .issues
+            .push("The file could not be parsed or repaired".to_string());
+        return Ok(report);
+    };
+
+    report.page_count = doc.get_pages().len();
+    if report.page_count == 0 {
+        report.issues.push("The document has no pages".to_string());
+    }
+
+    let missing = missing_objects(doc);
+    report.missing_object_count = missing.len();
+    if !missing.is_empty() {
+        report
+            .issues
+            .push(format!("{} referenced objects are missing", missing.len()));
+    }
+    report.missing_objects = missing
+        .into_iter()
+        .take(MAX_LISTED_MISSING_OBJECTS)
+        .map(|(id, generation)| format!("{} {} R", id, generation))
+        .collect();
+
+    report.encrypted = doc.is_encrypted() || doc.encryption_state.is_some();
+    match unlock(doc, password) {
+        Ok(()) => {
+            report.unreadable_pages = unreadable_pages(doc, selection);
+            if !report.unreadable_pages.is_empty() {
+                report.issues.push(format!(
+                    "{} pages cannot be read",
+                    report.unreadable_pages.len()
+                ));
+            }
+        }
+        Err(AppError::PdfPasswordRequired(_)) => report
+            .issues
+            .push("Page contents were not checked because the PDF needs a password".to_string()),
+        Err(e) => report.issues.push(e.to_string()),
+    }
+
+    report.healthy = report.xref_intact
+        && report.has_eof_marker
+        && report.missing_object_count == 0
+        && report.unreadable_pages.is_empty()
+        && report.page_count > 0;
+    Ok(report)
+}
+
+fn header_version(bytes: &[u8]) -> Option<String> {
+    let version: String = bytes
+        .get(5..)?
+        .iter()
+        .take_while(|b| b.is_ascii_digit() || **b == b'.')
+        .map(|b| *b as char)
+        .collect();
+    (!version.is_empty()).then_some(version)
+}
+
+/// In-use cross-reference entries whose object lopdf could not read at
+/// the recorded offset.
+fn unreadable_xref_entries(doc: &Document) -> usize {
+    doc.reference_table
+        .entries
+        .iter()
+        .filter(|(id, entry)| match entry {
+            XrefEntry::Normal { generation, .. } => !doc.objects.contains_key(&(**id, *generation)),
+            _ => false,
+        })
+        .count()
+}
+
+/// Objects that are referenced somewhere but absent from the file.
+fn missing_objects(doc: &Document) -> BTreeSet<ObjectId> {
+    fn collect(object: &Object, doc: &Document, missing: &mut BTreeSet<ObjectId>) {
+        match object {
+            Object::Reference(id) if !doc.objects.contains_key(id) => {
+                missing.insert(*id);
+            }
+            Object::Array(items) => items.iter().for_each(|o| collect(o, doc, missing)),
+            Object::Dictionary(dict) => dict.iter().for_each(|(_, o)| collect(o, doc, missing)),
+            Object::Stream(stream) => stream
+                .dict
+                .iter()
+                .for_each(|(_, o)| collect(o, doc, missing)),
+            _ => {}
+        }
+    }
+
+    let mut missing = BTreeSet::new();
+    for object in doc.objects.values() {
+        collect(object, doc, &mut missing);
+    }
+    collect(&Object::Dictionary(doc.trailer.clone()), doc, &mut missing);
+    missing
+}
+
+/// Pages whose content streams are missing, fail to decompress or do
+/// not parse.
+fn unreadable_pages(doc: &Document, selection: &PageSelection) -> Vec<PageProblem> {
+    doc.get_pages()
+        .into_iter()
+        .filter(|(page_number, _)| selection.contains(*page_number))
+        .filter_map(|(page_number, page_id)| {
+            page_problem(doc, page_id).map(|reason| PageProblem {
+                page_number,
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn page_problem(doc: &Document, page_id: ObjectId) -> Option<String> {
+    if doc.get_dictionary(page_id).is_err() {
+        return Some("The page object is missing".to_string());
+    }
+
+    let mut content = Vec::new();
+    for stream_id in doc.get_page_contents(page_id) {
+        let Ok(stream) = doc.get_object(stream_id).and_then(Object::as_stream) else {
+            return Some(format!(
+                "Content stream {} {} R is missing",
+                stream_id.0, stream_id.1
+            ));
+        };
+        let data = if stream.dict.has(b"Filter") {
+            match stream.decompressed_content() {
+                Ok(data) => data,
+                Err(e) => return Some(format!("Content stream cannot be decompressed: {}", e)),
+            }
+        } else {
+            stream.content.clone()
+        };
+        content.extend_from_slice(&data);
+        content.push(b'\n');
+    }
+
+    Content::decode(&content)
+        .err()
+        .map(|e| format!("Page content cannot be parsed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+    use std::path::PathBuf;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("validation-{}-{}", std::process::id(), name))
+    }
+
+    /// A sparse file of `size` bytes, so large sizes cost no disk space.
+    fn file_of_size(name: &str, size: u64) -> PathBuf {
+        let path = temp_path(name);
+        std::fs::File::create(&path).unwrap().set_len(size).unwrap();
+        path
+    }
+
+    fn one_page_pdf() -> Vec<u8> {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page = doc.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => 1,
+                "Kids" => vec![page.into()],
+            }),
+        );
+        let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog);
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn file_size_limit_is_inclusive() {
+        let at_limit = file_of_size("at-limit", 10 * MB);
+        let over_limit = file_of_size("over-limit", 109 * MB / 10);
+
+        let at_result = validate_file_size(at_limit.to_str().unwrap(), 10 * MB);
+        let over_result = validate_file_size(over_limit.to_str().unwrap(), 10 * MB);
+        let open_result = validate_open_size(over_limit.to_str().unwrap(), 10 * MB);
+        std::fs::remove_file(&at_limit).unwrap();
+        std::fs::remove_file(&over_limit).unwrap();
+
+        assert!(at_result.is_ok());
+        let message = over_result.unwrap_err().to_string();
+        assert!(
+            message.contains("10.9 MB") && message.contains("10.0 MB"),
+            "{}",
+            message
+        );
+        assert!(open_result.is_err());
+    }
+
+    #[test]
+    fn pdf_header_is_sniffed_from_the_start_of_the_file() {
+        let cases: [(&str, Vec<u8>, bool); 4] = [
+            ("plain", b"%PDF-1.7\n".to_vec(), true),
+            (
+                "junk-prefix",
+                [vec![b' '; 100], b"%PDF-1.4".to_vec()].concat(),
+                true,
+            ),
+            (
+                "late-header",
+                [vec![b' '; HEADER_SEARCH_BYTES], b"%PDF-1.4".to_vec()].concat(),
+                false,
+            ),
+            ("not-pdf", b"PK\x03\x04 a zip archive".to_vec(), false),
+        ];
+
+        for (name, bytes, accepted) in cases {
+            let path = temp_path(name);
+            std::fs::write(&path, bytes).unwrap();
+            let result = validate_pdf_file(path.to_str().unwrap());
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(result.is_ok(), accepted, "{}", name);
+        }
+
+        assert!(validate_pdf_file(temp_path("missing").to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn health_report_finds_the_header_and_eof_marker() {
+        let bytes = one_page_pdf();
+        let complete = temp_path("complete.pdf");
+        let truncated = temp_path("truncated.pdf");
+        std::fs::write(&complete, &bytes).unwrap();
+        let cut = bytes.windows(5).rposition(|w| w == b"%%EOF").unwrap();
+        std::fs::write(&truncated, &bytes[..cut]).unwrap();
+
+        let complete_report =
+            check_pdf_health(complete.to_str().unwrap(), None, &PageSelection::all());
+        let truncated_report =
+            check_pdf_health(truncated.to_str().unwrap(), None, &PageSelection::all());
+        std::fs::remove_file(&complete).unwrap();
+        std::fs::remove_file(&truncated).unwrap();
+
+        let report = complete_report.unwrap();
+        assert_eq!(report.header_offset, Some(0));
+        assert_eq!(report.header_version.as_deref(), Some("1.7"));
+        assert!(report.has_eof_marker);
+        assert!(report.healthy, "{:?}", report.issues);
+
+        let report = truncated_report.unwrap();
+        assert!(!report.has_eof_marker);
+        assert!(!report.healthy);
+    }
+}
@@ -1,24 +1,139 @@
 // src-tauri/src/pdf_processor.rs
 use crate::errors::AppError;
 use crate::pdf::{
-    pdf_extractor,
+    pdf_annotations::{self, PdfAnnotation},
+    pdf_extractor::{self, ExtractedDocument, ExtractedPage, ExtractionSummary},
+    pdf_html::{self, PdfHtmlDocument},
+    pdf_images::{self, PdfImage},
     pdf_info::{self, PdfInfo},
-    pdf_validation,
+    pdf_outline::{self, PdfOutline},
+    pdf_page_range::PageSelection,
+    pdf_tables::{self, PdfTable},
+    pdf_validation::{self, PdfHealthReport},
 };
+use std::path::Path;
 
 pub struct PdfProcessor;
 
 impl PdfProcessor {
-    pub fn extract_text(file_path: &str) -> Result<String, AppError> {
+    pub fn extract_text(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<String, AppError> {
         pdf_validation::validate_pdf_file(file_path)?;
-        pdf_extractor::extract_text(file_path)
+        pdf_extractor::extract_text(file_path, password, pages)
     }
 
-    pub fn validate_file_size(file_path: &str, max_size_mb: u64) -> Result<(), AppError> {
-        pdf_validation::validate_file_size(file_path, max_size_mb)
+    pub fn extract_document(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<ExtractedDocument, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_extractor::extract_document(file_path, password, pages)
+    }
+
+    pub fn extract_pages(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<Vec<(u32, String)>, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_extractor::extract_pages(file_path, password, pages)
+    }
+
+    pub fn for_each_page(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+        on_page: impl FnMut(ExtractedPage, usize, usize),
+    ) -> Result<ExtractionSummary, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_extractor::for_each_page(file_path, password, pages, on_page)
+    }
+
+    pub fn validate_file_size(file_path: &str, max_size_bytes: u64) -> Result<(), AppError> {
+        pdf_validation::validate_file_size(file_path, max_size_bytes)
+    }
+
+    pub fn validate_open_size(file_path: &str, max_open_bytes: u64) -> Result<(), AppError> {
+        pdf_validation::validate_open_size(file_path, max_open_bytes)
+    }
+
+    pub fn get_pdf_info(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<PdfInfo, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_info::get_pdf_info(file_path, password, pages)
+    }
+
+    pub fn check_health(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<PdfHealthReport, AppError> {
+        if !std::path::Path::new(file_path).is_file() {
+            return Err(AppError::PdfError("File does not exist".to_string()));
+        }
+        pdf_validation::check_pdf_health(file_path, password, pages)
     }
 
-    pub fn get_pdf_info(file_path: &str) -> Result<PdfInfo, AppError> {
-        pdf_info::get_pdf_info(file_path)
+    pub fn get_outline(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<PdfOutline, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_outline::get_outline(file_path, password, pages)
+    }
+
+    pub fn extract_tables(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<Vec<PdfTable>, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_tables::extract_tables(file_path, password, pages)
+    }
+
+    pub fn find_table(
+        file_path: &str,
+        password: Option<&str>,
+        page_number: u32,
+        index: usize,
+    ) -> Result<PdfTable, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_tables::find_table(file_path, password, page_number, index)
+    }
+
+    pub fn extract_images(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+        output_dir: &Path,
+    ) -> Result<Vec<PdfImage>, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_images::extract_images(file_path, password, pages, output_dir)
+    }
+
+    pub fn read_annotations(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<Vec<PdfAnnotation>, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_annotations::read_annotations(file_path, password, pages)
+    }
+
+    pub fn convert_to_html(
+        file_path: &str,
+        password: Option<&str>,
+        pages: &PageSelection,
+    ) -> Result<PdfHtmlDocument, AppError> {
+        pdf_validation::validate_pdf_file(file_path)?;
+        pdf_html::convert_to_html(file_path, password, pages)
     }
 }
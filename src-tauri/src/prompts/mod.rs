@@ -0,0 +1,25 @@
+// src-tauri/src/prompts/mod.rs
+pub mod store;
+pub mod template;
+
+use crate::app_dirs::app_config_file;
+use crate::errors::AppError;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const PROMPT_LIBRARY_FILE: &str = "prompt_templates.json";
+
+pub fn library_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app_config_file(app, PROMPT_LIBRARY_FILE)
+}
+
+/// Loads the library and renders the named template with `values`.
+pub fn render_template(
+    app: &AppHandle,
+    name: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, AppError> {
+    let library = store::PromptLibrary::load(&library_path(app)?)?;
+    library.get(name)?.render(values)
+}
@@ -0,0 +1,229 @@
+// src-tauri/src/prompts/store.rs
+use super::template::PromptTemplate;
+use crate::app_dirs::file_lock;
+use crate::errors::AppError;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const LIBRARY_VERSION: u32 = 1;
+
+const DEFAULT_SUMMARY_PROMPT: &str = "Please provide a concise summary of this PDF document, \
+highlighting the main points and key insights.\n\n{{pdf_text}}";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PromptLibrary {
+    pub version: u32,
+    pub templates: Vec<PromptTemplate>,
+}
+
+/// Accepts either a whole exported library or a bare list of templates.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImportPayload {
+    Library(PromptLibrary),
+    Templates(Vec<PromptTemplate>),
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ImportSummary {
+    pub imported: Vec<String>,
+    pub replaced: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl Default for PromptLibrary {
+    fn default() -> Self {
+        let summary = PromptTemplate::new(
+            "PDF Summary".to_string(),
+            Some("Default prompt for PDF summarization".to_string()),
+            DEFAULT_SUMMARY_PROMPT.to_string(),
+        )
+        .expect("default template is valid");
+
+        PromptLibrary {
+            version: LIBRARY_VERSION,
+            templates: vec![summary],
+        }
+    }
+}
+
+impl PromptLibrary {
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            return Ok(PromptLibrary::default());
+        }
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Loads the library, applies `change` and saves the result while
+    /// holding the file's lock, so concurrent edits are not lost.
+    pub fn modify<T>(
+        path: &Path,
+        change: impl FnOnce(&mut Self) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let lock = file_lock(path);
+        let _guard = lock.lock().map_err(|_| {
+            AppError::TemplateError("Prompt library lock is unavailable".to_string())
+        })?;
+        let mut library = PromptLibrary::load(path)?;
+        let result = change(&mut library)?;
+        library.save(path)?;
+        Ok(result)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&PromptTemplate, AppError> {
+        self.position(name)
+            .map(|i| &self.templates[i])
+            .ok_or_else(|| AppError::TemplateError(format!("Template '{}' not found", name)))
+    }
+
+    pub fn create(&mut self, template: PromptTemplate) -> Result<&PromptTemplate, AppError> {
+        if self.position(&template.name).is_some() {
+            return Err(AppError::TemplateError(format!(
+                "A template named '{}' already exists",
+                template.name
+            )));
+        }
+        self.templates.push(template);
+        Ok(self.templates.last().expect("just pushed"))
+    }
+
+    pub fn update(
+        &mut self,
+        name: &str,
+        new_name: Option<String>,
+        description: Option<String>,
+        content: Option<String>,
+    ) -> Result<&PromptTemplate, AppError> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| AppError::TemplateError(format!("Template '{}' not found", name)))?;
+
+        if let Some(new_name) = &new_name {
+            if self
+                .position(new_name)
+                .is_some_and(|existing| existing != index)
+            {
+                return Err(AppError::TemplateError(format!(
+                    "A template named '{}' already exists",
+                    new_name.trim()
+                )));
+            }
+        }
+
+        let mut updated = self.templates[index].clone();
+        if let Some(new_name) = new_name {
+            updated.name = new_name;
+        }
+        if let Some(description) = description {
+            updated.description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(content) = content {
+            updated.content = content;
+        }
+        updated.validate()?;
+        updated.updated_at = Utc::now();
+
+        self.templates[index] = updated;
+        Ok(&self.templates[index])
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), AppError> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| AppError::TemplateError(format!("Template '{}' not found", name)))?;
+        self.templates.remove(index);
+        Ok(())
+    }
+
+    pub fn export_json(&self) -> Result<String, AppError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Merges templates from exported JSON. Templates whose name already
+    /// exists are replaced when `overwrite` is set and skipped otherwise.
+    pub fn import_json(&mut self, json: &str, overwrite: bool) -> Result<ImportSummary, AppError> {
+        let templates = match serde_json::from_str::<ImportPayload>(json) {
+            Ok(ImportPayload::Library(library)) => library.templates,
+            Ok(ImportPayload::Templates(templates)) => templates,
+            Err(e) => {
+                return Err(AppError::TemplateError(format!(
+                    "Invalid template JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        // Validate everything first so a bad entry does not leave a
+        // half-imported library behind.
+        let mut validated = Vec::with_capacity(templates.len());
+        for mut template in templates {
+            template.validate()?;
+            validated.push(template);
+        }
+
+        let mut summary = ImportSummary::default();
+        for template in validated {
+            match self.position(&template.name) {
+                Some(index) if overwrite => {
+                    summary.replaced.push(template.name.clone());
+                    self.templates[index] = template;
+                }
+                Some(_) => summary.skipped.push(template.name),
+                None => {
+                    summary.imported.push(template.name.clone());
+                    self.templates.push(template);
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.templates
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_changes_keep_every_template() {
+        let path = std::env::temp_dir().join(format!("prompt-library-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let template =
+                        PromptTemplate::new(format!("Template {}", i), None, "Hi".to_string())
+                            .unwrap();
+                    PromptLibrary::modify(&path, |library| library.create(template).map(|_| ()))
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let library = PromptLibrary::load(&path).unwrap();
+        // The default template plus one per thread.
+        assert_eq!(library.templates.len(), 9);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+// src-tauri/src/prompts/template.rs
+use crate::errors::AppError;
+use chrono::{DateTime, Local, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromptTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub variables: Vec<String>,
+    #[serde(rename = "createdAt", default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt", default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PromptTemplate {
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        content: String,
+    ) -> Result<Self, AppError> {
+        let now = Utc::now();
+        let mut template = PromptTemplate {
+            name,
+            description,
+            content,
+            variables: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Checks name and syntax and refreshes the cached variable list.
+    pub fn validate(&mut self) -> Result<(), AppError> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err(AppError::TemplateError(
+                "Template name cannot be empty".to_string(),
+            ));
+        }
+        if self.content.trim().is_empty() {
+            return Err(AppError::TemplateError(format!(
+                "Template '{}' has no content",
+                self.name
+            )));
+        }
+
+        let stripped = placeholder_regex().replace_all(&self.content, "");
+        if stripped.contains("{{") || stripped.contains("}}") {
+            return Err(AppError::TemplateError(format!(
+                "Template '{}' has a malformed placeholder; use {{{{name}}}} with letters, digits and underscores",
+                self.name
+            )));
+        }
+
+        self.variables = extract_variables(&self.content);
+        Ok(())
+    }
+
+    pub fn uses_variable(&self, name: &str) -> bool {
+        self.variables.iter().any(|v| v == name)
+    }
+
+    /// Substitutes every `{{variable}}`, failing with the full list of
+    /// variables that have no value. `{{date}}` defaults to today's date.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<String, AppError> {
+        let missing: Vec<&str> = self
+            .variables
+            .iter()
+            .filter(|v| !values.contains_key(v.as_str()) && v.as_str() != "date")
+            .map(|v| v.as_str())
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(AppError::TemplateError(format!(
+                "Template '{}' is missing values for: {}",
+                self.name,
+                missing.join(", ")
+            )));
+        }
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let rendered = placeholder_regex().replace_all(&self.content, |caps: &regex::Captures| {
+            let name = &caps[1];
+            match values.get(name) {
+                Some(value) => value.clone(),
+                None => today.clone(),
+            }
+        });
+
+        Ok(rendered.into_owned())
+    }
+}
+
+pub fn extract_variables(content: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for caps in placeholder_regex().captures_iter(content) {
+        let name = caps[1].to_string();
+        if !variables.contains(&name) {
+            variables.push(name);
+        }
+    }
+    variables
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(content: &str) -> Result<PromptTemplate, AppError> {
+        PromptTemplate::new(" Review ".to_string(), None, content.to_string())
+    }
+
+    #[test]
+    fn validate_collects_variables_once_in_order() {
+        let template = template("{{ topic }} for {{audience}}, again {{topic}}").unwrap();
+        assert_eq!(template.name, "Review");
+        assert_eq!(template.variables, ["topic", "audience"]);
+        assert!(template.uses_variable("audience"));
+    }
+
+    #[test]
+    fn validate_rejects_malformed_placeholders_and_empty_templates() {
+        assert!(template("Summarize {{pdf text}}").is_err());
+        assert!(template("Summarize {{pdf_text}").is_err());
+        assert!(template("   ").is_err());
+        assert!(PromptTemplate::new(" ".to_string(), None, "Hi".to_string()).is_err());
+    }
+
+    #[test]
+    fn render_lists_every_missing_variable() {
+        let template = template("{{topic}} for {{audience}} in {{tone}}").unwrap();
+        let values = HashMap::from([("audience".to_string(), "engineers".to_string())]);
+        let error = template.render(&values).unwrap_err().to_string();
+        assert!(error.contains("topic, tone"), "{}", error);
+    }
+
+    #[test]
+    fn render_defaults_date_to_today() {
+        let template = template("{{topic}} as of {{date}}").unwrap();
+        let values = HashMap::from([("topic".to_string(), "Sales".to_string())]);
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            template.render(&values).unwrap(),
+            format!("Sales as of {}", today)
+        );
+
+        let values = HashMap::from([
+            ("topic".to_string(), "Sales".to_string()),
+            ("date".to_string(), "Q3".to_string()),
+        ]);
+        assert_eq!(template.render(&values).unwrap(), "Sales as of Q3");
+    }
+}
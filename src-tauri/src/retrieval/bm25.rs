@@ -0,0 +1,123 @@
+// src-tauri/src/retrieval/bm25.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "in", "is",
+    "it", "its", "of", "on", "or", "that", "the", "this", "to", "was", "were", "what", "when",
+    "where", "which", "who", "why", "how", "with", "does", "do", "did",
+];
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Bm25Index {
+    doc_lengths: Vec<u32>,
+    avg_doc_length: f32,
+    /// term -> list of (document index, term frequency)
+    postings: HashMap<String, Vec<(usize, u32)>>,
+}
+
+impl Bm25Index {
+    pub fn build<'a>(documents: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut index = Bm25Index::default();
+
+        for (doc_id, document) in documents.into_iter().enumerate() {
+            let tokens = tokenize(document);
+            index.doc_lengths.push(tokens.len() as u32);
+
+            let mut frequencies: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *frequencies.entry(token).or_insert(0) += 1;
+            }
+            for (term, freq) in frequencies {
+                index.postings.entry(term).or_default().push((doc_id, freq));
+            }
+        }
+
+        let total: u32 = index.doc_lengths.iter().sum();
+        index.avg_doc_length = if index.doc_lengths.is_empty() {
+            0.0
+        } else {
+            total as f32 / index.doc_lengths.len() as f32
+        };
+
+        index
+    }
+
+    /// Returns up to `limit` (document index, score) pairs, best first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(usize, f32)> {
+        let doc_count = self.doc_lengths.len() as f32;
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+
+        for term in tokenize(query) {
+            let Some(postings) = self.postings.get(&term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+            for &(doc_id, freq) in postings {
+                let tf = freq as f32;
+                let length_ratio = self.doc_lengths[doc_id] as f32 / self.avg_doc_length.max(1.0);
+                let score = idf * (tf * (K1 + 1.0)) / (tf + K1 * (1.0 - B + B * length_ratio));
+                *scores.entry(doc_id).or_insert(0.0) += score;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .filter(|token| {
+            (token.chars().count() > 1 || token.chars().all(|c| c.is_numeric()))
+                && !STOP_WORDS.contains(&token.as_str())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_skip_stop_words_and_single_letters() {
+        assert_eq!(
+            tokenize("What is the Rate of 5 x-rays in 2024?"),
+            ["rate", "5", "rays", "2024"]
+        );
+    }
+
+    #[test]
+    fn rarer_and_denser_matches_rank_first() {
+        let index = Bm25Index::build([
+            "solar panels convert sunlight into power",
+            "wind turbines generate power from wind, more wind means more power",
+            "power prices rose this year",
+        ]);
+        let ranked: Vec<usize> = index
+            .search("wind power", 10)
+            .into_iter()
+            .map(|(doc, _)| doc)
+            .collect();
+        assert_eq!(ranked[0], 1);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(index.search("sunlight", 10)[0].0, 0);
+        assert_eq!(index.search("wind power", 1).len(), 1);
+        assert!(index.search("the of", 10).is_empty());
+    }
+
+    #[test]
+    fn empty_index_finds_nothing() {
+        let index = Bm25Index::build(std::iter::empty());
+        assert!(index.search("anything", 5).is_empty());
+    }
+}
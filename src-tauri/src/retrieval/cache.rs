@@ -0,0 +1,94 @@
+// src-tauri/src/retrieval/cache.rs
+use super::index::{DocumentIndex, SourceFingerprint};
+use crate::errors::AppError;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+
+/// Each index holds the full text of its pages, so only a few are kept.
+const CACHE_CAPACITY: usize = 8;
+
+struct CachedIndex {
+    password: String,
+    index: DocumentIndex,
+}
+
+/// Indexes of password-protected PDFs. They hold the decrypted text, so
+/// they live in memory for the session instead of on disk, and are only
+/// handed out to callers that supply the same password.
+#[derive(Default)]
+pub struct IndexCache {
+    entries: Mutex<VecDeque<CachedIndex>>,
+}
+
+impl IndexCache {
+    /// Removes and returns the index for `source`; put it back with `store`
+    /// once it is no longer being updated.
+    pub fn take(
+        &self,
+        source: &SourceFingerprint,
+        password: &str,
+    ) -> Result<Option<DocumentIndex>, AppError> {
+        let mut entries = lock(&self.entries)?;
+        let position = entries
+            .iter()
+            .position(|e| &e.index.source == source && e.password == password);
+        Ok(position
+            .and_then(|position| entries.remove(position))
+            .map(|entry| entry.index))
+    }
+
+    pub fn store(&self, index: DocumentIndex, password: String) -> Result<(), AppError> {
+        let mut entries = lock(&self.entries)?;
+        entries.retain(|e| e.index.source != index.source);
+        entries.push_front(CachedIndex { password, index });
+        entries.truncate(CACHE_CAPACITY);
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, AppError> {
+    mutex
+        .lock()
+        .map_err(|_| AppError::PdfError("PDF index cache is unavailable".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_for(path: &str) -> DocumentIndex {
+        let source = SourceFingerprint {
+            path: path.to_string(),
+            size: 1,
+            modified: 1,
+        };
+        DocumentIndex::build(source, &[(1, "secret text".to_string())])
+    }
+
+    #[test]
+    fn hands_out_indexes_only_for_the_same_password() {
+        let cache = IndexCache::default();
+        let index = index_for("/a.pdf");
+        let source = index.source.clone();
+        cache.store(index, "right".to_string()).unwrap();
+
+        assert!(cache.take(&source, "wrong").unwrap().is_none());
+        assert!(cache.take(&source, "right").unwrap().is_some());
+        assert!(cache.take(&source, "right").unwrap().is_none());
+    }
+
+    #[test]
+    fn evicts_the_least_recently_stored_index() {
+        let cache = IndexCache::default();
+        for i in 0..=CACHE_CAPACITY {
+            cache
+                .store(index_for(&format!("/{}.pdf", i)), "pw".to_string())
+                .unwrap();
+        }
+
+        let first = index_for("/0.pdf").source;
+        let last = index_for(&format!("/{}.pdf", CACHE_CAPACITY)).source;
+        assert!(cache.take(&first, "pw").unwrap().is_none());
+        assert!(cache.take(&last, "pw").unwrap().is_some());
+    }
+}
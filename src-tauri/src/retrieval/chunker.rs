@@ -0,0 +1,101 @@
+// src-tauri/src/retrieval/chunker.rs
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_CHUNK_CHARS: usize = 1000;
+pub const DEFAULT_CHUNK_OVERLAP: usize = 150;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TextChunk {
+    pub id: usize,
+    pub page: u32,
+    pub text: String,
+}
+
+/// Splits page texts into overlapping chunks that never cross a page
+/// boundary, so every chunk can be cited by a single page number.
+pub fn chunk_pages(pages: &[(u32, String)], max_chars: usize, overlap: usize) -> Vec<TextChunk> {
+    let mut chunks = Vec::new();
+
+    for (page, text) in pages {
+        for piece in split_text(text, max_chars, overlap) {
+            chunks.push(TextChunk {
+                id: chunks.len(),
+                page: *page,
+                text: piece,
+            });
+        }
+    }
+
+    chunks
+}
+
+fn split_text(text: &str, max_chars: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut pieces = Vec::new();
+    let mut start = 0;
+
+    while start < words.len() {
+        let mut end = start;
+        let mut len = 0;
+        while end < words.len() && (end == start || len + 1 + words[end].len() <= max_chars) {
+            len += words[end].len() + usize::from(end > start);
+            end += 1;
+        }
+
+        pieces.push(words[start..end].join(" "));
+
+        if end >= words.len() {
+            break;
+        }
+
+        // Step back far enough to carry roughly `overlap` characters into
+        // the next chunk, but always make progress.
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 && carried + words[next - 1].len() < overlap {
+            carried += words[next - 1].len() + 1;
+            next -= 1;
+        }
+        start = next;
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_respect_the_size_and_overlap() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let pieces = split_text(text, 20, 6);
+        assert_eq!(
+            pieces,
+            // "epsilon" is longer than the overlap, so it is not repeated.
+            ["alpha beta gamma", "gamma delta epsilon", "zeta eta theta"]
+        );
+        assert!(pieces.iter().all(|p| p.len() <= 20));
+    }
+
+    #[test]
+    fn long_words_still_make_progress() {
+        let pieces = split_text("abcdefghijklmnop qrstuvwxyz", 5, 100);
+        assert_eq!(pieces, ["abcdefghijklmnop", "qrstuvwxyz"]);
+    }
+
+    #[test]
+    fn chunks_never_cross_pages() {
+        let pages = vec![
+            (3, "one two three".to_string()),
+            (4, String::new()),
+            (5, "four".to_string()),
+        ];
+        let chunks = chunk_pages(&pages, DEFAULT_CHUNK_CHARS, DEFAULT_CHUNK_OVERLAP);
+        let summary: Vec<(usize, u32, &str)> = chunks
+            .iter()
+            .map(|c| (c.id, c.page, c.text.as_str()))
+            .collect();
+        assert_eq!(summary, [(0, 3, "one two three"), (1, 5, "four")]);
+    }
+}
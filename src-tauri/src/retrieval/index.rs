@@ -0,0 +1,264 @@
+// src-tauri/src/retrieval/index.rs
+use super::bm25::Bm25Index;
+use super::chunker::{chunk_pages, TextChunk, DEFAULT_CHUNK_CHARS, DEFAULT_CHUNK_OVERLAP};
+use crate::errors::AppError;
+use crate::pdf::pdf_page_range::PageSelection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bump when the chunking or on-disk layout changes so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 3;
+const RRF_K: f32 = 60.0;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SourceFingerprint {
+    pub path: String,
+    pub size: u64,
+    pub modified: u64,
+}
+
+impl SourceFingerprint {
+    pub fn for_file(file_path: &str) -> Result<Self, AppError> {
+        let path = std::fs::canonicalize(file_path)?;
+        let metadata = std::fs::metadata(&path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(SourceFingerprint {
+            path: path.to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified,
+        })
+    }
+
+    /// Keeps indexes of different page ranges of one file apart.
+    pub fn with_pages(mut self, pages: &PageSelection) -> Self {
+        if !pages.is_all() {
+            self.path = format!("{}#pages={}", self.path, pages);
+        }
+        self
+    }
+
+    /// Identifies text that is not backed by a file, such as the open
+    /// editor document.
+    pub fn for_text(text: &str) -> Self {
+        SourceFingerprint {
+            path: format!("memory:{:016x}", fnv1a64(text.as_bytes())),
+            size: text.len() as u64,
+            modified: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChunkEmbeddings {
+    pub model: String,
+    pub vectors: Vec<Vec<f32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DocumentIndex {
+    version: u32,
+    pub source: SourceFingerprint,
+    pub chunks: Vec<TextChunk>,
+    bm25: Bm25Index,
+    pub embeddings: Option<ChunkEmbeddings>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoredChunk {
+    pub chunk: TextChunk,
+    pub score: f32,
+}
+
+impl DocumentIndex {
+    pub fn build(source: SourceFingerprint, pages: &[(u32, String)]) -> Self {
+        let chunks = chunk_pages(pages, DEFAULT_CHUNK_CHARS, DEFAULT_CHUNK_OVERLAP);
+        let bm25 = Bm25Index::build(chunks.iter().map(|c| c.text.as_str()));
+
+        DocumentIndex {
+            version: INDEX_VERSION,
+            source,
+            chunks,
+            bm25,
+            embeddings: None,
+        }
+    }
+
+    /// Loads the index stored at `path` if it was built from the same
+    /// version of the source file.
+    pub fn load(path: &Path, source: &SourceFingerprint) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let index: DocumentIndex = serde_json::from_slice(&data).ok()?;
+        (index.version == INDEX_VERSION && &index.source == source).then_some(index)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let data = serde_json::to_vec(self)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn has_embeddings_for(&self, model: &str) -> bool {
+        self.embeddings
+            .as_ref()
+            .is_some_and(|e| e.model == model && e.vectors.len() == self.chunks.len())
+    }
+
+    /// Ranks chunks with BM25 and, when a query embedding is available,
+    /// fuses the lexical and vector rankings with reciprocal rank fusion.
+    pub fn search(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: usize,
+    ) -> Vec<ScoredChunk> {
+        let candidates = (limit * 4).max(20);
+        let lexical = self.bm25.search(query, candidates);
+
+        let semantic = match (query_embedding, &self.embeddings) {
+            (Some(query_vector), Some(embeddings)) => {
+                let mut ranked: Vec<(usize, f32)> = embeddings
+                    .vectors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (i, cosine_similarity(query_vector, v)))
+                    .collect();
+                ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+                ranked.truncate(candidates);
+                ranked
+            }
+            _ => Vec::new(),
+        };
+
+        let ranked = if semantic.is_empty() {
+            lexical
+        } else {
+            let mut fused: HashMap<usize, f32> = HashMap::new();
+            for ranking in [&lexical, &semantic] {
+                for (rank, (chunk_id, _)) in ranking.iter().enumerate() {
+                    *fused.entry(*chunk_id).or_insert(0.0) += 1.0 / (RRF_K + rank as f32 + 1.0);
+                }
+            }
+            let mut fused: Vec<(usize, f32)> = fused.into_iter().collect();
+            fused.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            fused
+        };
+
+        ranked
+            .into_iter()
+            .take(limit)
+            .filter_map(|(chunk_id, score)| {
+                self.chunks.get(chunk_id).map(|chunk| ScoredChunk {
+                    chunk: chunk.clone(),
+                    score,
+                })
+            })
+            .collect()
+    }
+}
+
+/// File name for the index of a given source, stable across runs.
+pub fn index_file_path(index_dir: &Path, source: &SourceFingerprint) -> PathBuf {
+    index_dir.join(format!("{:016x}.json", fnv1a64(source.path.as_bytes())))
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(modified: u64) -> SourceFingerprint {
+        SourceFingerprint {
+            path: "/docs/report.pdf".to_string(),
+            size: 100,
+            modified,
+        }
+    }
+
+    fn fruit_index() -> DocumentIndex {
+        let pages = vec![
+            (1, "apple banana".to_string()),
+            (2, "apple cherry".to_string()),
+            (3, "grape melon".to_string()),
+        ];
+        DocumentIndex::build(source(1), &pages)
+    }
+
+    fn pages_of(results: &[ScoredChunk]) -> Vec<u32> {
+        results.iter().map(|r| r.chunk.page).collect()
+    }
+
+    #[test]
+    fn keyword_search_without_embeddings() {
+        let index = fruit_index();
+        let results = index.search("apple", None, 5);
+        let mut pages = pages_of(&results);
+        pages.sort();
+        assert_eq!(pages, [1, 2]);
+    }
+
+    #[test]
+    fn fuses_keyword_and_vector_rankings() {
+        let mut index = fruit_index();
+        index.embeddings = Some(ChunkEmbeddings {
+            model: "test".to_string(),
+            vectors: vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.1, 1.0]],
+        });
+        assert!(index.has_embeddings_for("test"));
+        assert!(!index.has_embeddings_for("other"));
+
+        // Page 2 ranks high in both lists, page 1 only matches the keyword
+        // and page 3 is only close in vector space.
+        let results = index.search("apple", Some(&[0.0, 1.0]), 5);
+        assert_eq!(pages_of(&results), [2, 1, 3]);
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn load_rejects_indexes_of_a_changed_file_or_version() {
+        let dir = std::env::temp_dir().join(format!("pdf-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = index_file_path(&dir, &source(1));
+        fruit_index().save(&path).unwrap();
+
+        assert!(DocumentIndex::load(&path, &source(1)).is_some());
+        assert!(DocumentIndex::load(&path, &source(2)).is_none());
+
+        let mut stale: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        stale["version"] = serde_json::json!(INDEX_VERSION - 1);
+        std::fs::write(&path, serde_json::to_vec(&stale).unwrap()).unwrap();
+        assert!(DocumentIndex::load(&path, &source(1)).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,6 @@
+// src-tauri/src/retrieval/mod.rs
+pub mod bm25;
+pub mod cache;
+pub mod chunker;
+pub mod index;
+pub mod qa;
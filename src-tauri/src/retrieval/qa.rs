@@ -0,0 +1,127 @@
+// src-tauri/src/retrieval/qa.rs
+use super::index::ScoredChunk;
+use regex::Regex;
+
+pub const DEFAULT_TOP_K: usize = 5;
+pub const MAX_TOP_K: usize = 20;
+pub const CITATION_EXCERPT_CHARS: usize = 240;
+
+/// What a chunk's `page` number refers to, and how answers cite it.
+#[derive(Debug, Clone, Copy)]
+pub enum CitationUnit {
+    /// PDF pages, cited as `[p. N]`.
+    Page,
+    /// Sections of an editor document, cited as `[s. N]`.
+    Section,
+}
+
+impl CitationUnit {
+    fn name(self) -> &'static str {
+        match self {
+            CitationUnit::Page => "page",
+            CitationUnit::Section => "section",
+        }
+    }
+
+    fn abbreviation(self) -> &'static str {
+        match self {
+            CitationUnit::Page => "p",
+            CitationUnit::Section => "s",
+        }
+    }
+}
+
+pub fn build_question_prompt(
+    question: &str,
+    passages: &[ScoredChunk],
+    unit: CitationUnit,
+) -> String {
+    let mut prompt = format!(
+        "Answer the question using only the passages below. \
+         Cite the {} of every fact you use in the form [{}. N]. \
+         If the passages do not contain the answer, say that the document does not cover it.\n\n\
+         Passages:\n",
+        unit.name(),
+        unit.abbreviation()
+    );
+
+    for (i, passage) in passages.iter().enumerate() {
+        prompt.push_str(&format!(
+            "[{}] ({} {})\n{}\n\n",
+            i + 1,
+            unit.name(),
+            passage.chunk.page,
+            passage.chunk.text
+        ));
+    }
+
+    prompt.push_str(&format!("Question: {}", question.trim()));
+    prompt
+}
+
+/// Collects numbers cited as `[p. 3]`, `[p 3-4]` or `[pp. 3, 5]` (or the
+/// `s.` forms for sections), in order of first appearance.
+pub fn parse_citations(answer: &str, unit: CitationUnit) -> Vec<u32> {
+    let citation = Regex::new(&format!(
+        r"\[\s*{0}{0}?\.?\s*([0-9][0-9,\s\-–]*)\]",
+        unit.abbreviation()
+    ))
+    .unwrap();
+    let mut pages = Vec::new();
+
+    for caps in citation.captures_iter(answer) {
+        for part in caps[1].split(',') {
+            let bounds: Vec<u32> = part
+                .split(['-', '–'])
+                .filter_map(|n| n.trim().parse().ok())
+                .collect();
+            let range = match bounds.as_slice() {
+                [single] => *single..=*single,
+                [start, end] if start <= end && end - start < 50 => *start..=*end,
+                _ => continue,
+            };
+            for page in range {
+                if !pages.contains(&page) {
+                    pages.push(page);
+                }
+            }
+        }
+    }
+
+    pages
+}
+
+pub fn excerpt(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_pages_lists_and_ranges() {
+        let answer = "Revenue grew [p. 3], costs fell [pp. 5, 7] and margins held [p 9-11]. \
+                      Revenue again [p.3].";
+        assert_eq!(
+            parse_citations(answer, CitationUnit::Page),
+            [3, 5, 7, 9, 10, 11]
+        );
+    }
+
+    #[test]
+    fn ignores_other_units_and_implausible_ranges() {
+        let answer = "See [s. 2] and [p. 1-500], or [p. 8–6] and [p. 4–5].";
+        assert_eq!(parse_citations(answer, CitationUnit::Page), [4, 5]);
+        assert_eq!(parse_citations(answer, CitationUnit::Section), [2]);
+    }
+
+    #[test]
+    fn excerpts_are_cut_on_characters() {
+        assert_eq!(excerpt("héllo wörld", 5), "héllo...");
+        assert_eq!(excerpt("short", 10), "short");
+    }
+}
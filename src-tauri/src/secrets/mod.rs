@@ -0,0 +1,14 @@
+// src-tauri/src/secrets/mod.rs
+pub mod state;
+pub mod vault;
+
+use crate::app_dirs::app_data_file;
+use crate::errors::AppError;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const VAULT_FILE: &str = "secrets.vault";
+
+pub fn vault_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app_data_file(app, VAULT_FILE)
+}
@@ -0,0 +1,60 @@
+// src-tauri/src/secrets/state.rs
+use super::vault::UnlockedVault;
+use crate::errors::AppError;
+use std::sync::Mutex;
+
+/// Holds the unlocked vault for the lifetime of the app. The derived key
+/// never leaves the process and is dropped (and zeroed) on lock.
+#[derive(Default)]
+pub struct VaultState {
+    vault: Mutex<Option<UnlockedVault>>,
+}
+
+impl VaultState {
+    pub fn is_unlocked(&self) -> bool {
+        self.vault.lock().map(|v| v.is_some()).unwrap_or(false)
+    }
+
+    pub fn unlock(&self, vault: UnlockedVault) {
+        if let Ok(mut guard) = self.vault.lock() {
+            *guard = Some(vault);
+        }
+    }
+
+    pub fn lock(&self) {
+        if let Ok(mut guard) = self.vault.lock() {
+            *guard = None;
+        }
+    }
+
+    pub fn with_vault<T>(
+        &self,
+        f: impl FnOnce(&mut UnlockedVault) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut guard = self
+            .vault
+            .lock()
+            .map_err(|_| AppError::VaultError("Vault state is unavailable".to_string()))?;
+        match guard.as_mut() {
+            Some(vault) => f(vault),
+            None => Err(AppError::VaultError(
+                "The secret vault is locked. Unlock it in settings.".to_string(),
+            )),
+        }
+    }
+
+    /// Looks up an API key by name for an outgoing LLM request.
+    pub fn resolve_api_key(&self, name: &str) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::VaultError(
+                "Please select an API key in settings".to_string(),
+            ));
+        }
+        self.with_vault(|vault| {
+            vault.get(name).map(str::to_string).ok_or_else(|| {
+                AppError::VaultError(format!("No API key named '{}' in the vault", name))
+            })
+        })
+    }
+}
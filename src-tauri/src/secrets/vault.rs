@@ -0,0 +1,314 @@
+// src-tauri/src/secrets/vault.rs
+use crate::errors::AppError;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const MIN_PASSPHRASE_CHARS: usize = 8;
+const MAX_NAME_CHARS: usize = 64;
+
+// Argon2id parameters recommended by OWASP for interactive logins.
+const ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const ARGON2_ITERATIONS: u32 = 2;
+const ARGON2_PARALLELISM: u32 = 1;
+
+/// On-disk layout. Only the KDF parameters are in the clear; the secrets
+/// map is a single XChaCha20-Poly1305 ciphertext.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct KdfParams {
+    algorithm: String,
+    #[serde(rename = "memoryKib")]
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+pub struct UnlockedVault {
+    path: PathBuf,
+    kdf: KdfParams,
+    key: Zeroizing<[u8; KEY_LEN]>,
+    secrets: BTreeMap<String, Zeroizing<String>>,
+}
+
+impl UnlockedVault {
+    /// Creates an empty vault protected by `passphrase` and writes it to `path`.
+    pub fn create(path: &Path, passphrase: &str) -> Result<Self, AppError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(AppError::VaultError(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            )));
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let kdf = KdfParams {
+            algorithm: "argon2id".to_string(),
+            memory_kib: ARGON2_MEMORY_KIB,
+            iterations: ARGON2_ITERATIONS,
+            parallelism: ARGON2_PARALLELISM,
+            salt: BASE64.encode(salt),
+        };
+
+        let vault = UnlockedVault {
+            path: path.to_path_buf(),
+            key: derive_key(passphrase, &kdf)?,
+            kdf,
+            secrets: BTreeMap::new(),
+        };
+        vault.persist()?;
+        Ok(vault)
+    }
+
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, AppError> {
+        let data = std::fs::read_to_string(path)?;
+        let file: VaultFile = serde_json::from_str(&data)
+            .map_err(|e| AppError::VaultError(format!("Vault file is corrupted: {}", e)))?;
+
+        if file.version != VAULT_VERSION {
+            return Err(AppError::VaultError(format!(
+                "Unsupported vault version {}",
+                file.version
+            )));
+        }
+
+        let key = derive_key(passphrase, &file.kdf)?;
+        let nonce = decode_fixed::<NONCE_LEN>(&file.nonce, "nonce")?;
+        let ciphertext = BASE64
+            .decode(&file.ciphertext)
+            .map_err(|_| AppError::VaultError("Vault file is corrupted".to_string()))?;
+
+        let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    XNonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: &associated_data(&file.kdf),
+                    },
+                )
+                .map_err(|_| {
+                    AppError::VaultError(
+                        "Incorrect passphrase or the vault has been tampered with".to_string(),
+                    )
+                })?,
+        );
+
+        let secrets: BTreeMap<String, String> = serde_json::from_slice(&plaintext)
+            .map_err(|_| AppError::VaultError("Vault contents are corrupted".to_string()))?;
+
+        Ok(UnlockedVault {
+            path: path.to_path_buf(),
+            kdf: file.kdf,
+            key,
+            secrets: secrets
+                .into_iter()
+                .map(|(name, value)| (name, Zeroizing::new(value)))
+                .collect(),
+        })
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.secrets.keys().cloned().collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(|value| value.as_str())
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), AppError> {
+        let name = validate_name(name)?;
+        if value.trim().is_empty() {
+            return Err(AppError::VaultError(
+                "Secret value cannot be empty".to_string(),
+            ));
+        }
+        self.secrets
+            .insert(name, Zeroizing::new(value.trim().to_string()));
+        self.persist()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), AppError> {
+        if self.secrets.remove(name.trim()).is_none() {
+            return Err(AppError::VaultError(format!(
+                "No secret named '{}'",
+                name.trim()
+            )));
+        }
+        self.persist()
+    }
+
+    /// Re-encrypts the whole map under a fresh nonce and atomically
+    /// replaces the vault file.
+    fn persist(&self) -> Result<(), AppError> {
+        let plain: BTreeMap<&str, &str> = self
+            .secrets
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let plaintext = Zeroizing::new(serde_json::to_vec(&plain)?);
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &associated_data(&self.kdf),
+                },
+            )
+            .map_err(|_| AppError::VaultError("Failed to encrypt vault".to_string()))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf: self.kdf.clone(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+
+        let tmp_path = self.path.with_extension("vault.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(&file)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> Result<Zeroizing<[u8; KEY_LEN]>, AppError> {
+    if kdf.algorithm != "argon2id" {
+        return Err(AppError::VaultError(format!(
+            "Unsupported key derivation '{}'",
+            kdf.algorithm
+        )));
+    }
+
+    let salt = decode_fixed::<SALT_LEN>(&kdf.salt, "salt")?;
+    let params = Params::new(
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| AppError::VaultError(format!("Invalid key derivation parameters: {}", e)))?;
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| AppError::VaultError(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Binds the ciphertext to the KDF header so the parameters cannot be
+/// swapped without failing authentication.
+fn associated_data(kdf: &KdfParams) -> Vec<u8> {
+    format!(
+        "tiptaptoe-vault:{}:{}:{}:{}:{}:{}",
+        VAULT_VERSION, kdf.algorithm, kdf.memory_kib, kdf.iterations, kdf.parallelism, kdf.salt
+    )
+    .into_bytes()
+}
+
+fn decode_fixed<const N: usize>(value: &str, field: &str) -> Result<[u8; N], AppError> {
+    BASE64
+        .decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| AppError::VaultError(format!("Vault {} is corrupted", field)))
+}
+
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.'));
+
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(AppError::VaultError(format!(
+            "Secret names must be 1-{} letters, digits, spaces, '.', '_' or '-'",
+            MAX_NAME_CHARS
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    fn vault_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.vault", name, std::process::id()))
+    }
+
+    #[test]
+    fn secrets_survive_a_round_trip() {
+        let path = vault_path("round-trip");
+        let mut vault = UnlockedVault::create(&path, PASSPHRASE).unwrap();
+        vault.set("openai", "  sk-test  ").unwrap();
+        vault.set("claude", "sk-ant").unwrap();
+        vault.remove("claude").unwrap();
+
+        let reopened = UnlockedVault::open(&path, PASSPHRASE);
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let reopened = reopened.unwrap();
+        assert_eq!(reopened.names(), ["openai"]);
+        assert_eq!(reopened.get("openai"), Some("sk-test"));
+        assert!(!on_disk.contains("sk-test"));
+    }
+
+    #[test]
+    fn wrong_passphrase_and_tampering_are_rejected() {
+        let path = vault_path("tamper");
+        let mut vault = UnlockedVault::create(&path, PASSPHRASE).unwrap();
+        vault.set("openai", "sk-test").unwrap();
+        let wrong = UnlockedVault::open(&path, "not the passphrase at all");
+
+        // Edited KDF parameters must not go unnoticed.
+        let mut file: VaultFile =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        file.kdf.iterations += 1;
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+        let tampered = UnlockedVault::open(&path, PASSPHRASE);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(wrong, Err(AppError::VaultError(_))));
+        assert!(matches!(tampered, Err(AppError::VaultError(_))));
+    }
+
+    #[test]
+    fn short_passphrases_are_refused() {
+        let path = vault_path("short");
+        assert!(UnlockedVault::create(&path, "short").is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,121 @@
+// src-tauri/src/settings/migration.rs
+use super::models::{
+    AppSettings, DEFAULT_MAX_PDF_OPEN_BYTES, DEFAULT_MAX_PDF_SIZE_BYTES, SETTINGS_VERSION,
+};
+use crate::errors::AppError;
+use serde_json::{json, Value};
+
+/// Upgrades a settings document of any known version to the current
+/// schema. Returns the settings and whether anything was migrated.
+pub fn migrate(mut value: Value) -> Result<(AppSettings, bool), AppError> {
+    let original_version = version_of(&value)?;
+
+    if original_version > SETTINGS_VERSION {
+        return Err(AppError::SettingsError(format!(
+            "Settings file version {} is newer than this app supports ({})",
+            original_version, SETTINGS_VERSION
+        )));
+    }
+
+    let mut version = original_version;
+    while version < SETTINGS_VERSION {
+        value = match version {
+            1 => migrate_v1_to_v2(value),
+            2 => migrate_v2_to_v3(value),
+            _ => {
+                return Err(AppError::SettingsError(format!(
+                    "Settings file version {} is not supported",
+                    version
+                )))
+            }
+        };
+        version += 1;
+    }
+
+    let settings: AppSettings = serde_json::from_value(value)
+        .map_err(|e| AppError::SettingsError(format!("Invalid settings file: {}", e)))?;
+    Ok((settings, original_version != SETTINGS_VERSION))
+}
+
+/// Every settings file the backend writes carries its version, starting
+/// at 1 with the provider profiles.
+pub fn version_of(value: &Value) -> Result<u32, AppError> {
+    let version = value
+        .get("version")
+        .ok_or_else(|| AppError::SettingsError("Settings file has no version".to_string()))?;
+    version
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| {
+            AppError::SettingsError(format!("Invalid settings file version: {}", version))
+        })
+}
+
+/// v1 -> v2: the PDF size limit moves from code into settings.
+fn migrate_v1_to_v2(mut v1: Value) -> Value {
+    if let Some(settings) = v1.as_object_mut() {
+        settings.insert("version".to_string(), json!(2));
+        settings
+            .entry("maxPdfSizeBytes")
+            .or_insert_with(|| json!(DEFAULT_MAX_PDF_SIZE_BYTES));
+    }
+    v1
+}
+
+/// v2 -> v3: adds the size limit for opening PDFs at all.
+fn migrate_v2_to_v3(mut v2: Value) -> Value {
+    if let Some(settings) = v2.as_object_mut() {
+        settings.insert("version".to_string(), json!(3));
+        settings
+            .entry("maxPdfOpenBytes")
+            .or_insert_with(|| json!(DEFAULT_MAX_PDF_OPEN_BYTES));
+    }
+    v2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_the_pdf_limits_to_v1_settings() {
+        let mut v1 = serde_json::to_value(AppSettings::default()).unwrap();
+        let settings = v1.as_object_mut().unwrap();
+        settings.insert("version".to_string(), json!(1));
+        settings.remove("maxPdfSizeBytes");
+        settings.remove("maxPdfOpenBytes");
+
+        let (settings, migrated) = migrate(v1).unwrap();
+        assert!(migrated);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.profiles.len(), 2);
+        assert_eq!(settings.max_pdf_size_bytes, DEFAULT_MAX_PDF_SIZE_BYTES);
+        assert_eq!(settings.max_pdf_open_bytes, DEFAULT_MAX_PDF_OPEN_BYTES);
+    }
+
+    #[test]
+    fn leaves_current_settings_unchanged() {
+        let current = serde_json::to_value(AppSettings::default()).unwrap();
+        let (settings, migrated) = migrate(current).unwrap();
+        assert!(!migrated);
+        assert_eq!(settings.profiles.len(), 2);
+    }
+
+    #[test]
+    fn rejects_versions_without_a_migration() {
+        for version in [
+            json!(0),
+            json!(SETTINGS_VERSION + 1),
+            json!(u64::from(u32::MAX) + 2),
+        ] {
+            let result = migrate(json!({ "version": version }));
+            assert!(
+                matches!(result, Err(AppError::SettingsError(_))),
+                "{}",
+                version
+            );
+        }
+        assert!(migrate(json!({ "version": "2" })).is_err());
+        assert!(migrate(json!({ "activeProfile": "OpenAI" })).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+// src-tauri/src/settings/mod.rs
+pub mod migration;
+pub mod models;
+pub mod store;
+
+use crate::app_dirs::app_config_file;
+use crate::errors::AppError;
+use crate::llm::factory::get_llm_client;
+use crate::llm::LlmClient;
+use crate::secrets::state::VaultState;
+use models::{AppSettings, ProviderProfile};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+pub fn settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app_config_file(app, SETTINGS_FILE)
+}
+
+pub fn load_settings(app: &AppHandle) -> Result<AppSettings, AppError> {
+    AppSettings::load(&settings_path(app)?)
+}
+
+/// Builds an LLM client from a named profile (or the active one),
+/// resolving its API key from the vault.
+pub fn client_for_profile(
+    app: &AppHandle,
+    vault: &VaultState,
+    profile_name: Option<&str>,
+) -> Result<(ProviderProfile, LlmClient), AppError> {
+    let settings = load_settings(app)?;
+    let profile = settings.profile(profile_name)?.clone();
+
+    let api_key = match &profile.api_key_name {
+        Some(name) if !name.trim().is_empty() => vault.resolve_api_key(name)?,
+        _ if profile.is_local() => String::new(),
+        _ => {
+            return Err(AppError::SettingsError(format!(
+                "Profile '{}' has no API key. Choose one in settings.",
+                profile.name
+            )))
+        }
+    };
+
+    let client = get_llm_client(
+        api_key,
+        Some(profile.base_url.clone()),
+        Some(profile.model.clone()),
+        Some(profile.max_tokens),
+        Some(profile.temperature),
+        Some(profile.timeout),
+    )?;
+
+    Ok((profile, client))
+}
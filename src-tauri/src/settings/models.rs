@@ -0,0 +1,90 @@
+// src-tauri/src/settings/models.rs
+use serde::{Deserialize, Serialize};
+
+pub const SETTINGS_VERSION: u32 = 3;
+
+/// 10 MiB, the limit every PDF command used before it was configurable.
+pub const DEFAULT_MAX_PDF_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 256 MiB. Opening a PDF takes a few times its size in memory, so this
+/// keeps large manuals under a gigabyte or so.
+pub const DEFAULT_MAX_PDF_OPEN_BYTES: u64 = 256 * 1024 * 1024;
+
+pub const DEFAULT_SUMMARY_PROMPT: &str = "Please provide a concise summary of this PDF document, highlighting the main points and key insights.";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProviderProfile {
+    pub name: String,
+    #[serde(rename = "baseUrl")]
+    pub base_url: String,
+    pub model: String,
+    /// Name of the API key in the secret vault; local servers need none.
+    #[serde(rename = "apiKeyName", default)]
+    pub api_key_name: Option<String>,
+    #[serde(rename = "maxTokens")]
+    pub max_tokens: u32,
+    pub temperature: f32,
+    pub timeout: u64,
+    #[serde(rename = "embeddingModel", default)]
+    pub embedding_model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppSettings {
+    pub version: u32,
+    #[serde(rename = "activeProfile")]
+    pub active_profile: String,
+    pub profiles: Vec<ProviderProfile>,
+    #[serde(rename = "summaryPrompt")]
+    pub summary_prompt: String,
+    /// Largest PDF the PDF commands accept for whole-document work, in
+    /// bytes. Requests for a page range may exceed it.
+    #[serde(rename = "maxPdfSizeBytes")]
+    pub max_pdf_size_bytes: u64,
+    /// Largest PDF file any PDF command opens, in bytes. Documents are
+    /// always parsed whole, so this holds for page ranges too and is the
+    /// bound on the memory a PDF command uses.
+    #[serde(rename = "maxPdfOpenBytes")]
+    pub max_pdf_open_bytes: u64,
+}
+
+impl ProviderProfile {
+    /// Local OpenAI-compatible servers (Ollama, LM Studio) accept any key.
+    pub fn is_local(&self) -> bool {
+        self.base_url.contains("localhost") || self.base_url.contains("127.0.0.1")
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            version: SETTINGS_VERSION,
+            active_profile: "OpenAI".to_string(),
+            profiles: vec![
+                ProviderProfile {
+                    name: "OpenAI".to_string(),
+                    base_url: "https://api.openai.com/v1".to_string(),
+                    model: "gpt-3.5-turbo".to_string(),
+                    api_key_name: Some("openai".to_string()),
+                    max_tokens: 500,
+                    temperature: 0.7,
+                    timeout: 120,
+                    embedding_model: None,
+                },
+                ProviderProfile {
+                    name: "Local Ollama".to_string(),
+                    base_url: "http://localhost:11434/v1".to_string(),
+                    model: "llama3".to_string(),
+                    api_key_name: None,
+                    max_tokens: 1024,
+                    temperature: 0.7,
+                    timeout: 300,
+                    embedding_model: Some("nomic-embed-text".to_string()),
+                },
+            ],
+            summary_prompt: DEFAULT_SUMMARY_PROMPT.to_string(),
+            max_pdf_size_bytes: DEFAULT_MAX_PDF_SIZE_BYTES,
+            max_pdf_open_bytes: DEFAULT_MAX_PDF_OPEN_BYTES,
+        }
+    }
+}
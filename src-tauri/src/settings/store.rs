@@ -0,0 +1,180 @@
+// src-tauri/src/settings/store.rs
+use super::migration::{migrate, version_of};
+use super::models::{AppSettings, ProviderProfile};
+use crate::errors::AppError;
+use std::path::Path;
+
+impl AppSettings {
+    /// Loads settings from `path`, migrating older schemas in place. The
+    /// pre-migration file is kept next to it as `<name>.v<version>.bak`.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            return Ok(AppSettings::default());
+        }
+
+        let data = std::fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_json::from_str(&data)
+            .map_err(|e| AppError::SettingsError(format!("Invalid settings file: {}", e)))?;
+        let previous_version = version_of(&value)?;
+
+        let (settings, migrated) = migrate(value)?;
+        settings.validate()?;
+
+        if migrated {
+            std::fs::copy(
+                path,
+                path.with_extension(format!("v{}.bak", previous_version)),
+            )?;
+            settings.save(path)?;
+        }
+
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn profile(&self, name: Option<&str>) -> Result<&ProviderProfile, AppError> {
+        let name = name.unwrap_or(&self.active_profile);
+        self.position(name)
+            .map(|i| &self.profiles[i])
+            .ok_or_else(|| AppError::SettingsError(format!("Profile '{}' not found", name)))
+    }
+
+    /// Inserts or replaces a profile. Passing `original_name` renames an
+    /// existing profile and keeps the active selection pointing at it.
+    pub fn upsert_profile(
+        &mut self,
+        mut profile: ProviderProfile,
+        original_name: Option<&str>,
+    ) -> Result<(), AppError> {
+        profile.name = profile.name.trim().to_string();
+        validate_profile(&profile)?;
+
+        let target = original_name.unwrap_or(&profile.name);
+        let existing = self.position(target);
+
+        if let Some(other) = self.position(&profile.name) {
+            if Some(other) != existing {
+                return Err(AppError::SettingsError(format!(
+                    "A profile named '{}' already exists",
+                    profile.name
+                )));
+            }
+        }
+
+        match existing {
+            Some(index) => {
+                if self.profiles[index].name == self.active_profile {
+                    self.active_profile = profile.name.clone();
+                }
+                self.profiles[index] = profile;
+            }
+            None if original_name.is_some() => {
+                return Err(AppError::SettingsError(format!(
+                    "Profile '{}' not found",
+                    target
+                )));
+            }
+            None => self.profiles.push(profile),
+        }
+
+        Ok(())
+    }
+
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), AppError> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| AppError::SettingsError(format!("Profile '{}' not found", name)))?;
+
+        if self.profiles.len() == 1 {
+            return Err(AppError::SettingsError(
+                "Cannot delete the only profile".to_string(),
+            ));
+        }
+
+        let removed = self.profiles.remove(index);
+        if removed.name == self.active_profile {
+            self.active_profile = self.profiles[0].name.clone();
+        }
+        Ok(())
+    }
+
+    pub fn set_active_profile(&mut self, name: &str) -> Result<(), AppError> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| AppError::SettingsError(format!("Profile '{}' not found", name)))?;
+        self.active_profile = self.profiles[index].name.clone();
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        if self.profiles.is_empty() {
+            return Err(AppError::SettingsError(
+                "Settings must contain at least one profile".to_string(),
+            ));
+        }
+        for profile in &self.profiles {
+            validate_profile(profile)?;
+        }
+        self.profile(None)?;
+        if self.max_pdf_size_bytes == 0 {
+            return Err(AppError::SettingsError(
+                "The PDF size limit must be positive".to_string(),
+            ));
+        }
+        if self.max_pdf_open_bytes == 0 {
+            return Err(AppError::SettingsError(
+                "The PDF open size limit must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.profiles.iter().position(|p| p.name == name.trim())
+    }
+}
+
+fn validate_profile(profile: &ProviderProfile) -> Result<(), AppError> {
+    if profile.name.trim().is_empty() {
+        return Err(AppError::SettingsError(
+            "Profile name cannot be empty".to_string(),
+        ));
+    }
+    if !profile.base_url.starts_with("http://") && !profile.base_url.starts_with("https://") {
+        return Err(AppError::SettingsError(format!(
+            "Profile '{}' needs an http(s) base URL",
+            profile.name
+        )));
+    }
+    if profile.model.trim().is_empty() {
+        return Err(AppError::SettingsError(format!(
+            "Profile '{}' needs a model",
+            profile.name
+        )));
+    }
+    if profile.max_tokens == 0 {
+        return Err(AppError::SettingsError(format!(
+            "Profile '{}' needs a positive max token count",
+            profile.name
+        )));
+    }
+    if !(0.0..=2.0).contains(&profile.temperature) {
+        return Err(AppError::SettingsError(format!(
+            "Profile '{}' temperature must be between 0 and 2",
+            profile.name
+        )));
+    }
+    if profile.timeout == 0 {
+        return Err(AppError::SettingsError(format!(
+            "Profile '{}' needs a positive timeout",
+            profile.name
+        )));
+    }
+    Ok(())
+}
@@ -7,6 +7,7 @@ import { CommentsPanel } from "./components/CommentsPanel";
 import { FileContextProvider } from "./contexts/FileContextProvider";
 import { invoke } from "@tauri-apps/api/core";
 import { migrateOldSettings } from "./utils/settingsStorage";
+import { LegacyKeyPrompt } from "./components/settings/LegacyKeyPrompt";
 import { marked } from "marked";
 import { Button } from "./components/ui";
 import { Comment } from "./types/comments";
@@ -20,11 +21,13 @@ function App() {
   const [content, setContent] = useState("");
   const [savedMessage, setSavedMessage] = useState("");
   const [comments, setComments] = useState<Comment[]>([]);
+  // An API key from an earlier version that still needs the vault passphrase.
+  const [legacyKeyPending, setLegacyKeyPending] = useState(false);
   const editorRef = useRef<any>(null);
 
   // Migrate old settings on app start
   useEffect(() => {
-    migrateOldSettings();
+    migrateOldSettings().then(setLegacyKeyPending);
     // Comments will be loaded when the editor is ready through the ChatPlugin
   }, []);
 
@@ -254,6 +257,11 @@ function App() {
             </div>
         </header>
 
+        <LegacyKeyPrompt
+          isOpen={legacyKeyPending}
+          onClose={() => setLegacyKeyPending(false)}
+        />
+
         {/* Content section */}
         <div className="app-content">
           {savedMessage && (
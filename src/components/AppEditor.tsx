@@ -14,6 +14,7 @@ import Subscript from '@tiptap/extension-subscript';
 import Superscript from '@tiptap/extension-superscript';
 import Underline from '@tiptap/extension-underline';
 import Link from '@tiptap/extension-link';
+import { PageBreak } from '../editor/pageBreak';
 
 // Plugins and utilities
 import { createChatPlugin } from '../plugins/ChatPlugin';
@@ -57,7 +58,8 @@ export const AppEditor = React.forwardRef<any, AppEditorProps>(
         TaskList,
         TaskItem.configure({ nested: true }),
         Highlight.configure({ multicolor: true }),
-        Image,
+        Image.configure({ allowBase64: true }),
+        PageBreak,
         Typography,
         Superscript,
         Subscript,
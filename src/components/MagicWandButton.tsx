@@ -37,7 +37,7 @@ export const MagicWandButton: React.FC<MagicWandButtonProps> = ({
     
     try {
       // Step 1: Load and validate AI settings
-      const settings = loadAISettings();
+      const settings = await loadAISettings();
       const validation = validateAISettings(settings);
       
       if (!validation.isValid) {
@@ -80,12 +80,7 @@ REVISED DOCUMENT:`;
       const response = await invoke<any>('process_ai_chat', {
         chatRequest: {
           prompt: revisionPrompt,
-          apiKey: settings.apiKey,
-          baseUrl: settings.baseUrl,
-          model: settings.model,
-          maxTokens: settings.maxTokens,
-          temperature: settings.temperature,
-          timeout: settings.timeout
+          profile: settings.profile
         }
       });
 
@@ -0,0 +1 @@
+export { LegacyKeyPrompt } from '@tiptaptoe/ai-react';
\ No newline at end of file
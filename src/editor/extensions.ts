@@ -10,6 +10,7 @@ import Subscript from '@tiptap/extension-subscript';
 import Superscript from '@tiptap/extension-superscript';
 import Underline from '@tiptap/extension-underline';
 import Link from '@tiptap/extension-link';
+import { PageBreak } from './pageBreak';
 
 export const editorExtensions = [
   StarterKit,
@@ -18,7 +19,9 @@ export const editorExtensions = [
   TaskList,
   TaskItem.configure({ nested: true }),
   Highlight.configure({ multicolor: true }),
-  Image,
+  // Converted PDFs carry their images as data URIs.
+  Image.configure({ allowBase64: true }),
+  PageBreak,
   Typography,
   Superscript,
   Subscript,
@@ -0,0 +1,33 @@
+// src/editor/pageBreak.ts
+import { Node, mergeAttributes } from '@tiptap/core';
+
+/**
+ * A page break from a converted PDF, `<hr data-page-break data-page="N">`.
+ * The horizontal rule would drop the attributes, and with them the breaks
+ * the PDF export starts new pages at.
+ */
+export const PageBreak = Node.create({
+  name: 'pageBreak',
+  group: 'block',
+  atom: true,
+  selectable: true,
+
+  addAttributes() {
+    return {
+      page: {
+        default: null,
+        parseHTML: element => element.getAttribute('data-page'),
+        renderHTML: attributes => (attributes.page ? { 'data-page': attributes.page } : {}),
+      },
+    };
+  },
+
+  parseHTML() {
+    // Ahead of the horizontal rule, which also matches `hr`.
+    return [{ tag: 'hr[data-page-break]', priority: 60 }];
+  },
+
+  renderHTML({ HTMLAttributes }) {
+    return ['hr', mergeAttributes({ 'data-page-break': 'true' }, HTMLAttributes)];
+  },
+});